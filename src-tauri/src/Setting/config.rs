@@ -0,0 +1,593 @@
+/*
+RTLauncher, a third-party Minecraft launcher built with the newest
+technology and provides innovative funtionalities
+Copyright (C) 2025 lutouna
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+// ***
+// 启动器设置：默认值、校验、迁移与持久化
+// ***
+
+use crate::module::instance::ins_main::{Instance, InstanceStore};
+use crate::utils::error::{LauncherError, LauncherResult};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::{OnceLock, RwLock};
+
+// 当前设置文件版本
+pub const SCHEMA_VERSION: u32 = 1;
+
+// 设置文件名
+const SETTINGS_FILE: &str = "settings.json";
+
+// 可以通过环境变量指定设置目录，便于脚本和测试使用
+const CONFIG_DIR_ENV: &str = "RTL_CONFIG_DIR";
+const CACHE_DIR_ENV: &str = "RTL_CACHE_DIR";
+pub const CURSEFORGE_KEY_ENV: &str = "RTL_CURSEFORGE_API_KEY";
+
+// 下载镜像源
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Mirror {
+    #[default]
+    Official,
+    Bmclapi,
+}
+
+// BMCLAPI 镜像地址
+const BMCLAPI: &str = "https://bmclapi2.bangbang93.com";
+
+impl Mirror {
+    // 将官方地址替换为镜像地址
+    pub fn rewrite(&self, url: &str) -> String {
+        match self {
+            Mirror::Official => url.to_string(),
+            Mirror::Bmclapi => {
+                let rules = [
+                    ("https://piston-meta.mojang.com", BMCLAPI.to_string()),
+                    ("https://piston-data.mojang.com", BMCLAPI.to_string()),
+                    ("https://launchermeta.mojang.com", BMCLAPI.to_string()),
+                    ("https://launcher.mojang.com", BMCLAPI.to_string()),
+                    (
+                        "https://resources.download.minecraft.net",
+                        format!("{}/assets", BMCLAPI),
+                    ),
+                    (
+                        "https://libraries.minecraft.net",
+                        format!("{}/maven", BMCLAPI),
+                    ),
+                    ("https://maven.fabricmc.net", format!("{}/maven", BMCLAPI)),
+                    (
+                        "https://meta.fabricmc.net",
+                        format!("{}/fabric-meta", BMCLAPI),
+                    ),
+                    (
+                        "https://maven.minecraftforge.net",
+                        format!("{}/maven", BMCLAPI),
+                    ),
+                ];
+                for (official, mirror) in rules {
+                    if let Some(rest) = url.strip_prefix(official) {
+                        return format!("{}{}", mirror, rest);
+                    }
+                }
+                url.to_string()
+            }
+        }
+    }
+
+    // 镜像提供的 Maven 仓库，作为库文件最后的回退
+    pub fn maven_repository(&self) -> Option<String> {
+        match self {
+            Mirror::Official => None,
+            Mirror::Bmclapi => Some(format!("{}/maven/", BMCLAPI)),
+        }
+    }
+}
+
+// 游戏目录设置
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GameSettings {
+    pub game_dir: Option<String>, // 为空时使用系统默认 .minecraft
+}
+
+// Java 设置
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JavaSettings {
+    pub java_path: Option<String>,
+    pub java_version: Option<String>,
+}
+
+// 内存设置（MB）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MemorySettings {
+    pub min_mb: u32,
+    pub max_mb: u32,
+}
+
+impl Default for MemorySettings {
+    fn default() -> Self {
+        Self {
+            min_mb: 1024,
+            max_mb: 4096,
+        }
+    }
+}
+
+// 下载设置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DownloadSettings {
+    pub mirror: Mirror,
+    pub library_concurrency: usize,
+    pub asset_concurrency: usize,
+    pub max_retries: u32,
+}
+
+impl Default for DownloadSettings {
+    fn default() -> Self {
+        Self {
+            mirror: Mirror::Official,
+            library_concurrency: 50,
+            asset_concurrency: 250,
+            max_retries: 3,
+        }
+    }
+}
+
+// 资源下载设置
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ContentSettings {
+    // CurseForge API key，可配置多个轮换使用
+    pub curseforge_api_keys: Vec<String>,
+}
+
+impl ContentSettings {
+    // 设置中的 key 优先，其次是环境变量（逗号分隔）
+    pub fn curseforge_keys(&self) -> Vec<String> {
+        let mut keys: Vec<String> = self
+            .curseforge_api_keys
+            .iter()
+            .map(|key| key.trim().to_string())
+            .filter(|key| !key.is_empty())
+            .collect();
+        if let Ok(env) = std::env::var(CURSEFORGE_KEY_ENV) {
+            keys.extend(
+                env.split(',')
+                    .map(|key| key.trim().to_string())
+                    .filter(|key| !key.is_empty()),
+            );
+        }
+        keys.dedup();
+        keys
+    }
+}
+
+// 启动器设置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LauncherSettings {
+    pub schema_version: u32,
+    pub language: String,
+    pub game: GameSettings,
+    pub java: JavaSettings,
+    pub memory: MemorySettings,
+    pub jvm_args: Vec<String>,
+    pub download: DownloadSettings,
+    pub content: ContentSettings,
+}
+
+impl Default for LauncherSettings {
+    fn default() -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            language: "zh-CN".to_string(),
+            game: GameSettings::default(),
+            java: JavaSettings::default(),
+            memory: MemorySettings::default(),
+            jvm_args: Vec::new(),
+            download: DownloadSettings::default(),
+            content: ContentSettings::default(),
+        }
+    }
+}
+
+// 校验错误
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SettingsError {
+    pub field: String,
+    pub message: String,
+}
+
+impl SettingsError {
+    fn new(field: &str, message: &str) -> Self {
+        Self {
+            field: field.to_string(),
+            message: message.to_string(),
+        }
+    }
+}
+
+impl LauncherSettings {
+    // 游戏根目录
+    pub fn game_dir(&self) -> PathBuf {
+        match &self.game.game_dir {
+            Some(dir) if !dir.trim().is_empty() => PathBuf::from(dir),
+            _ => default_game_dir(),
+        }
+    }
+
+    // 内存与自定义 JVM 参数
+    pub fn jvm_args_string(&self) -> String {
+        let mut args = vec![
+            format!("-Xms{}M", self.memory.min_mb),
+            format!("-Xmx{}M", self.memory.max_mb),
+        ];
+        args.extend(self.jvm_args.iter().cloned());
+        args.join(" ")
+    }
+
+    // 实例设置覆盖全局设置
+    pub fn for_instance(&self, instance: &Instance) -> LauncherResult<LauncherSettings> {
+        let mut settings = self.clone();
+        if let Some(java_path) = &instance.java_path {
+            settings.java.java_path = Some(java_path.clone());
+        }
+        if let Some(java_version) = &instance.java_version {
+            settings.java.java_version = Some(java_version.clone());
+        }
+        if let Some(min) = instance.min_memory_mb {
+            settings.memory.min_mb = min;
+        }
+        if let Some(max) = instance.max_memory_mb {
+            settings.memory.max_mb = max;
+        }
+        if !instance.jvm_args.is_empty() {
+            settings.jvm_args = instance.jvm_args.clone();
+        }
+        // 实例覆盖后可能出现最小内存大于最大内存，需要重新校验
+        settings
+            .validate()
+            .map_err(LauncherError::InvalidSettings)?;
+        Ok(settings)
+    }
+
+    // 校验设置，返回所有错误
+    pub fn validate(&self) -> Result<(), Vec<SettingsError>> {
+        let mut errors = Vec::new();
+
+        if self.memory.max_mb < 512 {
+            errors.push(SettingsError::new(
+                "memory.max_mb",
+                "最大内存不能小于 512 MB",
+            ));
+        }
+        if self.memory.min_mb > self.memory.max_mb {
+            errors.push(SettingsError::new(
+                "memory.min_mb",
+                "最小内存不能大于最大内存",
+            ));
+        }
+        if !(1..=512).contains(&self.download.library_concurrency) {
+            errors.push(SettingsError::new(
+                "download.library_concurrency",
+                "并发数必须在 1 到 512 之间",
+            ));
+        }
+        if !(1..=512).contains(&self.download.asset_concurrency) {
+            errors.push(SettingsError::new(
+                "download.asset_concurrency",
+                "并发数必须在 1 到 512 之间",
+            ));
+        }
+        if !(1..=10).contains(&self.download.max_retries) {
+            errors.push(SettingsError::new(
+                "download.max_retries",
+                "重试次数必须在 1 到 10 之间",
+            ));
+        }
+        if let Some(dir) = &self.game.game_dir {
+            if !dir.trim().is_empty() && !Path::new(dir).is_absolute() {
+                errors.push(SettingsError::new(
+                    "game.game_dir",
+                    "游戏目录必须是绝对路径",
+                ));
+            }
+        }
+        if let Some(java_path) = &self.java.java_path {
+            if !Path::new(java_path).exists() {
+                errors.push(SettingsError::new("java.java_path", "Java 路径不存在"));
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+// 系统默认的 .minecraft 目录
+fn default_game_dir() -> PathBuf {
+    let home = dirs::home_dir().unwrap_or_default();
+    match std::env::consts::OS {
+        "windows" => dirs::data_dir().unwrap_or(home).join(".minecraft"),
+        "macos" => dirs::data_dir().unwrap_or(home).join("minecraft"),
+        _ => home.join(".minecraft"),
+    }
+}
+
+// 设置文件所在目录
+pub fn config_dir() -> PathBuf {
+    if let Ok(dir) = std::env::var(CONFIG_DIR_ENV) {
+        return PathBuf::from(dir);
+    }
+    dirs::config_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("RTLauncher")
+}
+
+// 缓存目录，存放版本清单等可重新下载的数据
+pub fn cache_dir() -> PathBuf {
+    if let Ok(dir) = std::env::var(CACHE_DIR_ENV) {
+        return PathBuf::from(dir);
+    }
+    dirs::cache_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("RTLauncher")
+}
+
+// 将旧版本的设置迁移到当前版本
+pub fn migrate(mut value: serde_json::Value) -> LauncherResult<serde_json::Value> {
+    let mut version = value
+        .get("schema_version")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0) as u32;
+    if version > SCHEMA_VERSION {
+        return Err(LauncherError::Unsupported(format!(
+            "设置文件版本 {} 高于当前支持的版本 {}",
+            version, SCHEMA_VERSION
+        )));
+    }
+
+    while version < SCHEMA_VERSION {
+        value = match version {
+            0 => migrate_v0_to_v1(value),
+            _ => {
+                return Err(LauncherError::Unsupported(format!(
+                    "缺少设置版本 {} 的迁移",
+                    version
+                )))
+            }
+        };
+        version += 1;
+        value["schema_version"] = serde_json::json!(version);
+    }
+    Ok(value)
+}
+
+// v0 为前端直接保存的扁平结构，如 { "startup_parameter": "-Xms1G -Xmx4G", "game_dir": "..." }
+fn migrate_v0_to_v1(value: serde_json::Value) -> serde_json::Value {
+    let mut settings = serde_json::to_value(LauncherSettings::default()).unwrap_or_default();
+    let mut jvm_args = Vec::new();
+
+    if let Some(parameter) = value.get("startup_parameter").and_then(|v| v.as_str()) {
+        for arg in parameter.split_whitespace() {
+            if let Some(size) = arg.strip_prefix("-Xms").and_then(parse_memory_mb) {
+                settings["memory"]["min_mb"] = serde_json::json!(size);
+            } else if let Some(size) = arg.strip_prefix("-Xmx").and_then(parse_memory_mb) {
+                settings["memory"]["max_mb"] = serde_json::json!(size);
+            } else {
+                jvm_args.push(arg.to_string());
+            }
+        }
+    }
+    settings["jvm_args"] = serde_json::json!(jvm_args);
+
+    if let Some(dir) = value.get("game_dir").and_then(|v| v.as_str()) {
+        settings["game"]["game_dir"] = serde_json::json!(dir);
+    }
+    if let Some(java_path) = value.get("java_path").and_then(|v| v.as_str()) {
+        settings["java"]["java_path"] = serde_json::json!(java_path);
+    }
+    if let Some(concurrency) = value.get("concurrency").and_then(|v| v.as_u64()) {
+        settings["download"]["asset_concurrency"] = serde_json::json!(concurrency);
+    }
+    if let Some(mirror) = value.get("mirror").and_then(|v| v.as_str()) {
+        settings["download"]["mirror"] = serde_json::json!(mirror.to_lowercase());
+    }
+    settings
+}
+
+// 解析 "4G" / "512M" 这类内存大小
+fn parse_memory_mb(size: &str) -> Option<u32> {
+    let size = size.to_uppercase();
+    if let Some(gb) = size.strip_suffix('G') {
+        gb.parse::<u32>().ok()?.checked_mul(1024)
+    } else if let Some(mb) = size.strip_suffix('M') {
+        mb.parse().ok()
+    } else {
+        // 超出 u32 的值视为无效
+        u32::try_from(size.parse::<u64>().ok()? / 1024 / 1024).ok()
+    }
+}
+
+// 设置文件的读写
+pub struct SettingsStore {
+    dir: PathBuf,
+}
+
+impl Default for SettingsStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SettingsStore {
+    pub fn new() -> Self {
+        Self::with_dir(config_dir())
+    }
+
+    pub fn with_dir(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    pub fn path(&self) -> PathBuf {
+        self.dir.join(SETTINGS_FILE)
+    }
+
+    // 读取设置，文件不存在时返回默认值
+    pub fn load(&self) -> LauncherResult<LauncherSettings> {
+        let path = self.path();
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(LauncherSettings::default())
+            }
+            Err(e) => return Err(LauncherError::io(&path, e)),
+        };
+        let value: serde_json::Value = serde_json::from_str(&content)?;
+        let value = migrate(value)?;
+        Ok(serde_json::from_value(value)?)
+    }
+
+    // 先写入临时文件再重命名，避免写入中断导致设置损坏
+    pub fn save(&self, settings: &LauncherSettings) -> LauncherResult<()> {
+        std::fs::create_dir_all(&self.dir).map_err(|e| LauncherError::io(&self.dir, e))?;
+        let content = serde_json::to_string_pretty(settings)?;
+        let tmp_path = self.dir.join(format!("{}.tmp", SETTINGS_FILE));
+        std::fs::write(&tmp_path, content).map_err(|e| LauncherError::io(&tmp_path, e))?;
+        std::fs::rename(&tmp_path, self.path()).map_err(|e| LauncherError::io(&self.path(), e))
+    }
+}
+
+// 进程内缓存的当前设置
+fn settings_cache() -> &'static RwLock<LauncherSettings> {
+    static SETTINGS: OnceLock<RwLock<LauncherSettings>> = OnceLock::new();
+    SETTINGS.get_or_init(|| {
+        let settings = SettingsStore::new().load().unwrap_or_else(|e| {
+            log::error!("{}，使用默认设置", e);
+            LauncherSettings::default()
+        });
+        RwLock::new(settings)
+    })
+}
+
+// 获取当前设置
+pub fn current() -> LauncherSettings {
+    settings_cache().read().unwrap().clone()
+}
+
+// 校验并保存设置
+pub fn update(settings: LauncherSettings) -> LauncherResult<LauncherSettings> {
+    let settings = LauncherSettings {
+        schema_version: SCHEMA_VERSION,
+        ..settings
+    };
+    settings.validate()?;
+    SettingsStore::new().save(&settings)?;
+    *settings_cache().write().unwrap() = settings.clone();
+    Ok(settings)
+}
+
+#[tauri::command]
+pub fn get_settings() -> LauncherSettings {
+    current()
+}
+
+#[tauri::command]
+pub fn set_settings(settings: LauncherSettings) -> LauncherResult<LauncherSettings> {
+    update(settings)
+}
+
+#[tauri::command]
+pub fn reset_settings() -> LauncherResult<LauncherSettings> {
+    update(LauncherSettings::default())
+}
+
+// 获取实例生效的设置
+#[tauri::command]
+pub fn get_effective_settings(instance_id: String) -> LauncherResult<LauncherSettings> {
+    let instance = InstanceStore::new().get(&instance_id)?;
+    current().for_instance(&instance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_migrate_v0_and_save() {
+        let legacy = serde_json::json!({
+            "startup_parameter": "-Xms2G -Xmx8G -XX:+UseZGC",
+            "concurrency": 64,
+            "mirror": "BMCLAPI"
+        });
+        let value = migrate(legacy).unwrap();
+        let settings: LauncherSettings = serde_json::from_value(value).unwrap();
+        assert_eq!(settings.schema_version, SCHEMA_VERSION);
+        assert_eq!(settings.memory.min_mb, 2048);
+        assert_eq!(settings.memory.max_mb, 8192);
+        assert_eq!(settings.jvm_args, vec!["-XX:+UseZGC"]);
+        assert_eq!(settings.download.asset_concurrency, 64);
+        assert_eq!(settings.download.mirror, Mirror::Bmclapi);
+
+        let dir = std::env::temp_dir().join(format!("rtl-settings-test-{}", std::process::id()));
+        let store = SettingsStore::with_dir(dir.clone());
+        assert_eq!(store.load().unwrap(), LauncherSettings::default());
+        store.save(&settings).unwrap();
+        assert_eq!(store.load().unwrap(), settings);
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let future = serde_json::json!({ "schema_version": SCHEMA_VERSION + 1 });
+        assert!(migrate(future).is_err());
+    }
+
+    #[test]
+    fn test_validate_and_instance_override() {
+        let mut settings = LauncherSettings::default();
+        settings.memory.min_mb = 8192;
+        settings.download.library_concurrency = 0;
+        let errors = settings.validate().unwrap_err();
+        let fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(
+            fields,
+            vec!["memory.min_mb", "download.library_concurrency"]
+        );
+
+        let mut instance = Instance::new("a".into(), "a".into(), "1.21.4".into());
+        instance.max_memory_mb = Some(6144);
+        let effective = LauncherSettings::default().for_instance(&instance).unwrap();
+        assert_eq!(effective.jvm_args_string(), "-Xms1024M -Xmx6144M");
+        instance.min_memory_mb = Some(8192);
+        assert!(LauncherSettings::default().for_instance(&instance).is_err());
+
+        assert_eq!(parse_memory_mb("4G"), Some(4096));
+        assert_eq!(parse_memory_mb("4194304G"), None);
+        assert_eq!(parse_memory_mb("9999999999999999999"), None);
+        assert_eq!(parse_memory_mb("1073741824"), Some(1024));
+
+        assert_eq!(
+            Mirror::Bmclapi.rewrite("https://libraries.minecraft.net/a/b.jar"),
+            "https://bmclapi2.bangbang93.com/maven/a/b.jar"
+        );
+    }
+}
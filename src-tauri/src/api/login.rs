@@ -21,26 +21,32 @@ along with this program.  If not, see <https://www.gnu.org/licenses/>.
 // 登录主函数
 // ***
 
+use crate::utils::error::{LauncherError, LauncherResult};
 use crate::utils::request;
 use log::info;
 use open;
 use request::Request;
-use std::error::Error;
 use std::io::{BufRead, Write};
 
 use url;
 
 // Tauri共享
 #[tauri::command]
-pub async fn get_code() -> Result<String, String> {
+pub async fn get_code() -> LauncherResult<String> {
     let login = Login::new();
-    login.get_code().await.map_err(|e| e.to_string())
+    login.get_code().await
 }
 
 pub struct Login {
     request: request::Request,
 }
 
+impl Default for Login {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Login {
     pub fn new() -> Self {
         Self {
@@ -52,7 +58,7 @@ impl Login {
     }
 
     // 获取授权码
-    pub async fn get_code(&self) -> Result<String, Box<dyn Error + Send + Sync>> {
+    pub async fn get_code(&self) -> LauncherResult<String> {
         // 监听40323端口
         let listener = match std::net::TcpListener::bind("localhost:40323") {
             Ok(l) => {
@@ -60,19 +66,13 @@ impl Login {
                 l
             }
             Err(e) => {
-                return Err(Box::new(std::io::Error::new(
-                    std::io::ErrorKind::Other,
-                    format!("无法启动监听服务器: {}", e),
-                )));
+                return Err(LauncherError::Auth(format!("无法启动监听服务器: {}", e)));
             }
         };
 
         // 服务器就绪后再打开浏览器
         if let Err(e) = open::that(self.request.get_url()) {
-            return Err(Box::new(std::io::Error::new(
-                std::io::ErrorKind::Other,
-                format!("无法打开浏览器: {}", e),
-            )));
+            return Err(LauncherError::Auth(format!("无法打开浏览器: {}", e)));
         }
 
         info!("等待浏览器回调...");
@@ -80,16 +80,17 @@ impl Login {
             Ok((stream, addr)) => {
                 info!("收到来自 {} 的连接", addr);
                 let buf_reader = std::io::BufReader::new(&stream);
-                let request_line = buf_reader.lines().next().ok_or("无法读取请求")??;
-                info!("收到请求: {}", request_line);
+                let request_line = buf_reader
+                    .lines()
+                    .next()
+                    .ok_or_else(|| LauncherError::Auth("无法读取请求".to_string()))??;
+                // 只记录路径，查询参数中包含授权码
+                let path = request_line.split(' ').nth(1).unwrap_or_default();
+                info!("收到请求: {}", path.split('?').next().unwrap_or_default());
 
                 // 解析 URL 中的 code 参数
-                if let Some(code) = request_line
-                    .split(' ')
-                    .nth(1)
-                    .and_then(|path| {
-                        url::Url::parse(&format!("http://localhost:40323{}", path)).ok()
-                    })
+                if let Some(code) = url::Url::parse(&format!("http://localhost:40323{}", path))
+                    .ok()
                     .and_then(|url| {
                         url.query_pairs()
                             .find(|(key, _)| key == "code")
@@ -136,21 +137,11 @@ impl Login {
                 }
             }
             Err(e) => {
-                let json_response = serde_json::json!({
-                    "code": "",
-                    "status": "error",
-                    "message": format!("接受连接失败: {}", e)
-                });
-                return Ok(json_response.to_string());
+                return Err(LauncherError::Auth(format!("接受连接失败: {}", e)));
             }
         }
 
-        let json_response = serde_json::json!({
-            "code": "",
-            "status": "error",
-            "message": "未能获取授权码"
-        });
-        Ok(json_response.to_string())
+        Err(LauncherError::Auth("未能获取授权码".to_string()))
     }
 }
 
@@ -162,6 +153,7 @@ mod tests {
     async fn test_get_code() {
         let login = Login::new();
         let code = login.get_code().await.unwrap();
-        println!("获取到的授权码: {}", code);
+        // 授权码属于敏感信息，不输出
+        assert!(!code.is_empty());
     }
 }
@@ -0,0 +1,505 @@
+/*
+RTLauncher, a third-party Minecraft launcher built with the newest
+technology and provides innovative funtionalities
+Copyright (C) 2025 lutouna
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+// ***
+// 命令行入口，供无界面的构建机和 CI 使用
+// ***
+
+use clap::{Parser, Subcommand, ValueEnum};
+use rtl_launcher_lib::log::logger;
+use rtl_launcher_lib::module::download::dwl_main::{get_version_manifest, DownloadOptions};
+use rtl_launcher_lib::module::download::installed::{list_installed, resolve_version};
+use rtl_launcher_lib::module::download::paths::MinecraftPaths;
+use rtl_launcher_lib::module::download::verify::verify_installed;
+use rtl_launcher_lib::module::instance::ins_main::InstanceStore;
+use rtl_launcher_lib::module::start_game::crash::CrashReport;
+use rtl_launcher_lib::module::start_game::log_parser::GameLogRecord;
+use rtl_launcher_lib::module::start_game::process::{
+    GameEventSink, GameProcessInfo, GameProcessManager,
+};
+use rtl_launcher_lib::module::start_game::stg_main::StartGame;
+use rtl_launcher_lib::utils::error::{LauncherError, LauncherResult};
+use rtl_launcher_lib::utils::export_bat::{export_script, ScriptFormat, TokenMode};
+use rtl_launcher_lib::utils::get_java_path::get_java_path;
+use serde::Serialize;
+use std::process::ExitCode;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+#[derive(Parser)]
+#[command(name = "rtlauncher-cli", version, about = "RTLauncher 命令行工具")]
+struct Cli {
+    /// 以 JSON 输出结果，便于脚本解析
+    #[arg(long, global = true)]
+    json: bool,
+
+    /// 在 stderr 输出详细日志
+    #[arg(short, long, global = true)]
+    verbose: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// 版本相关
+    Versions {
+        #[command(subcommand)]
+        command: VersionsCommand,
+    },
+    /// 安装版本
+    Install { version_id: String },
+    /// 校验已安装版本的文件
+    Verify { version_id: String },
+    /// 启动游戏并等待退出
+    Launch {
+        /// 版本号，使用 --instance 时由实例决定
+        #[arg(required_unless_present = "instance", conflicts_with = "instance")]
+        version_id: Option<String>,
+        /// 离线账户名
+        #[arg(long)]
+        account: String,
+        /// 使用实例的游戏目录和配置
+        #[arg(long)]
+        instance: Option<String>,
+        /// 指定 Java 版本，默认使用设置，未设置时读取版本 JSON
+        #[arg(long)]
+        java: Option<String>,
+        /// JVM 参数，默认使用设置中的内存和参数
+        #[arg(long, default_value = "", allow_hyphen_values = true)]
+        jvm_args: String,
+    },
+    /// Java 相关
+    Java {
+        #[command(subcommand)]
+        command: JavaCommand,
+    },
+    /// 导出启动脚本
+    ExportScript {
+        version_id: String,
+        /// 输出文件，根据扩展名判断格式
+        #[arg(short, long)]
+        output: String,
+        #[arg(long)]
+        account: String,
+        #[arg(long)]
+        java: Option<String>,
+        #[arg(long, value_enum)]
+        format: Option<CliScriptFormat>,
+        #[arg(long, value_enum, default_value = "plain")]
+        token_mode: CliTokenMode,
+        #[arg(long, default_value = "", allow_hyphen_values = true)]
+        jvm_args: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum VersionsCommand {
+    /// 列出可安装的版本
+    List {
+        /// 版本类型，如 release、snapshot
+        #[arg(long = "type")]
+        version_type: Option<String>,
+        /// 只列出已安装的版本
+        #[arg(long)]
+        installed: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum JavaCommand {
+    /// 列出检测到的 Java
+    List,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum CliScriptFormat {
+    Bat,
+    Ps1,
+    Sh,
+    Command,
+}
+
+impl From<CliScriptFormat> for ScriptFormat {
+    fn from(format: CliScriptFormat) -> Self {
+        match format {
+            CliScriptFormat::Bat => ScriptFormat::Bat,
+            CliScriptFormat::Ps1 => ScriptFormat::Ps1,
+            CliScriptFormat::Sh => ScriptFormat::Sh,
+            CliScriptFormat::Command => ScriptFormat::Command,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum CliTokenMode {
+    Plain,
+    Placeholder,
+    Prompt,
+}
+
+impl From<CliTokenMode> for TokenMode {
+    fn from(mode: CliTokenMode) -> Self {
+        match mode {
+            CliTokenMode::Plain => TokenMode::Plain,
+            CliTokenMode::Placeholder => TokenMode::Placeholder,
+            CliTokenMode::Prompt => TokenMode::Prompt,
+        }
+    }
+}
+
+// 版本列表中的一项
+#[derive(Serialize)]
+struct VersionEntry {
+    id: String,
+    #[serde(rename = "type")]
+    version_type: String,
+    release_time: String,
+    installed: bool,
+}
+
+// 打印结果，JSON 模式下输出单行 JSON
+fn print<T: Serialize>(json: bool, value: &T, human: impl FnOnce(&T)) {
+    if json {
+        println!("{}", serde_json::to_string(value).unwrap_or_default());
+    } else {
+        human(value);
+    }
+}
+
+// 读取版本 JSON 中的资源索引，Java 版本由启动时根据设置和版本 JSON 决定
+fn asset_index(version_id: &str) -> LauncherResult<String> {
+    let json = resolve_version(&MinecraftPaths::new(), version_id)?;
+    Ok(json["assetIndex"]["id"]
+        .as_str()
+        .unwrap_or_default()
+        .to_string())
+}
+
+async fn versions_list(
+    json: bool,
+    version_type: Option<String>,
+    installed_only: bool,
+) -> LauncherResult<()> {
+    let paths = MinecraftPaths::new();
+    // 只列出已安装版本时直接读取本地版本 JSON，离线也可用
+    if installed_only {
+        let entries: Vec<VersionEntry> = list_installed(&paths)
+            .into_iter()
+            .map(|v| VersionEntry {
+                installed: true,
+                version_type: v.version_type.unwrap_or_default(),
+                release_time: v.release_time.unwrap_or_default(),
+                id: v.id,
+            })
+            .filter(|v| version_type.as_ref().is_none_or(|t| &v.version_type == t))
+            .collect();
+        return print_versions(json, entries);
+    }
+    let manifest = get_version_manifest().await?;
+    let entries: Vec<VersionEntry> = manifest["versions"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|v| {
+            let id = v["id"].as_str().unwrap_or_default().to_string();
+            VersionEntry {
+                installed: paths.get_version_json(&id).exists(),
+                version_type: v["type"].as_str().unwrap_or_default().to_string(),
+                release_time: v["releaseTime"].as_str().unwrap_or_default().to_string(),
+                id,
+            }
+        })
+        .filter(|v| version_type.as_ref().is_none_or(|t| &v.version_type == t))
+        .collect();
+    print_versions(json, entries)
+}
+
+fn print_versions(json: bool, entries: Vec<VersionEntry>) -> LauncherResult<()> {
+    print(json, &entries, |entries| {
+        for entry in entries {
+            let mark = if entry.installed { "*" } else { " " };
+            println!(
+                "{} {:<24} {:<10} {}",
+                mark, entry.id, entry.version_type, entry.release_time
+            );
+        }
+    });
+    Ok(())
+}
+
+async fn install(json: bool, version_id: String) -> LauncherResult<()> {
+    let manifest = get_version_manifest().await?;
+    let url = manifest["versions"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .find(|v| v["id"].as_str() == Some(version_id.as_str()))
+        .and_then(|v| v["url"].as_str())
+        .ok_or_else(|| LauncherError::VersionNotFound(version_id.clone()))?;
+
+    let (_, asset_index_id) = DownloadOptions::new(url.to_string())
+        .dwl_version_manifest()
+        .await?;
+    let result = serde_json::json!({
+        "version_id": version_id,
+        "asset_index_id": asset_index_id,
+    });
+    print(json, &result, |_| println!("已安装 {}", version_id));
+    Ok(())
+}
+
+fn verify(json: bool, version_id: String) -> LauncherResult<bool> {
+    let report = verify_installed(&MinecraftPaths::new(), &version_id)?;
+    print(json, &report, |report| {
+        for path in &report.missing {
+            println!("缺失: {}", path);
+        }
+        for path in &report.corrupted {
+            println!("损坏: {}", path);
+        }
+        println!(
+            "已校验 {} 个文件，缺失 {}，损坏 {}",
+            report.checked,
+            report.missing.len(),
+            report.corrupted.len()
+        );
+    });
+    Ok(report.is_ok())
+}
+
+// 将游戏输出打印到终端，退出时通知等待方
+struct CliSink {
+    json: bool,
+    exit_tx: mpsc::UnboundedSender<GameProcessInfo>,
+}
+
+impl GameEventSink for CliSink {
+    fn on_log(&self, _id: &str, record: &GameLogRecord) {
+        if self.json {
+            let event = serde_json::json!({ "event": "log", "record": record });
+            println!("{}", event);
+        } else {
+            println!("[{}] {}", record.level, record.message);
+            if let Some(throwable) = &record.throwable {
+                println!("{}", throwable);
+            }
+        }
+    }
+
+    fn on_exit(&self, info: &GameProcessInfo) {
+        let _ = self.exit_tx.send(info.clone());
+    }
+
+    fn on_crash(&self, _id: &str, report: &CrashReport) {
+        if self.json {
+            let event = serde_json::json!({ "event": "crash", "report": report });
+            println!("{}", event);
+        } else {
+            eprintln!("游戏崩溃，可能的原因：");
+            for finding in &report.findings {
+                eprintln!("- {}：{}", finding.title, finding.suggestion);
+            }
+        }
+    }
+}
+
+async fn launch(
+    json: bool,
+    version_id: Option<String>,
+    account: String,
+    instance: Option<String>,
+    java: Option<String>,
+    jvm_args: String,
+) -> LauncherResult<i32> {
+    let start_game = match instance {
+        Some(instance_id) => {
+            let store = InstanceStore::new();
+            let instance = store.get(&instance_id)?;
+            let _ = store.touch(&instance_id);
+            let asset_index = asset_index(&instance.version_id)?;
+            StartGame::from_instance(
+                &instance,
+                jvm_args,
+                java.unwrap_or_default(),
+                asset_index,
+                account,
+            )?
+        }
+        None => {
+            let version_id = version_id
+                .ok_or_else(|| LauncherError::InvalidArgument("缺少版本号".to_string()))?;
+            let asset_index = asset_index(&version_id)?;
+            StartGame::new(
+                jvm_args,
+                version_id,
+                java.unwrap_or_default(),
+                asset_index,
+                account,
+            )
+        }
+    };
+
+    let manager = GameProcessManager::default();
+    let (exit_tx, mut exit_rx) = mpsc::unbounded_channel();
+    let id = start_game.start_game(&manager, Arc::new(CliSink { json, exit_tx }))?;
+    if json {
+        println!("{}", serde_json::json!({ "event": "started", "id": id }));
+    }
+
+    let info = exit_rx
+        .recv()
+        .await
+        .ok_or_else(|| LauncherError::Other("游戏进程状态丢失".to_string()))?;
+    if json {
+        println!(
+            "{}",
+            serde_json::json!({ "event": "exit", "process": info })
+        );
+    }
+    Ok(info.exit_code.unwrap_or(1))
+}
+
+fn java_list(json: bool) {
+    let paths = get_java_path();
+    print(json, &paths, |paths| {
+        for path in paths {
+            println!("{}", path);
+        }
+    });
+}
+
+async fn run(cli: Cli) -> LauncherResult<ExitCode> {
+    let json = cli.json;
+    match cli.command {
+        Command::Versions {
+            command:
+                VersionsCommand::List {
+                    version_type,
+                    installed,
+                },
+        } => versions_list(json, version_type, installed).await?,
+        Command::Install { version_id } => install(json, version_id).await?,
+        Command::Verify { version_id } => {
+            if !verify(json, version_id)? {
+                return Ok(ExitCode::from(2));
+            }
+        }
+        Command::Launch {
+            version_id,
+            account,
+            instance,
+            java,
+            jvm_args,
+        } => {
+            let code = launch(json, version_id, account, instance, java, jvm_args).await?;
+            return Ok(ExitCode::from(code.clamp(0, 255) as u8));
+        }
+        Command::Java {
+            command: JavaCommand::List,
+        } => java_list(json),
+        Command::ExportScript {
+            version_id,
+            output,
+            account,
+            java,
+            format,
+            token_mode,
+            jvm_args,
+        } => {
+            let asset_index = asset_index(&version_id)?;
+            export_script(
+                jvm_args,
+                version_id,
+                java.unwrap_or_default(),
+                output.clone(),
+                asset_index,
+                account,
+                format.map(Into::into),
+                Some(token_mode.into()),
+                None,
+            )
+            .await?;
+            let result = serde_json::json!({ "output": output });
+            print(json, &result, |_| println!("已导出到 {}", output));
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let level = if cli.verbose {
+        log::LevelFilter::Debug
+    } else {
+        log::LevelFilter::Warn
+    };
+    logger::init_console(level);
+
+    let json = cli.json;
+    match run(cli).await {
+        Ok(code) => code,
+        Err(e) => {
+            if json {
+                println!("{}", serde_json::json!({ "error": e }));
+            } else {
+                eprintln!("错误: {}", e);
+            }
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn test_cli_arguments() {
+        Cli::command().debug_assert();
+        let cli = Cli::parse_from([
+            "rtlauncher-cli",
+            "launch",
+            "1.21.4",
+            "--account",
+            "Steve",
+            "--jvm-args",
+            "-Xmx4G",
+            "--json",
+        ]);
+        assert!(cli.json);
+        assert!(matches!(cli.command, Command::Launch { jvm_args, .. } if jvm_args == "-Xmx4G"));
+
+        let cli = Cli::parse_from([
+            "rtlauncher-cli",
+            "launch",
+            "--instance",
+            "My-Pack",
+            "--account",
+            "Steve",
+        ]);
+        assert!(
+            matches!(cli.command, Command::Launch { version_id: None, instance: Some(id), .. } if id == "My-Pack")
+        );
+        assert!(Cli::try_parse_from(["rtlauncher-cli", "launch", "--account", "Steve"]).is_err());
+    }
+}
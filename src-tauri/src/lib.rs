@@ -17,16 +17,31 @@ You should have received a copy of the GNU General Public License
 along with this program.  If not, see <https://www.gnu.org/licenses/>.
 */
 
-#[tauri::command]
-fn greet(name: &str) -> String {
-    format!("Hello, {}! You've been greeted from Rust!", name)
-}
+// ***
+// 应用入口
+// ***
+
+pub mod Setting;
+pub mod api;
+pub mod log;
+pub mod module;
+pub mod router;
+pub mod utils;
+
+use module::start_game::process::GameProcessManager;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    crate::log::logger::init();
+
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
-        .invoke_handler(tauri::generate_handler![greet])
+        .manage(GameProcessManager::default())
+        .setup(|app| {
+            crate::log::logger::attach(app.handle().clone());
+            Ok(())
+        })
+        .invoke_handler(router::router::invoke_handler!())
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
@@ -0,0 +1,318 @@
+/*
+RTLauncher, a third-party Minecraft launcher built with the newest
+technology and provides innovative funtionalities
+Copyright (C) 2025 lutouna
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+// ***
+// 启动器日志，实现 log 门面
+// ***
+
+use super::redact::redact;
+use crate::utils::error::{LauncherError, LauncherResult};
+use chrono::{Local, TimeZone};
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use tauri::{AppHandle, Emitter};
+
+// 前端监听的事件名
+pub const LAUNCHER_LOG_EVENT: &str = "launcher-log";
+// 日志目录环境变量，主要用于测试
+pub const LOG_DIR_ENV: &str = "RTL_LOG_DIR";
+
+const LOG_FILE: &str = "rtlauncher.log";
+// 内存中保留的日志条数
+const RING_CAPACITY: usize = 1000;
+// 单个日志文件上限 5MB，最多保留 5 个历史文件
+const MAX_FILE_SIZE: u64 = 5 * 1024 * 1024;
+const MAX_FILES: usize = 5;
+
+// 发送给前端的日志
+#[derive(Debug, Clone, Serialize)]
+pub struct LogRecord {
+    pub timestamp: i64, // 毫秒时间戳
+    pub level: String,
+    pub target: String,
+    pub message: String,
+}
+
+impl LogRecord {
+    fn to_line(&self) -> String {
+        let time = Local
+            .timestamp_millis_opt(self.timestamp)
+            .single()
+            .map(|t| t.format("%Y-%m-%d %H:%M:%S%.3f").to_string())
+            .unwrap_or_default();
+        format!(
+            "[{}] [{}] {}: {}\n",
+            time, self.level, self.target, self.message
+        )
+    }
+}
+
+// 按大小滚动的日志文件
+struct RotatingFile {
+    dir: PathBuf,
+    file: Option<File>,
+    size: u64,
+}
+
+impl RotatingFile {
+    fn open(dir: PathBuf) -> Self {
+        let mut rotating = Self {
+            dir,
+            file: None,
+            size: 0,
+        };
+        rotating.reopen();
+        rotating
+    }
+
+    fn reopen(&mut self) {
+        let path = self.dir.join(LOG_FILE);
+        self.file = std::fs::create_dir_all(&self.dir)
+            .and_then(|_| OpenOptions::new().create(true).append(true).open(&path))
+            .ok();
+        self.size = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+    }
+
+    fn write_line(&mut self, line: &str) {
+        if self.size + line.len() as u64 > MAX_FILE_SIZE {
+            self.rotate();
+        }
+        if let Some(file) = self.file.as_mut() {
+            if file.write_all(line.as_bytes()).is_ok() {
+                self.size += line.len() as u64;
+            }
+        }
+    }
+
+    // rtlauncher.log -> rtlauncher.log.1 -> ... -> rtlauncher.log.N
+    fn rotate(&mut self) {
+        self.file = None;
+        let name = |index: usize| match index {
+            0 => self.dir.join(LOG_FILE),
+            _ => self.dir.join(format!("{}.{}", LOG_FILE, index)),
+        };
+        let _ = std::fs::remove_file(name(MAX_FILES));
+        for index in (0..MAX_FILES).rev() {
+            let _ = std::fs::rename(name(index), name(index + 1));
+        }
+        self.reopen();
+    }
+
+    fn flush(&mut self) {
+        if let Some(file) = self.file.as_mut() {
+            let _ = file.flush();
+        }
+    }
+}
+
+// 启动器日志：写入滚动文件、保存最近的记录并转发到前端
+pub struct LauncherLogger {
+    records: Mutex<VecDeque<LogRecord>>,
+    file: Mutex<Option<RotatingFile>>,
+    app: OnceLock<AppHandle>,
+    console: bool,
+}
+
+impl LauncherLogger {
+    fn new(dir: Option<PathBuf>, console: bool) -> Self {
+        Self {
+            records: Mutex::new(VecDeque::with_capacity(RING_CAPACITY)),
+            file: Mutex::new(dir.map(RotatingFile::open)),
+            app: OnceLock::new(),
+            console,
+        }
+    }
+
+    // 最近的日志，limit 为空时返回全部
+    pub fn recent(&self, limit: Option<usize>) -> Vec<LogRecord> {
+        let records = self.records.lock().unwrap();
+        let skip = limit.map_or(0, |limit| records.len().saturating_sub(limit));
+        records.iter().skip(skip).cloned().collect()
+    }
+
+    fn push(&self, record: LogRecord) {
+        let line = record.to_line();
+        if self.console {
+            eprint!("{}", line);
+        }
+        if let Ok(mut file) = self.file.lock() {
+            if let Some(file) = file.as_mut() {
+                file.write_line(&line);
+            }
+        }
+        if let Ok(mut records) = self.records.lock() {
+            if records.len() == RING_CAPACITY {
+                records.pop_front();
+            }
+            records.push_back(record.clone());
+        }
+        // 事件发送失败时不能再写日志，避免递归
+        if let Some(app) = self.app.get() {
+            let _ = app.emit(LAUNCHER_LOG_EVENT, record);
+        }
+    }
+}
+
+impl Log for LauncherLogger {
+    // 本程序的日志按当前级别输出，第三方库只记录警告和错误
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+            && (metadata.target().starts_with(env!("CARGO_CRATE_NAME"))
+                || metadata.level() <= Level::Warn)
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        self.push(LogRecord {
+            timestamp: Local::now().timestamp_millis(),
+            level: record.level().to_string(),
+            target: record.target().to_string(),
+            message: redact(&record.args().to_string()),
+        });
+    }
+
+    fn flush(&self) {
+        if let Ok(mut file) = self.file.lock() {
+            if let Some(file) = file.as_mut() {
+                file.flush();
+            }
+        }
+    }
+}
+
+static LOGGER: OnceLock<LauncherLogger> = OnceLock::new();
+
+// 日志目录：启动器数据目录下的 logs
+pub fn log_dir() -> Option<PathBuf> {
+    if let Some(dir) = std::env::var_os(LOG_DIR_ENV) {
+        return Some(PathBuf::from(dir));
+    }
+    dirs::data_dir().map(|dir| dir.join("RTLauncher").join("logs"))
+}
+
+// 注册全局日志，重复调用时忽略
+pub fn init() {
+    install(LauncherLogger::new(log_dir(), false), default_level());
+}
+
+// 命令行使用，日志同时输出到 stderr
+pub fn init_console(level: LevelFilter) {
+    install(LauncherLogger::new(log_dir(), true), level);
+}
+
+fn install(logger: LauncherLogger, level: LevelFilter) {
+    let logger = LOGGER.get_or_init(|| logger);
+    if log::set_logger(logger).is_ok() {
+        log::set_max_level(level);
+    }
+}
+
+// 应用启动后开始向前端转发日志
+pub fn attach(app: AppHandle) {
+    if let Some(logger) = LOGGER.get() {
+        let _ = logger.app.set(app);
+    }
+}
+
+fn default_level() -> LevelFilter {
+    if cfg!(debug_assertions) {
+        LevelFilter::Debug
+    } else {
+        LevelFilter::Info
+    }
+}
+
+#[tauri::command]
+pub fn get_launcher_logs(limit: Option<usize>) -> Vec<LogRecord> {
+    LOGGER
+        .get()
+        .map(|logger| logger.recent(limit))
+        .unwrap_or_default()
+}
+
+#[tauri::command]
+pub fn get_log_level() -> String {
+    log::max_level().to_string()
+}
+
+// 运行时调整日志级别：off、error、warn、info、debug、trace
+#[tauri::command]
+pub fn set_log_level(level: String) -> LauncherResult<()> {
+    let level: LevelFilter = level
+        .parse()
+        .map_err(|_| LauncherError::InvalidArgument(format!("无效的日志级别: {}", level)))?;
+    log::set_max_level(level);
+    log::info!("日志级别已设置为 {}", level);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_log_dir() -> LauncherResult<String> {
+    log_dir()
+        .map(|dir| dir.to_string_lossy().to_string())
+        .ok_or_else(|| LauncherError::Other("无法获取日志目录".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ring_buffer_and_rotation() {
+        let dir = std::env::temp_dir().join(format!("rtl-log-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let logger = LauncherLogger::new(Some(dir.clone()), false);
+
+        let line = "x".repeat(1024);
+        for index in 0..(RING_CAPACITY + 10) {
+            logger.push(LogRecord {
+                timestamp: index as i64,
+                level: "INFO".to_string(),
+                target: "test".to_string(),
+                message: format!("{} {}", index, line),
+            });
+        }
+        logger.push(LogRecord {
+            timestamp: 0,
+            level: "INFO".to_string(),
+            target: "test".to_string(),
+            message: redact("refresh_token=abc"),
+        });
+
+        let recent = logger.recent(Some(2));
+        assert_eq!(recent.len(), 2);
+        assert_eq!(recent[1].message, "refresh_token=********");
+        assert_eq!(logger.recent(None).len(), RING_CAPACITY);
+
+        // 写入约 1MB，未超过上限时不会滚动
+        assert!(dir.join(LOG_FILE).is_file());
+        assert!(!dir.join(format!("{}.1", LOG_FILE)).exists());
+        logger.file.lock().unwrap().as_mut().unwrap().rotate();
+        assert!(dir.join(format!("{}.1", LOG_FILE)).is_file());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
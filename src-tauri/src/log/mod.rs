@@ -17,4 +17,5 @@ You should have received a copy of the GNU General Public License
 along with this program.  If not, see <https://www.gnu.org/licenses/>.
 */
 
-pub mod info;
\ No newline at end of file
+pub mod logger;
+pub mod redact;
@@ -0,0 +1,206 @@
+/*
+RTLauncher, a third-party Minecraft launcher built with the newest
+technology and provides innovative funtionalities
+Copyright (C) 2025 lutouna
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+// ***
+// 日志脱敏，屏蔽令牌和密码
+// ***
+
+// 替换后的占位符
+pub const MASK: &str = "********";
+
+// 需要屏蔽值的键名（小写）
+const SECRET_KEYS: [&str; 12] = [
+    "accesstoken",
+    "access_token",
+    "refreshtoken",
+    "refresh_token",
+    "id_token",
+    "client_secret",
+    "clientsecret",
+    "password",
+    "passwd",
+    "bearer",
+    "x-api-key",
+    "api_key",
+];
+
+// 只在 URL 查询参数中屏蔽的键名，例如 OAuth 回调中的 ?code=
+const QUERY_KEYS: [&str; 1] = ["code"];
+
+// 屏蔽文本中的敏感值，支持以下形式：
+// key=value、"key":"value"、key: value、--accessToken value、Bearer value
+pub fn redact(text: &str) -> String {
+    let lower = text.to_ascii_lowercase();
+    let mut spans: Vec<(usize, usize)> = Vec::new();
+
+    for key in SECRET_KEYS {
+        let mut from = 0;
+        while let Some(pos) = lower[from..].find(key) {
+            let start = from + pos;
+            let end = start + key.len();
+            from = end;
+            if let Some(span) = secret_value(text, start, end, key) {
+                spans.push(span);
+                from = span.1;
+            }
+        }
+    }
+
+    for key in QUERY_KEYS {
+        let mut from = 0;
+        while let Some(pos) = lower[from..].find(key) {
+            let start = from + pos;
+            let end = start + key.len();
+            from = end;
+            if let Some(span) = query_value(text, start, end) {
+                spans.push(span);
+                from = span.1;
+            }
+        }
+    }
+
+    if spans.is_empty() {
+        return text.to_string();
+    }
+    spans.sort();
+
+    let mut result = String::with_capacity(text.len());
+    let mut last = 0;
+    for (start, end) in spans {
+        if start < last {
+            continue;
+        }
+        result.push_str(&text[last..start]);
+        result.push_str(MASK);
+        last = end;
+    }
+    result.push_str(&text[last..]);
+    result
+}
+
+// 查找键名之后的值所在区间
+fn secret_value(text: &str, start: usize, end: usize, key: &str) -> Option<(usize, usize)> {
+    let bytes = text.as_bytes();
+    let is_word = |b: u8| b.is_ascii_alphanumeric() || b == b'_';
+    if start > 0 && is_word(bytes[start - 1]) {
+        return None;
+    }
+    if end < bytes.len() && is_word(bytes[end]) {
+        return None;
+    }
+
+    // 命令行参数和 Bearer 以空白分隔，其余形式需要 ':' 或 '='
+    let is_flag = text[..start].ends_with("--") || key == "bearer";
+    let mut assigned = false;
+    let mut value_start = end;
+    while value_start < bytes.len() {
+        match bytes[value_start] {
+            b':' | b'=' => assigned = true,
+            b' ' | b'\t' | b'"' | b'\'' | b',' => {}
+            _ => break,
+        }
+        value_start += 1;
+    }
+    if !(assigned || is_flag && value_start > end) {
+        return None;
+    }
+
+    let value_end = text[value_start..]
+        .char_indices()
+        .find(|(_, c)| {
+            c.is_whitespace() || matches!(c, '"' | '\'' | ',' | '&' | ';' | '}' | ']' | ')')
+        })
+        .map(|(i, _)| value_start + i)
+        .unwrap_or(text.len());
+    if value_end == value_start {
+        return None;
+    }
+    Some((value_start, value_end))
+}
+
+// 查询参数的值，键名前必须是 '?' 或 '&'，之后紧跟 '='
+fn query_value(text: &str, start: usize, end: usize) -> Option<(usize, usize)> {
+    let bytes = text.as_bytes();
+    if start == 0 || !matches!(bytes[start - 1], b'?' | b'&') || bytes.get(end) != Some(&b'=') {
+        return None;
+    }
+    let value_start = end + 1;
+    let value_end = text[value_start..]
+        .char_indices()
+        .find(|(_, c)| c.is_whitespace() || matches!(c, '&' | '#' | '"' | '\''))
+        .map(|(i, _)| value_start + i)
+        .unwrap_or(text.len());
+    (value_end > value_start).then_some((value_start, value_end))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact_secrets() {
+        assert_eq!(
+            redact("启动参数: [\"--username\", \"Steve\", \"--accessToken\", \"eyJhbGci.x-y\", \"--userType\", \"msa\"]"),
+            "启动参数: [\"--username\", \"Steve\", \"--accessToken\", \"********\", \"--userType\", \"msa\"]"
+        );
+        assert_eq!(
+            redact(r#"{"access_token":"abc123","refresh_token": "def","expires_in":3600}"#),
+            r#"{"access_token":"********","refresh_token": "********","expires_in":3600}"#
+        );
+        assert_eq!(
+            redact("code=1&client_secret=s3cr3t&password=hunter2"),
+            "code=1&client_secret=********&password=********"
+        );
+        assert_eq!(
+            redact("Authorization: Bearer tok.en"),
+            "Authorization: Bearer ********"
+        );
+        assert_eq!(
+            redact("java --accessToken 0123 --version 1.21"),
+            "java --accessToken ******** --version 1.21"
+        );
+    }
+
+    #[test]
+    fn test_redact_query_code() {
+        assert_eq!(
+            redact("收到请求: GET /?code=M.C507_BAY.2.U.abc&state=x HTTP/1.1"),
+            "收到请求: GET /?code=********&state=x HTTP/1.1"
+        );
+        assert_eq!(
+            redact("http://localhost:40323/?state=x&code=abc"),
+            "http://localhost:40323/?state=x&code=********"
+        );
+        // 不是查询参数的 code 保持原样
+        assert_eq!(redact("status code=404"), "status code=404");
+        assert_eq!(redact(r#"{"code": 200}"#), r#"{"code": 200}"#);
+    }
+
+    #[test]
+    fn test_redact_keeps_plain_text() {
+        for text in [
+            "${auth_access_token}",
+            "password_hint: none",
+            "下载完成: 12 个文件",
+            "输入 password 后登录",
+        ] {
+            assert_eq!(redact(text), text);
+        }
+    }
+}
@@ -0,0 +1,170 @@
+/*
+RTLauncher, a third-party Minecraft launcher built with the newest
+technology and provides innovative funtionalities
+Copyright (C) 2025 lutouna
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+// ***
+// 项目元数据缓存
+// ***
+
+use crate::utils::error::{LauncherError, LauncherResult};
+use crate::Setting::config;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// 项目信息很少变化
+pub const PROJECT_TTL: Duration = Duration::from_secs(6 * 60 * 60);
+// 版本列表在新版本发布后需要尽快刷新
+pub const VERSIONS_TTL: Duration = Duration::from_secs(30 * 60);
+
+#[derive(Serialize, Deserialize)]
+struct Entry<T> {
+    fetched_at: u64,
+    data: T,
+}
+
+// 缓存保存在 cache/content 下，每个键一个 JSON 文件
+pub struct MetadataCache {
+    dir: PathBuf,
+}
+
+impl Default for MetadataCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MetadataCache {
+    pub fn new() -> Self {
+        Self::with_dir(config::cache_dir().join("content"))
+    }
+
+    pub fn with_dir(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        let name: String = key
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '.' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        self.dir.join(format!("{}.json", name))
+    }
+
+    fn read<T: DeserializeOwned>(&self, key: &str) -> Option<Entry<T>> {
+        let content = std::fs::read_to_string(self.path(key)).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    // 读取未过期的缓存
+    pub fn get<T: DeserializeOwned>(&self, key: &str, max_age: Duration) -> Option<T> {
+        self.read(key)
+            .filter(|entry| now_secs().saturating_sub(entry.fetched_at) < max_age.as_secs())
+            .map(|entry| entry.data)
+    }
+
+    pub fn put<T: Serialize>(&self, key: &str, data: &T) -> LauncherResult<()> {
+        std::fs::create_dir_all(&self.dir).map_err(|e| LauncherError::io(&self.dir, e))?;
+        let path = self.path(key);
+        let entry = Entry {
+            fetched_at: now_secs(),
+            data,
+        };
+        std::fs::write(&path, serde_json::to_string(&entry)?)
+            .map_err(|e| LauncherError::io(&path, e))
+    }
+
+    // 缓存过期时重新获取，获取失败时使用过期的缓存
+    pub async fn load<T, F, Fut>(&self, key: &str, max_age: Duration, fetch: F) -> LauncherResult<T>
+    where
+        T: Serialize + DeserializeOwned,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = LauncherResult<T>>,
+    {
+        if let Some(data) = self.get(key, max_age) {
+            return Ok(data);
+        }
+        match fetch().await {
+            Ok(data) => {
+                if let Err(e) = self.put(key, &data) {
+                    log::warn!("写入元数据缓存失败: {}", e);
+                }
+                Ok(data)
+            }
+            Err(e) => match self.read::<T>(key) {
+                Some(entry) => {
+                    log::warn!("获取 {} 失败，使用过期缓存: {}", key, e);
+                    Ok(entry.data)
+                }
+                None => Err(e),
+            },
+        }
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_cache_falls_back_to_stale_entry() {
+        let dir = std::env::temp_dir().join(format!("rtl-content-cache-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let cache = MetadataCache::with_dir(dir.clone());
+
+        let fresh: String = cache
+            .load("modrinth-project-AANobbMI", PROJECT_TTL, || async {
+                Ok("sodium".to_string())
+            })
+            .await
+            .unwrap();
+        assert_eq!(fresh, "sodium");
+        assert_eq!(
+            cache
+                .get::<String>("modrinth-project-AANobbMI", PROJECT_TTL)
+                .as_deref(),
+            Some("sodium")
+        );
+
+        // 已过期且网络失败时使用旧数据
+        let stale: String = cache
+            .load("modrinth-project-AANobbMI", Duration::ZERO, || async {
+                Err(LauncherError::Other("offline".to_string()))
+            })
+            .await
+            .unwrap();
+        assert_eq!(stale, "sodium");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,606 @@
+/*
+RTLauncher, a third-party Minecraft launcher built with the newest
+technology and provides innovative funtionalities
+Copyright (C) 2025 lutouna
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+// ***
+// CurseForge API
+// ***
+
+use super::{
+    http_client, ContentDependency, ContentPage, ContentType, DependencyKind, Platform,
+    SearchQuery, UnifiedFile, UnifiedProject, UnifiedVersion,
+};
+use crate::utils::error::{LauncherError, LauncherResult};
+use crate::Setting::config;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+pub const CURSEFORGE_API_URL: &str = "https://api.curseforge.com";
+// Minecraft 在 CurseForge 中的游戏编号
+pub const MINECRAFT_GAME_ID: u32 = 432;
+
+// 文件列表单页的最大数量
+const PAGE_SIZE: usize = 50;
+// 搜索接口 index + pageSize 不能超过该值
+const MAX_SEARCH_RESULTS: u64 = 10_000;
+// 文件哈希算法编号，1 为 SHA-1
+const HASH_ALGO_SHA1: u32 = 1;
+
+// 下一次请求使用的 key 序号，多个 key 轮换使用
+static NEXT_KEY: AtomicUsize = AtomicUsize::new(0);
+
+// 资源类型对应的 classId
+pub fn class_id(content_type: ContentType) -> u32 {
+    match content_type {
+        ContentType::Mod => 6,
+        ContentType::ResourcePack => 12,
+        ContentType::Shader => 6552,
+        ContentType::DataPack => 6945,
+    }
+}
+
+// 加载器对应的 modLoaderType
+pub fn mod_loader_type(loader: &str) -> Option<u32> {
+    match loader.to_lowercase().as_str() {
+        "forge" => Some(1),
+        "fabric" => Some(4),
+        "quilt" => Some(5),
+        "neoforge" => Some(6),
+        _ => None,
+    }
+}
+
+// 作者关闭第三方下载时 downloadUrl 为空，使用 forgecdn 的地址
+// 路径为 id 的前若干位与后三位（不补零），文件名需要百分号编码
+pub fn forgecdn_url(file_id: u64, file_name: &str) -> String {
+    let mut url = url::Url::parse("https://edge.forgecdn.net/files/").unwrap();
+    url.path_segments_mut()
+        .unwrap()
+        .pop_if_empty()
+        .push(&(file_id / 1000).to_string())
+        .push(&(file_id % 1000).to_string())
+        .push(file_name);
+    url.to_string()
+}
+
+// classId 对应的资源类型
+pub fn content_type_for_class(id: u32) -> Option<ContentType> {
+    [
+        ContentType::Mod,
+        ContentType::ResourcePack,
+        ContentType::Shader,
+        ContentType::DataPack,
+    ]
+    .into_iter()
+    .find(|content_type| class_id(*content_type) == id)
+}
+
+// CurseForge 文件指纹：去掉空白字节后的 MurmurHash2，种子为 1
+pub fn fingerprint(bytes: &[u8]) -> u32 {
+    const M: u32 = 0x5bd1_e995;
+    let data: Vec<u8> = bytes
+        .iter()
+        .copied()
+        .filter(|b| !matches!(b, 9 | 10 | 13 | 32))
+        .collect();
+    let mut hash = 1 ^ data.len() as u32;
+    let mut chunks = data.chunks_exact(4);
+    for chunk in &mut chunks {
+        let mut k = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        k = k.wrapping_mul(M);
+        k ^= k >> 24;
+        k = k.wrapping_mul(M);
+        hash = hash.wrapping_mul(M) ^ k;
+    }
+    let tail = chunks.remainder();
+    if !tail.is_empty() {
+        for (i, byte) in tail.iter().enumerate() {
+            hash ^= (*byte as u32) << (8 * i);
+        }
+        hash = hash.wrapping_mul(M);
+    }
+    hash ^= hash >> 13;
+    hash = hash.wrapping_mul(M);
+    hash ^ (hash >> 15)
+}
+
+#[derive(Debug, Deserialize)]
+struct Response<T> {
+    data: T,
+}
+
+#[derive(Debug, Deserialize)]
+struct FilesResponse {
+    data: Vec<CurseForgeFile>,
+    pagination: Option<Pagination>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchResponse {
+    data: Vec<CurseForgeMod>,
+    pagination: Option<Pagination>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FingerprintMatches {
+    #[serde(default)]
+    exact_matches: Vec<FingerprintMatch>,
+}
+
+// 指纹匹配的文件以及项目的最新文件
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct FingerprintMatch {
+    pub file: CurseForgeFile,
+    #[serde(default)]
+    pub latest_files: Vec<CurseForgeFile>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Pagination {
+    total_count: u64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CurseForgeMod {
+    pub id: u64,
+    slug: Option<String>,
+    pub name: String,
+    #[serde(default)]
+    summary: String,
+    logo: Option<CurseForgeLogo>,
+    #[serde(default)]
+    download_count: f64,
+    pub class_id: Option<u32>,
+    pub links: Option<CurseForgeLinks>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CurseForgeLinks {
+    pub website_url: Option<String>,
+}
+
+impl CurseForgeMod {
+    fn unified(self) -> UnifiedProject {
+        UnifiedProject {
+            platform: Platform::CurseForge,
+            project_id: self.id.to_string(),
+            slug: self.slug,
+            name: self.name,
+            summary: self.summary,
+            icon_url: self.logo.and_then(|logo| logo.thumbnail_url),
+            downloads: self.download_count as u64,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CurseForgeLogo {
+    thumbnail_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CurseForgeFile {
+    pub id: u64,
+    pub mod_id: u64,
+    pub display_name: String,
+    pub file_name: String,
+    pub file_date: Option<String>,
+    pub download_url: Option<String>,
+    pub file_length: Option<u64>,
+    #[serde(default)]
+    pub game_versions: Vec<String>,
+    #[serde(default)]
+    pub hashes: Vec<CurseForgeHash>,
+    #[serde(default)]
+    pub dependencies: Vec<CurseForgeDependency>,
+    pub file_fingerprint: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CurseForgeDependency {
+    pub mod_id: u64,
+    pub relation_type: u32,
+}
+
+impl CurseForgeDependency {
+    // relationType: 1 内置库, 2 可选, 3 必需, 4 工具, 5 不兼容, 6 包含
+    fn unified(&self) -> Option<ContentDependency> {
+        let kind = match self.relation_type {
+            1 | 6 => DependencyKind::Embedded,
+            2 => DependencyKind::Optional,
+            3 => DependencyKind::Required,
+            5 => DependencyKind::Incompatible,
+            _ => return None,
+        };
+        Some(ContentDependency {
+            kind,
+            project_id: Some(self.mod_id.to_string()),
+            version_id: None,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct CurseForgeHash {
+    pub value: String,
+    pub algo: u32,
+}
+
+// gameVersions 中混有加载器名称
+const LOADER_NAMES: [&str; 4] = ["forge", "fabric", "quilt", "neoforge"];
+
+impl CurseForgeFile {
+    pub(crate) fn unified_version(&self) -> UnifiedVersion {
+        let (loaders, game_versions): (Vec<String>, Vec<String>) = self
+            .game_versions
+            .iter()
+            .cloned()
+            .partition(|v| LOADER_NAMES.contains(&v.to_lowercase().as_str()));
+        UnifiedVersion {
+            platform: Platform::CurseForge,
+            project_id: self.mod_id.to_string(),
+            version_id: self.id.to_string(),
+            name: self.display_name.clone(),
+            date_published: self.file_date.clone(),
+            game_versions,
+            loaders: loaders.into_iter().map(|l| l.to_lowercase()).collect(),
+            dependencies: self
+                .dependencies
+                .iter()
+                .filter_map(CurseForgeDependency::unified)
+                .collect(),
+            incompatible_reason: None,
+        }
+    }
+
+    pub(crate) fn unified_file(&self) -> UnifiedFile {
+        let download_url = self
+            .download_url
+            .clone()
+            .filter(|url| !url.is_empty())
+            .unwrap_or_else(|| forgecdn_url(self.id, &self.file_name));
+        UnifiedFile {
+            platform: Platform::CurseForge,
+            file_id: self.id.to_string(),
+            display_name: self.display_name.clone(),
+            file_name: self.file_name.clone(),
+            download_url,
+            size: self.file_length,
+            sha1: self
+                .hashes
+                .iter()
+                .find(|hash| hash.algo == HASH_ALGO_SHA1)
+                .map(|hash| hash.value.clone()),
+            primary: true,
+        }
+    }
+}
+
+pub struct CurseForgeApi {
+    base: String,
+    keys: Vec<String>,
+    client: reqwest::Client,
+}
+
+impl Default for CurseForgeApi {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CurseForgeApi {
+    pub fn new() -> Self {
+        Self::with_base(
+            CURSEFORGE_API_URL.to_string(),
+            config::current().content.curseforge_keys(),
+        )
+    }
+
+    pub fn with_base(base: String, keys: Vec<String>) -> Self {
+        Self {
+            base: base.trim_end_matches('/').to_string(),
+            keys,
+            client: http_client(),
+        }
+    }
+
+    async fn get<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, String)],
+    ) -> LauncherResult<T> {
+        self.request(path, |client, url| client.get(url).query(query))
+            .await
+    }
+
+    async fn post<T: DeserializeOwned>(&self, path: &str, body: &Value) -> LauncherResult<T> {
+        self.request(path, |client, url| client.post(url).json(body))
+            .await
+    }
+
+    pub fn has_keys(&self) -> bool {
+        !self.keys.is_empty()
+    }
+
+    // 依次尝试每个 key，被拒绝（401/403/429）时换下一个
+    async fn request<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        build: impl Fn(&reqwest::Client, &str) -> reqwest::RequestBuilder,
+    ) -> LauncherResult<T> {
+        if self.keys.is_empty() {
+            return Err(LauncherError::Auth(format!(
+                "未配置 CurseForge API key，请在设置或环境变量 {} 中填写",
+                config::CURSEFORGE_KEY_ENV
+            )));
+        }
+        let url = format!("{}{}", self.base, path);
+        let start = NEXT_KEY.fetch_add(1, Ordering::Relaxed);
+        let mut last_error = None;
+        for offset in 0..self.keys.len() {
+            let key = &self.keys[(start + offset) % self.keys.len()];
+            let response = build(&self.client, &url)
+                .header("x-api-key", key)
+                .send()
+                .await
+                .map_err(|e| LauncherError::network(&url, e))?;
+            let status = response.status();
+            if matches!(status.as_u16(), 401 | 403 | 429) {
+                log::warn!("CurseForge API key 被拒绝 ({})，尝试下一个", status);
+                last_error = Some(LauncherError::Auth(format!(
+                    "CurseForge API key 无效: {}",
+                    status
+                )));
+                continue;
+            }
+            let response = response
+                .error_for_status()
+                .map_err(|e| LauncherError::network(&url, e))?;
+            return response
+                .json::<T>()
+                .await
+                .map_err(|e| LauncherError::Parse(format!("{}: {}", url, e)));
+        }
+        Err(last_error
+            .unwrap_or_else(|| LauncherError::Auth("CurseForge API key 无效".to_string())))
+    }
+
+    pub async fn search(&self, search: &SearchQuery) -> LauncherResult<ContentPage> {
+        let index = search.offset().min(MAX_SEARCH_RESULTS);
+        let page_size = (search.page_size as u64).min(MAX_SEARCH_RESULTS - index);
+        let mut params = vec![
+            ("gameId", MINECRAFT_GAME_ID.to_string()),
+            ("classId", class_id(search.content_type).to_string()),
+            ("searchFilter", search.query.clone()),
+            ("index", index.to_string()),
+            ("pageSize", page_size.to_string()),
+        ];
+        if let Some(version) = &search.game_version {
+            params.push(("gameVersion", version.clone()));
+        }
+        if search.content_type == ContentType::Mod {
+            if let Some(loader) = search.loader.as_deref().and_then(mod_loader_type) {
+                params.push(("modLoaderType", loader.to_string()));
+            }
+        }
+        let response: SearchResponse = self.get("/v1/mods/search", &params).await?;
+        let total_hits = response
+            .pagination
+            .map(|p| p.total_count.min(MAX_SEARCH_RESULTS))
+            .unwrap_or(response.data.len() as u64);
+        Ok(ContentPage {
+            items: response
+                .data
+                .into_iter()
+                .map(CurseForgeMod::unified)
+                .collect(),
+            page: search.page,
+            page_size: search.page_size,
+            total_hits,
+        })
+    }
+
+    pub async fn project(&self, project_id: &str) -> LauncherResult<UnifiedProject> {
+        let response: Response<CurseForgeMod> =
+            self.get(&format!("/v1/mods/{}", project_id), &[]).await?;
+        Ok(response.data.unified())
+    }
+
+    // 项目的文件列表，可按游戏版本筛选
+    pub async fn versions(
+        &self,
+        project_id: &str,
+        game_version: Option<&str>,
+    ) -> LauncherResult<Vec<UnifiedVersion>> {
+        // 文件列表分页返回，按 index 翻页直到取完 totalCount
+        let mut files = Vec::new();
+        loop {
+            let mut params = vec![
+                ("index", files.len().to_string()),
+                ("pageSize", PAGE_SIZE.to_string()),
+            ];
+            if let Some(version) = game_version {
+                params.push(("gameVersion", version.to_string()));
+            }
+            let response: FilesResponse = self
+                .get(&format!("/v1/mods/{}/files", project_id), &params)
+                .await?;
+            let count = response.data.len();
+            files.extend(response.data);
+            let total = response.pagination.map_or(0, |p| p.total_count);
+            if count == 0 || files.len() as u64 >= total {
+                break;
+            }
+        }
+        Ok(files
+            .iter()
+            .filter(|file| game_version.is_none_or(|v| file.game_versions.iter().any(|g| g == v)))
+            .map(|file| file.unified_version())
+            .collect())
+    }
+
+    pub async fn version(&self, project_id: &str, file_id: &str) -> LauncherResult<UnifiedVersion> {
+        let response: Response<CurseForgeFile> = self
+            .get(&format!("/v1/mods/{}/files/{}", project_id, file_id), &[])
+            .await?;
+        Ok(response.data.unified_version())
+    }
+
+    // 批量获取文件信息
+    pub(crate) async fn files_by_ids(
+        &self,
+        file_ids: &[u64],
+    ) -> LauncherResult<Vec<CurseForgeFile>> {
+        let response: Response<Vec<CurseForgeFile>> = self
+            .post("/v1/mods/files", &json!({ "fileIds": file_ids }))
+            .await?;
+        Ok(response.data)
+    }
+
+    // 批量获取项目信息
+    pub(crate) async fn mods_by_ids(&self, mod_ids: &[u64]) -> LauncherResult<Vec<CurseForgeMod>> {
+        let response: Response<Vec<CurseForgeMod>> =
+            self.post("/v1/mods", &json!({ "modIds": mod_ids })).await?;
+        Ok(response.data)
+    }
+
+    // 按文件指纹查找已安装的文件
+    pub(crate) async fn fingerprint_matches(
+        &self,
+        fingerprints: &[u32],
+    ) -> LauncherResult<Vec<FingerprintMatch>> {
+        let response: Response<FingerprintMatches> = self
+            .post(
+                &format!("/v1/fingerprints/{}", MINECRAFT_GAME_ID),
+                &json!({ "fingerprints": fingerprints }),
+            )
+            .await?;
+        Ok(response.data.exact_matches)
+    }
+
+    pub async fn files(&self, project_id: &str, file_id: &str) -> LauncherResult<Vec<UnifiedFile>> {
+        let response: Response<CurseForgeFile> = self
+            .get(&format!("/v1/mods/{}/files/{}", project_id, file_id), &[])
+            .await?;
+        Ok(vec![response.data.unified_file()])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::module::loader::fake_server;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_fingerprint_ignores_whitespace() {
+        assert_eq!(fingerprint(b""), 0x5bd1_5e36);
+        assert_eq!(fingerprint(b"a b\r\nc\td"), fingerprint(b"abcd"));
+        assert_ne!(fingerprint(b"abcd"), fingerprint(b"abce"));
+    }
+
+    #[test]
+    fn test_forgecdn_url() {
+        assert_eq!(
+            forgecdn_url(4567123, "jei.jar"),
+            "https://edge.forgecdn.net/files/4567/123/jei.jar"
+        );
+        assert_eq!(
+            forgecdn_url(4567012, "jei.jar"),
+            "https://edge.forgecdn.net/files/4567/12/jei.jar"
+        );
+        assert_eq!(
+            forgecdn_url(4567000, "Just Enough Items [1.21]#2.jar"),
+            "https://edge.forgecdn.net/files/4567/0/Just%20Enough%20Items%20[1.21]%232.jar"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_curseforge_file_without_download_url() {
+        let (base, server) = fake_server::serve(HashMap::from([(
+            "/v1/mods/238222/files/4567123".to_string(),
+            br#"{"data":{"id":4567123,"modId":238222,"displayName":"JEI 19","fileName":"jei.jar",
+                "downloadUrl":null,"fileLength":42,"gameVersions":["1.21.1","NeoForge"],
+                "hashes":[{"value":"md5","algo":2},{"value":"sha","algo":1}]}}"#
+                .to_vec(),
+        )]))
+        .await;
+
+        let without_keys = CurseForgeApi::with_base(base.clone(), Vec::new());
+        assert!(without_keys.files("238222", "4567123").await.is_err());
+
+        let api = CurseForgeApi::with_base(base, vec!["key".to_string()]);
+        let files = api.files("238222", "4567123").await.unwrap();
+        assert_eq!(files[0].sha1.as_deref(), Some("sha"));
+        assert_eq!(
+            files[0].download_url,
+            "https://edge.forgecdn.net/files/4567/123/jei.jar"
+        );
+
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn test_versions_paginated() {
+        let file = |id: u64| {
+            format!(
+                r#"{{"id":{},"modId":238222,"displayName":"JEI {}","fileName":"jei-{}.jar",
+                "downloadUrl":"https://example.com/jei-{}.jar","fileLength":1,
+                "gameVersions":["1.21.1"],"hashes":[]}}"#,
+                id, id, id, id
+            )
+        };
+        let page = |ids: std::ops::Range<u64>| {
+            let data: Vec<String> = ids.map(file).collect();
+            format!(
+                r#"{{"data":[{}],"pagination":{{"index":0,"pageSize":50,"totalCount":{}}}}}"#,
+                data.join(","),
+                PAGE_SIZE + 2
+            )
+            .into_bytes()
+        };
+        let (base, server) = fake_server::serve(HashMap::from([
+            (
+                "/v1/mods/238222/files?index=0&pageSize=50".to_string(),
+                page(0..PAGE_SIZE as u64),
+            ),
+            (
+                "/v1/mods/238222/files?index=50&pageSize=50".to_string(),
+                page(PAGE_SIZE as u64..PAGE_SIZE as u64 + 2),
+            ),
+        ]))
+        .await;
+
+        let api = CurseForgeApi::with_base(base, vec!["key".to_string()]);
+        let versions = api.versions("238222", None).await.unwrap();
+        assert_eq!(versions.len(), PAGE_SIZE + 2);
+        assert_eq!(versions.last().unwrap().name, "JEI 51");
+
+        server.abort();
+    }
+}
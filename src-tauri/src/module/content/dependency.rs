@@ -0,0 +1,775 @@
+/*
+RTLauncher, a third-party Minecraft launcher built with the newest
+technology and provides innovative funtionalities
+Copyright (C) 2025 lutouna
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+// ***
+// 安装资源时递归解析依赖，下载前先生成安装计划
+// ***
+
+use super::curseforge::CurseForgeApi;
+use super::filter::ContentFilter;
+use super::index::{disabled_path, ContentIndex, InstalledContent};
+use super::modrinth::ModrinthApi;
+use super::update::{hash_mods, LocalFile};
+use super::{
+    download_to, sort_versions, ContentType, DependencyKind, Platform, UnifiedFile, UnifiedVersion,
+};
+use crate::module::instance::ins_main::InstanceStore;
+use crate::module::modpack::safe_relative_path;
+use crate::utils::error::{LauncherError, LauncherResult};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+
+// 单次安装最多解析的项目数，防止依赖链异常时无限请求
+const MAX_PLAN_ITEMS: usize = 64;
+
+// 计划中要下载的一个文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlannedInstall {
+    pub content_type: ContentType,
+    pub version: UnifiedVersion,
+    pub file: UnifiedFile,
+    // 依赖它的项目，直接安装的项目为空
+    pub required_by: Option<String>,
+    // 被替换的旧文件（相对游戏目录）
+    pub replaces: Option<String>,
+}
+
+impl PlannedInstall {
+    fn incompatible(&self) -> impl Iterator<Item = &String> {
+        self.version
+            .dependencies
+            .iter()
+            .filter(|dep| dep.kind == DependencyKind::Incompatible)
+            .filter_map(|dep| dep.project_id.as_ref())
+    }
+}
+
+// 未选择安装的可选依赖
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OptionalDependency {
+    pub platform: Platform,
+    pub project_id: String,
+    pub required_by: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContentConflict {
+    pub project_id: String,
+    pub conflicts_with: String,
+    pub reason: String,
+}
+
+// 安装计划，存在冲突或无法解析的必需依赖时不能安装
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InstallPlan {
+    pub items: Vec<PlannedInstall>,
+    pub optional: Vec<OptionalDependency>,
+    pub conflicts: Vec<ContentConflict>,
+    pub unresolved: Vec<String>,
+}
+
+impl InstallPlan {
+    pub fn can_install(&self) -> bool {
+        !self.items.is_empty() && self.conflicts.is_empty() && self.unresolved.is_empty()
+    }
+}
+
+// 等待解析的项目
+struct Pending {
+    project_id: Option<String>,
+    version_id: Option<String>,
+    content_type: ContentType,
+    required_by: Option<String>,
+}
+
+impl Pending {
+    fn label(&self) -> String {
+        self.project_id
+            .clone()
+            .or_else(|| self.version_id.clone())
+            .unwrap_or_default()
+    }
+}
+
+pub struct DependencyResolver {
+    modrinth: ModrinthApi,
+    curseforge: CurseForgeApi,
+    filter: ContentFilter,
+    installed: Vec<InstalledContent>,
+}
+
+impl DependencyResolver {
+    pub fn new(filter: ContentFilter, installed: Vec<InstalledContent>) -> Self {
+        Self::with_apis(ModrinthApi::new(), CurseForgeApi::new(), filter, installed)
+    }
+
+    pub fn with_apis(
+        modrinth: ModrinthApi,
+        curseforge: CurseForgeApi,
+        filter: ContentFilter,
+        installed: Vec<InstalledContent>,
+    ) -> Self {
+        Self {
+            modrinth,
+            curseforge,
+            filter,
+            installed,
+        }
+    }
+
+    // 整合包导入、单独下载或手动复制的模组没有安装记录，按哈希识别后补充到记录中
+    pub async fn track_untracked(&mut self, index: &mut ContentIndex) {
+        let mods_dir = index.game_dir().join(ContentType::Mod.folder());
+        let mut files: Vec<LocalFile> = tokio::task::spawn_blocking(move || hash_mods(&mods_dir))
+            .await
+            .unwrap_or_default();
+        files.retain(|file| {
+            let path = mod_path(&file.file_name);
+            !index.entries().iter().any(|entry| entry.path == path)
+        });
+
+        if !files.is_empty() {
+            let hashes: Vec<String> = files.iter().map(|file| file.sha1.clone()).collect();
+            match self.modrinth.versions_by_hash(&hashes, "sha1").await {
+                Ok(found) => files.retain(|file| match found.get(&file.sha1) {
+                    Some(version) => {
+                        index.upsert(untracked_entry(&file.file_name, version.unified()));
+                        false
+                    }
+                    None => true,
+                }),
+                Err(e) => log::warn!("Modrinth 识别已安装模组失败: {}", e),
+            }
+        }
+        if !files.is_empty() && self.curseforge.has_keys() {
+            let fingerprints: Vec<u32> = files.iter().map(|file| file.fingerprint).collect();
+            match self.curseforge.fingerprint_matches(&fingerprints).await {
+                Ok(matches) => {
+                    for matched in matches {
+                        if let Some(file) = files.iter().find(|file| {
+                            matched.file.file_fingerprint == Some(file.fingerprint as u64)
+                        }) {
+                            let version = matched.file.unified_version();
+                            index.upsert(untracked_entry(&file.file_name, version));
+                        }
+                    }
+                }
+                Err(e) => log::warn!("CurseForge 识别已安装模组失败: {}", e),
+            }
+        }
+        self.installed = index.entries().to_vec();
+    }
+
+    fn installed(&self, platform: Platform, project_id: &str) -> Option<&InstalledContent> {
+        self.installed
+            .iter()
+            .find(|entry| entry.platform == platform && entry.project_id == project_id)
+    }
+
+    // 指定了版本时使用该版本，否则选择最新的兼容版本
+    async fn pick(
+        &self,
+        platform: Platform,
+        pending: &Pending,
+    ) -> LauncherResult<Option<UnifiedVersion>> {
+        let mut version = match (platform, &pending.project_id, &pending.version_id) {
+            (Platform::Modrinth, _, Some(version_id)) => self.modrinth.version(version_id).await?,
+            (Platform::CurseForge, Some(project_id), Some(file_id)) => {
+                self.curseforge.version(project_id, file_id).await?
+            }
+            (_, Some(project_id), None) => {
+                let mut versions = match platform {
+                    Platform::Modrinth => self.modrinth.versions(project_id, None).await?,
+                    Platform::CurseForge => self.curseforge.versions(project_id, None).await?,
+                };
+                sort_versions(&mut versions);
+                self.filter.flag(pending.content_type, &mut versions);
+                return Ok(versions
+                    .into_iter()
+                    .find(|version| version.incompatible_reason.is_none()));
+            }
+            _ => return Ok(None),
+        };
+        version.incompatible_reason = self
+            .filter
+            .incompatible_reason(pending.content_type, &version);
+        Ok(version.incompatible_reason.is_none().then_some(version))
+    }
+
+    async fn primary_file(&self, version: &UnifiedVersion) -> LauncherResult<Option<UnifiedFile>> {
+        let files = match version.platform {
+            Platform::Modrinth => self.modrinth.files(&version.version_id).await?,
+            Platform::CurseForge => {
+                self.curseforge
+                    .files(&version.project_id, &version.version_id)
+                    .await?
+            }
+        };
+        let primary = files.iter().position(|file| file.primary).unwrap_or(0);
+        Ok(files.into_iter().nth(primary))
+    }
+
+    // 从要安装的项目开始按广度优先解析必需依赖
+    pub async fn resolve(
+        &self,
+        platform: Platform,
+        content_type: ContentType,
+        project_id: String,
+        version_id: Option<String>,
+        include_optional: bool,
+    ) -> LauncherResult<InstallPlan> {
+        let mut plan = InstallPlan::default();
+        let mut seen = HashSet::new();
+        let mut queue = VecDeque::from([Pending {
+            project_id: Some(project_id),
+            version_id,
+            content_type,
+            required_by: None,
+        }]);
+
+        while let Some(pending) = queue.pop_front() {
+            if plan.items.len() >= MAX_PLAN_ITEMS {
+                plan.unresolved
+                    .push(format!("依赖数量超过 {} 个", MAX_PLAN_ITEMS));
+                break;
+            }
+            let is_root = pending.required_by.is_none();
+            if let Some(project_id) = &pending.project_id {
+                if seen.contains(project_id) {
+                    continue;
+                }
+                // 已安装的依赖不再下载
+                if !is_root && self.installed(platform, project_id).is_some() {
+                    continue;
+                }
+            }
+            let version = match self.pick(platform, &pending).await {
+                Ok(Some(version)) => version,
+                Ok(None) => {
+                    plan.unresolved
+                        .push(format!("{} 没有与实例兼容的版本", pending.label()));
+                    continue;
+                }
+                Err(e) if is_root => return Err(e),
+                Err(e) => {
+                    plan.unresolved.push(format!("{}: {}", pending.label(), e));
+                    continue;
+                }
+            };
+            if !seen.insert(version.project_id.clone()) {
+                continue;
+            }
+            let installed = self.installed(platform, &version.project_id);
+            if !is_root && installed.is_some() {
+                continue;
+            }
+            let Some(file) = self.primary_file(&version).await? else {
+                plan.unresolved
+                    .push(format!("{} 没有可下载的文件", version.name));
+                continue;
+            };
+
+            for dependency in &version.dependencies {
+                let include = match dependency.kind {
+                    DependencyKind::Required => true,
+                    DependencyKind::Optional => include_optional,
+                    DependencyKind::Incompatible | DependencyKind::Embedded => false,
+                };
+                if include {
+                    queue.push_back(Pending {
+                        project_id: dependency.project_id.clone(),
+                        version_id: dependency.version_id.clone(),
+                        content_type: ContentType::Mod,
+                        required_by: Some(version.project_id.clone()),
+                    });
+                } else if let (DependencyKind::Optional, Some(project_id)) =
+                    (dependency.kind, &dependency.project_id)
+                {
+                    plan.optional.push(OptionalDependency {
+                        platform,
+                        project_id: project_id.clone(),
+                        required_by: version.project_id.clone(),
+                    });
+                }
+            }
+
+            plan.items.push(PlannedInstall {
+                content_type: pending.content_type,
+                replaces: installed
+                    .filter(|entry| entry.version_id != version.version_id)
+                    .map(|entry| entry.path.clone()),
+                version,
+                file,
+                required_by: pending.required_by,
+            });
+        }
+
+        let planned: HashSet<&String> = plan.items.iter().map(|i| &i.version.project_id).collect();
+        plan.optional.retain(|dep| {
+            !planned.contains(&dep.project_id)
+                && self.installed(platform, &dep.project_id).is_none()
+        });
+        plan.conflicts = conflicts(&self.installed, &plan.items);
+        Ok(plan)
+    }
+}
+
+fn mod_path(file_name: &str) -> String {
+    format!("{}/{}", ContentType::Mod.folder(), file_name)
+}
+
+// 按哈希识别到的模组记录
+fn untracked_entry(file_name: &str, version: UnifiedVersion) -> InstalledContent {
+    InstalledContent {
+        platform: version.platform,
+        project_id: version.project_id,
+        version_id: version.version_id,
+        content_type: ContentType::Mod,
+        path: mod_path(file_name),
+        dependency: false,
+        incompatible: version
+            .dependencies
+            .into_iter()
+            .filter(|dep| dep.kind == DependencyKind::Incompatible)
+            .filter_map(|dep| dep.project_id)
+            .collect(),
+    }
+}
+
+// 检查计划与已安装资源、计划内部的不兼容声明
+pub fn conflicts(installed: &[InstalledContent], items: &[PlannedInstall]) -> Vec<ContentConflict> {
+    let mut conflicts = Vec::new();
+    let in_plan = |platform: Platform, id: &str| {
+        items
+            .iter()
+            .any(|item| item.version.platform == platform && item.version.project_id == id)
+    };
+    for item in items {
+        let platform = item.version.platform;
+        for id in item.incompatible() {
+            let installed = installed
+                .iter()
+                .any(|entry| entry.platform == platform && &entry.project_id == id);
+            if installed || in_plan(platform, id) {
+                conflicts.push(ContentConflict {
+                    project_id: item.version.project_id.clone(),
+                    conflicts_with: id.clone(),
+                    reason: format!("{} 声明与 {} 不兼容", item.version.name, id),
+                });
+            }
+        }
+    }
+    for entry in installed {
+        // 会被替换的旧版本不参与检查
+        if in_plan(entry.platform, &entry.project_id) {
+            continue;
+        }
+        for id in entry
+            .incompatible
+            .iter()
+            .filter(|id| in_plan(entry.platform, id))
+        {
+            conflicts.push(ContentConflict {
+                project_id: id.clone(),
+                conflicts_with: entry.project_id.clone(),
+                reason: format!("已安装的 {} 声明与 {} 不兼容", entry.path, id),
+            });
+        }
+    }
+    conflicts
+}
+
+// 按计划下载，替换旧版本并更新安装记录，数据包安装到指定存档
+pub async fn install_plan(
+    game_dir: &Path,
+    world: Option<&str>,
+    plan: &InstallPlan,
+) -> LauncherResult<Vec<PathBuf>> {
+    let mut index = ContentIndex::load(game_dir);
+    let conflicts = conflicts(index.entries(), &plan.items);
+    if !conflicts.is_empty() || !plan.unresolved.is_empty() {
+        let reasons: Vec<String> = conflicts
+            .into_iter()
+            .map(|c| c.reason)
+            .chain(plan.unresolved.iter().cloned())
+            .collect();
+        return Err(LauncherError::InvalidArgument(format!(
+            "无法安装: {}",
+            reasons.join("; ")
+        )));
+    }
+    // 计划来自前端，只允许替换记录中已有的文件
+    for old in plan.items.iter().filter_map(|item| item.replaces.as_ref()) {
+        let known = safe_relative_path(old).is_some()
+            && index.entries().iter().any(|entry| entry.path == *old);
+        if !known {
+            return Err(LauncherError::InvalidArgument(format!(
+                "无法替换未记录的文件: {}",
+                old
+            )));
+        }
+    }
+
+    // 中途失败时也保存已完成的部分，避免已下载的文件没有记录
+    let result = install_items(game_dir, world, plan, &mut index).await;
+    index.save()?;
+    result
+}
+
+async fn install_items(
+    game_dir: &Path,
+    world: Option<&str>,
+    plan: &InstallPlan,
+    index: &mut ContentIndex,
+) -> LauncherResult<Vec<PathBuf>> {
+    let mut installed = Vec::new();
+    for item in &plan.items {
+        let path = download_to(game_dir, item.content_type, world, &item.file).await?;
+        let relative = index.relative_path(&path).unwrap_or_default();
+        if let Some(old) = item.replaces.as_ref().filter(|old| **old != relative) {
+            let old = game_dir.join(old);
+            for old in [disabled_path(&old), old] {
+                if old.exists() {
+                    std::fs::remove_file(&old).map_err(|e| LauncherError::io(&old, e))?;
+                }
+            }
+        }
+        index.upsert(InstalledContent {
+            platform: item.version.platform,
+            project_id: item.version.project_id.clone(),
+            version_id: item.version.version_id.clone(),
+            content_type: item.content_type,
+            path: relative,
+            dependency: item.required_by.is_some(),
+            incompatible: item.incompatible().cloned().collect(),
+        });
+        installed.push(path);
+    }
+    Ok(installed)
+}
+
+// 生成安装计划，前端确认后再调用 install_content
+#[tauri::command]
+pub async fn plan_content_install(
+    instance_id: String,
+    content_type: ContentType,
+    platform: Platform,
+    project_id: String,
+    version_id: Option<String>,
+    include_optional: Option<bool>,
+) -> LauncherResult<InstallPlan> {
+    let instance = InstanceStore::new().get(&instance_id)?;
+    let filter = ContentFilter::for_instance(&instance)?;
+    let mut index = ContentIndex::load(&instance.game_dir());
+    let mut resolver = DependencyResolver::new(filter, index.entries().to_vec());
+    resolver.track_untracked(&mut index).await;
+    // 保存识别结果，安装时的冲突检查和替换校验同样使用这些记录
+    index.save()?;
+    resolver
+        .resolve(
+            platform,
+            content_type,
+            project_id,
+            version_id,
+            include_optional.unwrap_or(false),
+        )
+        .await
+}
+
+#[tauri::command]
+pub async fn install_content(
+    instance_id: String,
+    plan: InstallPlan,
+    world: Option<String>,
+) -> LauncherResult<Vec<PathBuf>> {
+    let instance = InstanceStore::new().get(&instance_id)?;
+    install_plan(&instance.game_dir(), world.as_deref(), &plan).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::module::loader::fake_server;
+    use std::collections::HashMap;
+
+    fn version(id: &str, project: &str, loaders: &str, deps: &str) -> String {
+        format!(
+            r#"{{"id":"{id}","project_id":"{project}","name":"{id}","date_published":"2024-01-01",
+            "game_versions":["1.21.1"],"loaders":["{loaders}"],"dependencies":[{deps}],
+            "files":[{{"filename":"{id}.jar","url":"{{base}}/{id}.jar","primary":true}}]}}"#
+        )
+    }
+
+    #[tokio::test]
+    async fn test_resolve_dependencies() {
+        let root = version(
+            "a1",
+            "a",
+            "fabric",
+            r#"{"project_id":"b","dependency_type":"required"},
+               {"project_id":"c","dependency_type":"incompatible"},
+               {"project_id":"d","dependency_type":"optional"},
+               {"project_id":"e","dependency_type":"embedded"}"#,
+        );
+        let forge_only = version("b2", "b", "forge", "");
+        let fabric = version("b1", "b", "fabric", "");
+        let (base, server) = fake_server::serve(HashMap::from([
+            (
+                "/project/a/version".to_string(),
+                format!("[{}]", root).into_bytes(),
+            ),
+            (
+                "/project/b/version".to_string(),
+                format!("[{},{}]", forge_only, fabric).into_bytes(),
+            ),
+            ("/version/a1".to_string(), root.clone().into_bytes()),
+            ("/version/b1".to_string(), fabric.clone().into_bytes()),
+        ]))
+        .await;
+
+        let filter = ContentFilter {
+            game_version: Some("1.21.1".to_string()),
+            loader: Some("fabric".to_string()),
+        };
+        let resolver = |installed| {
+            DependencyResolver::with_apis(
+                ModrinthApi::with_base(base.clone()),
+                CurseForgeApi::with_base(base.clone(), Vec::new()),
+                filter.clone(),
+                installed,
+            )
+        };
+
+        let plan = resolver(Vec::new())
+            .resolve(
+                Platform::Modrinth,
+                ContentType::Mod,
+                "a".to_string(),
+                None,
+                false,
+            )
+            .await
+            .unwrap();
+        let ids: Vec<&str> = plan
+            .items
+            .iter()
+            .map(|i| i.version.version_id.as_str())
+            .collect();
+        assert_eq!(ids, ["a1", "b1"]);
+        assert_eq!(plan.items[1].required_by.as_deref(), Some("a"));
+        assert_eq!(plan.optional.len(), 1);
+        assert!(plan.can_install());
+
+        // 已安装不兼容的模组时拒绝安装
+        let installed = InstalledContent {
+            platform: Platform::Modrinth,
+            project_id: "c".to_string(),
+            version_id: "c1".to_string(),
+            content_type: ContentType::Mod,
+            path: "mods/c.jar".to_string(),
+            dependency: false,
+            incompatible: Vec::new(),
+        };
+        let plan = resolver(vec![installed])
+            .resolve(
+                Platform::Modrinth,
+                ContentType::Mod,
+                "a".to_string(),
+                None,
+                false,
+            )
+            .await
+            .unwrap();
+        assert_eq!(plan.conflicts.len(), 1);
+        assert!(!plan.can_install());
+
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn test_install_plan() {
+        let (base, server) = fake_server::serve(HashMap::from([
+            ("/b.jar".to_string(), b"b".to_vec()),
+            ("/pack.zip".to_string(), b"pack".to_vec()),
+        ]))
+        .await;
+        let dir = std::env::temp_dir().join(format!("rtl-install-plan-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("mods")).unwrap();
+        std::fs::write(dir.join("mods/a.jar"), b"a").unwrap();
+
+        let mut index = ContentIndex::load(&dir);
+        index.upsert(InstalledContent {
+            platform: Platform::Modrinth,
+            project_id: "a".to_string(),
+            version_id: "a1".to_string(),
+            content_type: ContentType::Mod,
+            path: "mods/a.jar".to_string(),
+            dependency: false,
+            incompatible: Vec::new(),
+        });
+        index.save().unwrap();
+
+        let item =
+            |project: &str, content_type, file: &str, replaces: Option<&str>| PlannedInstall {
+                content_type,
+                version: UnifiedVersion {
+                    platform: Platform::Modrinth,
+                    project_id: project.to_string(),
+                    version_id: format!("{}2", project),
+                    name: project.to_string(),
+                    date_published: None,
+                    game_versions: Vec::new(),
+                    loaders: Vec::new(),
+                    dependencies: Vec::new(),
+                    incompatible_reason: None,
+                },
+                file: UnifiedFile {
+                    platform: Platform::Modrinth,
+                    file_id: file.to_string(),
+                    display_name: file.to_string(),
+                    file_name: file.to_string(),
+                    download_url: format!("{}/{}", base, file),
+                    size: None,
+                    sha1: None,
+                    primary: true,
+                },
+                required_by: None,
+                replaces: replaces.map(str::to_string),
+            };
+        let plan = |items| InstallPlan {
+            items,
+            ..Default::default()
+        };
+
+        // 只能替换记录中的文件
+        for replaces in ["../outside.jar", "mods/unknown.jar"] {
+            let bad = plan(vec![item("a", ContentType::Mod, "b.jar", Some(replaces))]);
+            assert!(matches!(
+                install_plan(&dir, None, &bad).await,
+                Err(LauncherError::InvalidArgument(_))
+            ));
+        }
+        assert!(dir.join("mods/a.jar").exists());
+
+        // 数据包安装到指定存档，后面的文件下载失败时前面的记录仍会保存
+        let partial = plan(vec![
+            item("a", ContentType::Mod, "b.jar", Some("mods/a.jar")),
+            item("p", ContentType::DataPack, "pack.zip", None),
+            item("m", ContentType::Mod, "missing.jar", None),
+        ]);
+        assert!(install_plan(&dir, Some("World"), &partial).await.is_err());
+        assert!(!dir.join("mods/a.jar").exists());
+        assert!(dir.join("saves/World/datapacks/pack.zip").exists());
+        let index = ContentIndex::load(&dir);
+        assert_eq!(
+            index.find(Platform::Modrinth, "a").unwrap().path,
+            "mods/b.jar"
+        );
+        assert_eq!(
+            index.find(Platform::Modrinth, "p").unwrap().path,
+            "saves/World/datapacks/pack.zip"
+        );
+        assert!(index.find(Platform::Modrinth, "m").is_none());
+
+        server.abort();
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_track_untracked_mods() {
+        use sha1::Digest;
+
+        let dir = std::env::temp_dir().join(format!("rtl-untracked-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("mods")).unwrap();
+        std::fs::write(dir.join("mods/fabric-api.jar"), b"b-jar").unwrap();
+        std::fs::write(dir.join("mods/c.jar"), b"c-jar").unwrap();
+        std::fs::write(dir.join("mods/custom.jar"), b"custom").unwrap();
+        let sha1 = |bytes: &[u8]| format!("{:x}", sha1::Sha1::digest(bytes));
+
+        let root = version(
+            "a1",
+            "a",
+            "fabric",
+            r#"{"project_id":"b","dependency_type":"required"},
+               {"project_id":"c","dependency_type":"incompatible"}"#,
+        );
+        let (base, server) = fake_server::serve(HashMap::from([
+            (
+                "/version_files".to_string(),
+                format!(
+                    r#"{{"{}":{},"{}":{}}}"#,
+                    sha1(b"b-jar"),
+                    version("b1", "b", "fabric", ""),
+                    sha1(b"c-jar"),
+                    version("c1", "c", "fabric", "")
+                )
+                .into_bytes(),
+            ),
+            (
+                "/project/a/version".to_string(),
+                format!("[{}]", root).into_bytes(),
+            ),
+            ("/version/a1".to_string(), root.clone().into_bytes()),
+        ]))
+        .await;
+
+        let mut index = ContentIndex::load(&dir);
+        let mut resolver = DependencyResolver::with_apis(
+            ModrinthApi::with_base(base.clone()),
+            CurseForgeApi::with_base(base.clone(), Vec::new()),
+            ContentFilter {
+                game_version: Some("1.21.1".to_string()),
+                loader: Some("fabric".to_string()),
+            },
+            index.entries().to_vec(),
+        );
+        resolver.track_untracked(&mut index).await;
+        assert_eq!(
+            index.find(Platform::Modrinth, "b").unwrap().path,
+            "mods/fabric-api.jar"
+        );
+        assert_eq!(index.entries().len(), 2);
+
+        // 已有的依赖不再下载，与已安装模组的冲突会被发现
+        let plan = resolver
+            .resolve(
+                Platform::Modrinth,
+                ContentType::Mod,
+                "a".to_string(),
+                None,
+                false,
+            )
+            .await
+            .unwrap();
+        let ids: Vec<&str> = plan
+            .items
+            .iter()
+            .map(|i| i.version.version_id.as_str())
+            .collect();
+        assert_eq!(ids, ["a1"]);
+        assert_eq!(plan.conflicts.len(), 1);
+        assert_eq!(plan.conflicts[0].conflicts_with, "c");
+
+        server.abort();
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
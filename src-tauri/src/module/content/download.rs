@@ -0,0 +1,225 @@
+/*
+RTLauncher, a third-party Minecraft launcher built with the newest
+technology and provides innovative funtionalities
+Copyright (C) 2025 lutouna
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+// ***
+// 分段下载，服务器不支持 Range 时退回单线程下载
+// ***
+
+use super::http_client;
+use crate::module::download::verify::sha1_file;
+use crate::utils::error::{LauncherError, LauncherResult};
+use futures::future::try_join_all;
+use futures_util::StreamExt;
+use reqwest::header::{ACCEPT_RANGES, CONTENT_LENGTH, RANGE};
+use reqwest::StatusCode;
+use std::path::{Path, PathBuf};
+use tokio::io::AsyncWriteExt;
+
+// 分段数量
+const CHUNKS: u64 = 4;
+// 小于该大小的文件不分段
+const MIN_RANGE_SIZE: u64 = 1024 * 1024;
+
+// 把 [0, size) 分成若干个闭区间
+pub fn chunk_ranges(size: u64, chunks: u64) -> Vec<(u64, u64)> {
+    if size == 0 {
+        return Vec::new();
+    }
+    let chunks = chunks.clamp(1, size);
+    let step = size.div_ceil(chunks);
+    (0..size)
+        .step_by(step as usize)
+        .map(|start| (start, (start + step).min(size) - 1))
+        .collect()
+}
+
+// 服务器支持 Range 时返回文件大小
+async fn range_size(client: &reqwest::Client, url: &str) -> Option<u64> {
+    let response = client.head(url).send().await.ok()?;
+    if !response.status().is_success() {
+        return None;
+    }
+    let headers = response.headers();
+    let ranges = headers.get(ACCEPT_RANGES)?.to_str().ok()?;
+    if !ranges.eq_ignore_ascii_case("bytes") {
+        return None;
+    }
+    headers
+        .get(CONTENT_LENGTH)?
+        .to_str()
+        .ok()?
+        .parse()
+        .ok()
+        .filter(|size| *size > 0)
+}
+
+async fn download_chunk(
+    client: &reqwest::Client,
+    url: &str,
+    (start, end): (u64, u64),
+) -> LauncherResult<Vec<u8>> {
+    let response = client
+        .get(url)
+        .header(RANGE, format!("bytes={}-{}", start, end))
+        .send()
+        .await
+        .map_err(|e| LauncherError::network(url, e))?;
+    if response.status() != StatusCode::PARTIAL_CONTENT {
+        return Err(LauncherError::Network {
+            url: url.to_string(),
+            status: Some(response.status().as_u16()),
+            reason: "服务器没有返回分段内容".to_string(),
+        });
+    }
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|e| LauncherError::network(url, e))?;
+    if bytes.len() as u64 != end - start + 1 {
+        return Err(LauncherError::Network {
+            url: url.to_string(),
+            status: None,
+            reason: "分段大小不一致".to_string(),
+        });
+    }
+    Ok(bytes.to_vec())
+}
+
+async fn download_ranges(
+    client: &reqwest::Client,
+    url: &str,
+    part: &Path,
+    size: u64,
+) -> LauncherResult<()> {
+    let chunks = try_join_all(
+        chunk_ranges(size, CHUNKS)
+            .into_iter()
+            .map(|range| download_chunk(client, url, range)),
+    )
+    .await?;
+    let mut file = tokio::fs::File::create(part)
+        .await
+        .map_err(|e| LauncherError::io(part, e))?;
+    for chunk in chunks {
+        file.write_all(&chunk)
+            .await
+            .map_err(|e| LauncherError::io(part, e))?;
+    }
+    file.flush().await.map_err(|e| LauncherError::io(part, e))
+}
+
+async fn download_single(client: &reqwest::Client, url: &str, part: &Path) -> LauncherResult<()> {
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| LauncherError::network(url, e))?;
+    let mut file = tokio::fs::File::create(part)
+        .await
+        .map_err(|e| LauncherError::io(part, e))?;
+    let mut stream = response.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| LauncherError::network(url, e))?;
+        file.write_all(&chunk)
+            .await
+            .map_err(|e| LauncherError::io(part, e))?;
+    }
+    file.flush().await.map_err(|e| LauncherError::io(part, e))
+}
+
+fn part_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".part");
+    path.with_file_name(name)
+}
+
+// 先写入 .part 文件，校验通过后再移动到目标位置
+pub async fn range_download(url: &str, path: &Path, sha1: Option<&str>) -> LauncherResult<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| LauncherError::io(parent, e))?;
+    }
+    let client = http_client();
+    let part = part_path(path);
+
+    let ranged = match range_size(&client, url).await {
+        Some(size) if size >= MIN_RANGE_SIZE => {
+            match download_ranges(&client, url, &part, size).await {
+                Ok(()) => true,
+                Err(e) => {
+                    log::warn!("分段下载失败，改为单线程下载: {}", e);
+                    false
+                }
+            }
+        }
+        _ => false,
+    };
+    if !ranged {
+        download_single(&client, url, &part).await?;
+    }
+
+    if let Some(expected) = sha1 {
+        let actual = sha1_file(&part).map_err(|e| LauncherError::io(&part, e))?;
+        if !actual.eq_ignore_ascii_case(expected) {
+            let _ = std::fs::remove_file(&part);
+            return Err(LauncherError::HashMismatch {
+                path: path.display().to_string(),
+                expected: expected.to_string(),
+                actual,
+            });
+        }
+    }
+    std::fs::rename(&part, path).map_err(|e| LauncherError::io(path, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::module::loader::fake_server;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_chunk_ranges() {
+        assert_eq!(chunk_ranges(10, 4), vec![(0, 2), (3, 5), (6, 8), (9, 9)]);
+        assert_eq!(chunk_ranges(2, 4), vec![(0, 0), (1, 1)]);
+        assert!(chunk_ranges(0, 4).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_download_without_range_support() {
+        let (base, server) =
+            fake_server::serve(HashMap::from([("/mod.jar".to_string(), b"hello".to_vec())])).await;
+        let dir = std::env::temp_dir().join(format!("rtl-content-download-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let path = dir.join("mods").join("mod.jar");
+        let url = format!("{}/mod.jar", base);
+
+        let sha1 = "aaf4c61ddcc5e8a2dabede0f3b482cd9aea9434d";
+        range_download(&url, &path, Some(sha1)).await.unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"hello");
+
+        let mismatch = range_download(&url, &dir.join("bad.jar"), Some("00")).await;
+        assert!(matches!(mismatch, Err(LauncherError::HashMismatch { .. })));
+        assert!(!dir.join("bad.jar").exists());
+        assert!(!dir.join("bad.jar.part").exists());
+
+        server.abort();
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,161 @@
+/*
+RTLauncher, a third-party Minecraft launcher built with the newest
+technology and provides innovative funtionalities
+Copyright (C) 2025 lutouna
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+// ***
+// 按实例的游戏版本与加载器筛选资源
+// ***
+
+use super::{ContentType, UnifiedVersion};
+use crate::module::download::installed::resolve_version;
+use crate::module::download::paths::MinecraftPaths;
+use crate::module::instance::ins_main::Instance;
+use crate::module::loader::detect_loader;
+use crate::utils::error::LauncherResult;
+use serde::{Deserialize, Serialize};
+
+// 可以加载该加载器模组的加载器，Quilt 兼容 Fabric 模组
+pub fn compatible_loaders(loader: &str) -> Vec<String> {
+    let loader = loader.to_lowercase();
+    match loader.as_str() {
+        "quilt" => vec![loader, "fabric".to_string()],
+        _ => vec![loader],
+    }
+}
+
+// 筛选条件，为空的字段不参与筛选
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContentFilter {
+    pub game_version: Option<String>,
+    pub loader: Option<String>,
+}
+
+impl ContentFilter {
+    // 从版本 JSON 读取游戏版本和加载器，游戏版本是继承链最顶层的版本
+    pub fn for_version(paths: &MinecraftPaths, version_id: &str) -> LauncherResult<Self> {
+        let json = resolve_version(paths, version_id)?;
+        Ok(Self {
+            game_version: json["jar"].as_str().map(|jar| jar.to_string()),
+            loader: detect_loader(&json).map(|loader| loader.to_string()),
+        })
+    }
+
+    pub fn for_instance(instance: &Instance) -> LauncherResult<Self> {
+        Self::for_version(&MinecraftPaths::new(), &instance.version_id)
+    }
+
+    // 不兼容时返回原因，只有模组需要检查加载器
+    pub fn incompatible_reason(
+        &self,
+        content_type: ContentType,
+        version: &UnifiedVersion,
+    ) -> Option<String> {
+        if let Some(game_version) = &self.game_version {
+            if !version.game_versions.is_empty() && !version.game_versions.contains(game_version) {
+                return Some(format!("不支持 Minecraft {}", game_version));
+            }
+        }
+        if content_type != ContentType::Mod || version.loaders.is_empty() {
+            return None;
+        }
+        match &self.loader {
+            Some(loader) => {
+                let accepted = compatible_loaders(loader);
+                let supported = version
+                    .loaders
+                    .iter()
+                    .any(|l| accepted.contains(&l.to_lowercase()));
+                (!supported).then(|| format!("不支持 {} 加载器", loader))
+            }
+            None => Some("实例没有安装模组加载器".to_string()),
+        }
+    }
+
+    // 标记不兼容的版本，兼容的版本排在前面
+    pub fn flag(&self, content_type: ContentType, versions: &mut [UnifiedVersion]) {
+        for version in versions.iter_mut() {
+            version.incompatible_reason = self.incompatible_reason(content_type, version);
+        }
+        versions.sort_by_key(|version| version.incompatible_reason.is_some());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::module::content::Platform;
+
+    fn version(id: &str, game_versions: &[&str], loaders: &[&str]) -> UnifiedVersion {
+        UnifiedVersion {
+            platform: Platform::Modrinth,
+            project_id: "p".to_string(),
+            version_id: id.to_string(),
+            name: id.to_string(),
+            date_published: None,
+            game_versions: game_versions.iter().map(|v| v.to_string()).collect(),
+            loaders: loaders.iter().map(|v| v.to_string()).collect(),
+            dependencies: Vec::new(),
+            incompatible_reason: None,
+        }
+    }
+
+    #[test]
+    fn test_flag_incompatible_versions() {
+        let dir = std::env::temp_dir().join(format!("rtl-content-filter-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let paths = MinecraftPaths::with_base_dir(dir.clone());
+        let write = |id: &str, json: serde_json::Value| {
+            std::fs::create_dir_all(paths.get_version_dir(id)).unwrap();
+            std::fs::write(paths.get_version_json(id), json.to_string()).unwrap();
+        };
+        write(
+            "1.21.1",
+            serde_json::json!({ "id": "1.21.1", "libraries": [] }),
+        );
+        write(
+            "quilt-1.21.1",
+            serde_json::json!({
+                "id": "quilt-1.21.1",
+                "inheritsFrom": "1.21.1",
+                "libraries": [{ "name": "org.quiltmc:quilt-loader:0.27.1" }]
+            }),
+        );
+
+        let filter = ContentFilter::for_version(&paths, "quilt-1.21.1").unwrap();
+        assert_eq!(filter.game_version.as_deref(), Some("1.21.1"));
+        assert_eq!(filter.loader.as_deref(), Some("quilt"));
+
+        let mut versions = vec![
+            version("old", &["1.20.1"], &["fabric"]),
+            version("forge", &["1.21.1"], &["forge"]),
+            version("fabric", &["1.21.1"], &["fabric"]),
+        ];
+        filter.flag(ContentType::Mod, &mut versions);
+        assert_eq!(versions[0].version_id, "fabric");
+        assert!(versions[0].incompatible_reason.is_none());
+        assert!(versions[1].incompatible_reason.is_some());
+        assert!(versions[2].incompatible_reason.is_some());
+
+        // 资源包不检查加载器
+        let mut packs = vec![version("pack", &["1.21.1"], &["minecraft"])];
+        filter.flag(ContentType::ResourcePack, &mut packs);
+        assert!(packs[0].incompatible_reason.is_none());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
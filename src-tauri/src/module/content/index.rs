@@ -0,0 +1,119 @@
+/*
+RTLauncher, a third-party Minecraft launcher built with the newest
+technology and provides innovative funtionalities
+Copyright (C) 2025 lutouna
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+// ***
+// 实例中通过启动器安装的资源记录
+// ***
+
+use super::{ContentType, Platform};
+use crate::utils::error::{LauncherError, LauncherResult};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+// 保存在实例目录中的记录文件
+pub const INDEX_FILE: &str = "content.json";
+// 被禁用的文件后缀
+pub const DISABLED_SUFFIX: &str = ".disabled";
+
+// 一条安装记录，path 是相对于游戏目录的路径
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InstalledContent {
+    pub platform: Platform,
+    pub project_id: String,
+    pub version_id: String,
+    pub content_type: ContentType,
+    pub path: String,
+    // 作为其他资源的依赖安装
+    #[serde(default)]
+    pub dependency: bool,
+    // 该版本声明不兼容的项目
+    #[serde(default)]
+    pub incompatible: Vec<String>,
+}
+
+pub struct ContentIndex {
+    game_dir: PathBuf,
+    entries: Vec<InstalledContent>,
+}
+
+impl ContentIndex {
+    // 读取记录，去掉文件已经不存在的条目
+    pub fn load(game_dir: &Path) -> Self {
+        let entries: Vec<InstalledContent> = std::fs::read_to_string(game_dir.join(INDEX_FILE))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        let mut index = Self {
+            game_dir: game_dir.to_path_buf(),
+            entries,
+        };
+        index.entries.retain(|entry| {
+            let path = index.game_dir.join(&entry.path);
+            path.exists() || disabled_path(&path).exists()
+        });
+        index
+    }
+
+    pub fn save(&self) -> LauncherResult<()> {
+        std::fs::create_dir_all(&self.game_dir)
+            .map_err(|e| LauncherError::io(&self.game_dir, e))?;
+        let path = self.game_dir.join(INDEX_FILE);
+        std::fs::write(&path, serde_json::to_string_pretty(&self.entries)?)
+            .map_err(|e| LauncherError::io(&path, e))
+    }
+
+    pub fn game_dir(&self) -> &Path {
+        &self.game_dir
+    }
+
+    pub fn entries(&self) -> &[InstalledContent] {
+        &self.entries
+    }
+
+    pub fn find(&self, platform: Platform, project_id: &str) -> Option<&InstalledContent> {
+        self.entries
+            .iter()
+            .find(|entry| entry.platform == platform && entry.project_id == project_id)
+    }
+
+    // 同一项目只保留一条记录
+    pub fn upsert(&mut self, entry: InstalledContent) {
+        self.entries
+            .retain(|e| !(e.platform == entry.platform && e.project_id == entry.project_id));
+        self.entries.push(entry);
+    }
+
+    pub fn remove_path(&mut self, path: &str) {
+        self.entries.retain(|entry| entry.path != path);
+    }
+
+    // 把游戏目录中的文件转换为记录使用的相对路径
+    pub fn relative_path(&self, path: &Path) -> Option<String> {
+        path.strip_prefix(&self.game_dir)
+            .ok()
+            .map(|p| p.to_string_lossy().replace('\\', "/"))
+    }
+}
+
+// 文件被禁用后的路径
+pub fn disabled_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(DISABLED_SUFFIX);
+    path.with_file_name(name)
+}
@@ -0,0 +1,516 @@
+/*
+RTLauncher, a third-party Minecraft launcher built with the newest
+technology and provides innovative funtionalities
+Copyright (C) 2025 lutouna
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+// ***
+// 管理实例中的模组、资源包、光影包和数据包文件
+// ***
+
+use super::index::{disabled_path, ContentIndex, DISABLED_SUFFIX};
+use super::scanner::{read_bytes, read_entry};
+use super::{safe_file_name, ContentType};
+use crate::module::download::installed::resolve_version;
+use crate::module::download::paths::MinecraftPaths;
+use crate::module::instance::ins_main::InstanceStore;
+use crate::utils::error::{LauncherError, LauncherResult};
+use crate::Setting::config;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+// 资源包格式对应的版本范围 (起始版本, pack_format)，按版本从旧到新排列
+const RESOURCE_FORMATS: [((u32, u32), u32); 21] = [
+    ((6, 1), 1),
+    ((9, 0), 2),
+    ((11, 0), 3),
+    ((13, 0), 4),
+    ((15, 0), 5),
+    ((16, 2), 6),
+    ((17, 0), 7),
+    ((18, 0), 8),
+    ((19, 0), 9),
+    ((19, 3), 12),
+    ((19, 4), 13),
+    ((20, 0), 15),
+    ((20, 2), 18),
+    ((20, 3), 22),
+    ((20, 5), 32),
+    ((21, 0), 34),
+    ((21, 2), 42),
+    ((21, 4), 46),
+    ((21, 5), 55),
+    ((21, 6), 63),
+    ((21, 7), 64),
+];
+
+// 数据包格式对应的版本范围
+const DATA_FORMATS: [((u32, u32), u32); 18] = [
+    ((13, 0), 4),
+    ((15, 0), 5),
+    ((16, 2), 6),
+    ((17, 0), 7),
+    ((18, 0), 8),
+    ((18, 2), 9),
+    ((19, 0), 10),
+    ((19, 4), 12),
+    ((20, 0), 15),
+    ((20, 2), 18),
+    ((20, 3), 26),
+    ((20, 5), 41),
+    ((21, 0), 48),
+    ((21, 2), 57),
+    ((21, 4), 61),
+    ((21, 5), 71),
+    ((21, 6), 80),
+    ((21, 7), 81),
+];
+
+// 资源包或数据包的 pack.mcmeta 信息
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PackMeta {
+    pub description: String,
+    pub pack_format: Option<u32>,
+    // supported_formats 声明的范围
+    pub min_format: Option<u32>,
+    pub max_format: Option<u32>,
+    // pack.png 解压后的路径
+    pub icon: Option<PathBuf>,
+    // 实例版本要求的格式，未知版本为空
+    pub expected_format: Option<u32>,
+    pub compatible: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocalContent {
+    // 不带 .disabled 后缀的文件名
+    pub file_name: String,
+    pub enabled: bool,
+    pub directory: bool,
+    pub size: u64,
+    pub pack: Option<PackMeta>,
+}
+
+// 只处理 1.x.y 形式的正式版本号
+fn parse_release(version: &str) -> Option<(u32, u32)> {
+    let mut parts = version.split('.');
+    if parts.next()? != "1" {
+        return None;
+    }
+    let minor = parts.next()?.parse().ok()?;
+    let patch = match parts.next() {
+        Some(patch) => patch.parse().ok()?,
+        None => 0,
+    };
+    Some((minor, patch))
+}
+
+// 根据游戏版本查表得到 pack_format
+pub fn pack_format_for(game_version: &str, content_type: ContentType) -> Option<u32> {
+    let release = parse_release(game_version)?;
+    let table: &[((u32, u32), u32)] = match content_type {
+        ContentType::ResourcePack => &RESOURCE_FORMATS,
+        ContentType::DataPack => &DATA_FORMATS,
+        _ => return None,
+    };
+    table
+        .iter()
+        .rev()
+        .find(|(since, _)| release >= *since)
+        .map(|(_, format)| *format)
+}
+
+// 优先读取客户端 jar 中 version.json 的 pack_version，没有时查表
+pub fn expected_pack_format(
+    paths: &MinecraftPaths,
+    version_id: &str,
+    content_type: ContentType,
+) -> Option<u32> {
+    let json = resolve_version(paths, version_id).ok()?;
+    let jar_id = json["jar"].as_str()?;
+    let jar = paths
+        .get_version_dir(jar_id)
+        .join(format!("{}.jar", jar_id));
+    let from_jar = std::fs::File::open(jar)
+        .ok()
+        .and_then(|file| zip::ZipArchive::new(file).ok())
+        .and_then(|mut archive| read_entry(&mut archive, "version.json"))
+        .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+        .and_then(|version| {
+            let pack_version = &version["pack_version"];
+            let key = match content_type {
+                ContentType::DataPack => "data",
+                _ => "resource",
+            };
+            pack_version[key]
+                .as_u64()
+                .or_else(|| pack_version.as_u64())
+                .map(|format| format as u32)
+        });
+    from_jar.or_else(|| pack_format_for(jar_id, content_type))
+}
+
+// 描述可以是字符串或文本组件
+fn component_text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Array(items) => items.iter().map(component_text).collect(),
+        Value::Object(map) => {
+            let mut text = map.get("text").map(component_text).unwrap_or_default();
+            if let Some(extra) = map.get("extra") {
+                text.push_str(&component_text(extra));
+            }
+            text
+        }
+        _ => String::new(),
+    }
+}
+
+// supported_formats 可以是数字、[min, max] 或 { min_inclusive, max_inclusive }
+fn format_range(value: &Value) -> Option<(u32, u32)> {
+    match value {
+        Value::Number(n) => n.as_u64().map(|n| (n as u32, n as u32)),
+        Value::Array(range) => Some((
+            range.first()?.as_u64()? as u32,
+            range.get(1)?.as_u64()? as u32,
+        )),
+        Value::Object(_) => Some((
+            value["min_inclusive"].as_u64()? as u32,
+            value["max_inclusive"].as_u64()? as u32,
+        )),
+        _ => None,
+    }
+}
+
+pub fn parse_pack_meta(content: &str, expected_format: Option<u32>) -> Option<PackMeta> {
+    // 部分资源包的 pack.mcmeta 带有 BOM
+    let json: Value = serde_json::from_str(content.trim_start_matches('\u{feff}')).ok()?;
+    let pack = json.get("pack")?;
+    let pack_format = pack["pack_format"].as_u64().map(|f| f as u32);
+    let range = format_range(&pack["supported_formats"]);
+    let compatible = expected_format.and_then(|expected| match (range, pack_format) {
+        (Some((min, max)), _) => Some(min <= expected && expected <= max),
+        (None, Some(format)) => Some(format == expected),
+        (None, None) => None,
+    });
+    Some(PackMeta {
+        description: component_text(&pack["description"]),
+        pack_format,
+        min_format: range.map(|r| r.0),
+        max_format: range.map(|r| r.1),
+        icon: None,
+        expected_format,
+        compatible,
+    })
+}
+
+// 读取压缩包或文件夹中的 pack.mcmeta，pack.png 解压到 icon_path
+fn read_pack(path: &Path, expected_format: Option<u32>, icon_path: &Path) -> Option<PackMeta> {
+    let (meta, icon) = if path.is_dir() {
+        let meta = std::fs::read_to_string(path.join("pack.mcmeta")).ok()?;
+        (meta, std::fs::read(path.join("pack.png")).ok())
+    } else {
+        let mut archive = zip::ZipArchive::new(std::fs::File::open(path).ok()?).ok()?;
+        let meta = read_entry(&mut archive, "pack.mcmeta")?;
+        (meta, read_bytes(&mut archive, "pack.png"))
+    };
+    let mut pack = parse_pack_meta(&meta, expected_format)?;
+    if let Some(bytes) = icon {
+        let written = icon_path
+            .parent()
+            .is_some_and(|parent| std::fs::create_dir_all(parent).is_ok())
+            && std::fs::write(icon_path, bytes).is_ok();
+        pack.icon = written.then(|| icon_path.to_path_buf());
+    }
+    Some(pack)
+}
+
+// 去掉 .disabled 后缀
+fn split_disabled(name: &str) -> (&str, bool) {
+    match name.strip_suffix(DISABLED_SUFFIX) {
+        Some(name) => (name, false),
+        None => (name, true),
+    }
+}
+
+// 文件夹中的内容，模组只列出 jar / zip 文件
+pub fn list_local(
+    dir: &Path,
+    content_type: ContentType,
+    expected_format: Option<u32>,
+    icon_dir: &Path,
+) -> Vec<LocalContent> {
+    let mut items: Vec<LocalContent> = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            let (file_name, enabled) = split_disabled(&name);
+            let directory = path.is_dir();
+            let lower = file_name.to_lowercase();
+            let is_archive = lower.ends_with(".zip") || lower.ends_with(".jar");
+            if content_type == ContentType::Mod && (directory || !is_archive) {
+                return None;
+            }
+            if !directory && !is_archive {
+                return None;
+            }
+            let pack = match content_type {
+                ContentType::ResourcePack | ContentType::DataPack => read_pack(
+                    &path,
+                    expected_format,
+                    &icon_dir.join(format!("{}.png", file_name)),
+                ),
+                _ => None,
+            };
+            Some(LocalContent {
+                file_name: file_name.to_string(),
+                enabled,
+                directory,
+                size: entry.metadata().map(|m| m.len()).unwrap_or_default(),
+                pack,
+            })
+        })
+        .collect();
+    items.sort_by_key(|item| item.file_name.to_lowercase());
+    items
+}
+
+// 找到启用或禁用状态下实际存在的路径
+fn existing_path(dir: &Path, file_name: &str) -> LauncherResult<PathBuf> {
+    let path = dir.join(safe_file_name(file_name)?);
+    let disabled = disabled_path(&path);
+    if path.exists() {
+        Ok(path)
+    } else if disabled.exists() {
+        Ok(disabled)
+    } else {
+        Err(LauncherError::InvalidArgument(format!(
+            "文件不存在: {}",
+            file_name
+        )))
+    }
+}
+
+// 添加或去掉 .disabled 后缀
+pub fn set_enabled(dir: &Path, file_name: &str, enabled: bool) -> LauncherResult<PathBuf> {
+    let current = existing_path(dir, file_name)?;
+    let path = dir.join(safe_file_name(file_name)?);
+    let target = if enabled { path } else { disabled_path(&path) };
+    if current != target {
+        std::fs::rename(&current, &target).map_err(|e| LauncherError::io(&current, e))?;
+    }
+    Ok(target)
+}
+
+pub fn delete_local(dir: &Path, file_name: &str) -> LauncherResult<()> {
+    let path = existing_path(dir, file_name)?;
+    if path.is_dir() {
+        std::fs::remove_dir_all(&path).map_err(|e| LauncherError::io(&path, e))
+    } else {
+        std::fs::remove_file(&path).map_err(|e| LauncherError::io(&path, e))
+    }
+}
+
+// 复制本地文件到文件夹中，同名文件已存在时拒绝导入
+pub fn import_local(dir: &Path, source: &Path) -> LauncherResult<PathBuf> {
+    if !source.is_file() {
+        return Err(LauncherError::InvalidArgument(format!(
+            "文件不存在: {}",
+            source.display()
+        )));
+    }
+    let name = safe_file_name(&source.file_name().unwrap_or_default().to_string_lossy())?;
+    let target = dir.join(&name);
+    if target.exists() || disabled_path(&target).exists() {
+        return Err(LauncherError::InvalidArgument(format!(
+            "已存在同名文件: {}",
+            name
+        )));
+    }
+    std::fs::create_dir_all(dir).map_err(|e| LauncherError::io(dir, e))?;
+    std::fs::copy(source, &target).map_err(|e| LauncherError::io(&target, e))?;
+    Ok(target)
+}
+
+// 实例中资源所在的文件夹
+fn content_dir(
+    instance_id: &str,
+    content_type: ContentType,
+    world: Option<&str>,
+) -> LauncherResult<(PathBuf, PathBuf)> {
+    let game_dir = InstanceStore::new().get(instance_id)?.game_dir();
+    let dir = content_type.target_dir(&game_dir, world)?;
+    Ok((game_dir, dir))
+}
+
+#[tauri::command]
+pub fn list_local_content(
+    instance_id: String,
+    content_type: ContentType,
+    world: Option<String>,
+) -> LauncherResult<Vec<LocalContent>> {
+    let instance = InstanceStore::new().get(&instance_id)?;
+    let dir = content_type.target_dir(&instance.game_dir(), world.as_deref())?;
+    let expected = expected_pack_format(&MinecraftPaths::new(), &instance.version_id, content_type);
+    let icon_dir = config::cache_dir()
+        .join("pack-icons")
+        .join(&instance.id)
+        .join(content_type.folder());
+    Ok(list_local(&dir, content_type, expected, &icon_dir))
+}
+
+#[tauri::command]
+pub fn set_content_enabled(
+    instance_id: String,
+    content_type: ContentType,
+    world: Option<String>,
+    file_name: String,
+    enabled: bool,
+) -> LauncherResult<PathBuf> {
+    let (_, dir) = content_dir(&instance_id, content_type, world.as_deref())?;
+    set_enabled(&dir, &file_name, enabled)
+}
+
+#[tauri::command]
+pub fn delete_content(
+    instance_id: String,
+    content_type: ContentType,
+    world: Option<String>,
+    file_name: String,
+) -> LauncherResult<()> {
+    let (game_dir, dir) = content_dir(&instance_id, content_type, world.as_deref())?;
+    delete_local(&dir, &file_name)?;
+    let mut index = ContentIndex::load(&game_dir);
+    if let Some(path) = index.relative_path(&dir.join(&file_name)) {
+        index.remove_path(&path);
+        index.save()?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn import_content(
+    instance_id: String,
+    content_type: ContentType,
+    world: Option<String>,
+    source: PathBuf,
+) -> LauncherResult<PathBuf> {
+    let (_, dir) = content_dir(&instance_id, content_type, world.as_deref())?;
+    import_local(&dir, &source)
+}
+
+// 实例中的存档，用于选择数据包的安装位置
+#[tauri::command]
+pub fn list_worlds(instance_id: String) -> LauncherResult<Vec<String>> {
+    let game_dir = InstanceStore::new().get(&instance_id)?.game_dir();
+    let mut worlds: Vec<String> = std::fs::read_dir(game_dir.join("saves"))
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().join("level.dat").exists())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .collect();
+    worlds.sort();
+    Ok(worlds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::module::content::scanner::tests::write_jar;
+
+    #[test]
+    fn test_pack_format() {
+        assert_eq!(
+            pack_format_for("1.21.1", ContentType::ResourcePack),
+            Some(34)
+        );
+        assert_eq!(pack_format_for("1.20", ContentType::DataPack), Some(15));
+        assert_eq!(
+            pack_format_for("1.12.2", ContentType::ResourcePack),
+            Some(3)
+        );
+        assert_eq!(pack_format_for("24w14a", ContentType::ResourcePack), None);
+
+        let meta = parse_pack_meta(
+            r#"{"pack":{"pack_format":15,"supported_formats":[15,34],"description":{"text":"Faithful","extra":[" 32x"]}}}"#,
+            Some(34),
+        )
+        .unwrap();
+        assert_eq!(meta.description, "Faithful 32x");
+        assert_eq!(meta.compatible, Some(true));
+        let old = parse_pack_meta(
+            r#"{"pack":{"pack_format":3,"description":"old"}}"#,
+            Some(34),
+        )
+        .unwrap();
+        assert_eq!(old.compatible, Some(false));
+    }
+
+    #[test]
+    fn test_manage_local_files() {
+        let dir = std::env::temp_dir().join(format!("rtl-content-manage-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let packs = dir.join("resourcepacks");
+        std::fs::create_dir_all(&packs).unwrap();
+        let source = dir.join("Faithful.zip");
+        write_jar(
+            &source,
+            &[
+                (
+                    "pack.mcmeta",
+                    br#"{"pack":{"pack_format":34,"description":"Faithful"}}"#,
+                ),
+                ("pack.png", b"png"),
+            ],
+        );
+
+        let imported = import_local(&packs, &source).unwrap();
+        assert!(imported.exists());
+        assert!(import_local(&packs, &source).is_err());
+
+        let items = list_local(
+            &packs,
+            ContentType::ResourcePack,
+            Some(34),
+            &dir.join("icons"),
+        );
+        assert_eq!(items.len(), 1);
+        let pack = items[0].pack.as_ref().unwrap();
+        assert_eq!(pack.compatible, Some(true));
+        assert!(pack.icon.as_ref().unwrap().exists());
+
+        set_enabled(&packs, "Faithful.zip", false).unwrap();
+        assert!(packs.join("Faithful.zip.disabled").exists());
+        let items = list_local(
+            &packs,
+            ContentType::ResourcePack,
+            Some(34),
+            &dir.join("icons"),
+        );
+        assert!(!items[0].enabled);
+        assert_eq!(items[0].file_name, "Faithful.zip");
+
+        delete_local(&packs, "Faithful.zip").unwrap();
+        assert!(list_local(&packs, ContentType::ResourcePack, None, &dir.join("icons")).is_empty());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
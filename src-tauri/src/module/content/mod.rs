@@ -0,0 +1,441 @@
+/*
+RTLauncher, a third-party Minecraft launcher built with the newest
+technology and provides innovative funtionalities
+Copyright (C) 2025 lutouna
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+// ***
+// 模组、资源包与光影下载
+// ***
+
+pub mod cache;
+pub mod curseforge;
+pub mod dependency;
+pub mod download;
+pub mod filter;
+pub mod index;
+pub mod manage;
+pub mod modrinth;
+pub mod scanner;
+pub mod update;
+
+use crate::module::instance::ins_main::InstanceStore;
+use crate::utils::error::{LauncherError, LauncherResult};
+use cache::{MetadataCache, PROJECT_TTL, VERSIONS_TTL};
+use curseforge::CurseForgeApi;
+use filter::ContentFilter;
+use modrinth::ModrinthApi;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+// 请求 Modrinth / CurseForge 时使用的 User-Agent
+pub const USER_AGENT: &str = concat!("RTLauncher/", env!("CARGO_PKG_VERSION"));
+// 每个平台每页的默认数量
+pub const DEFAULT_PAGE_SIZE: u32 = 20;
+// 每页的最大数量
+const MAX_PAGE_SIZE: u32 = 50;
+
+// 资源来源平台
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Platform {
+    Modrinth,
+    CurseForge,
+}
+
+impl Platform {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Platform::Modrinth => "modrinth",
+            Platform::CurseForge => "curse_forge",
+        }
+    }
+}
+
+// 搜索时选择的平台
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContentSource {
+    Modrinth,
+    CurseForge,
+    #[default]
+    Both,
+}
+
+// 资源类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContentType {
+    Mod,
+    ResourcePack,
+    Shader,
+    DataPack,
+}
+
+impl ContentType {
+    // 游戏目录中存放该类资源的文件夹
+    pub fn folder(&self) -> &'static str {
+        match self {
+            ContentType::Mod => "mods",
+            ContentType::ResourcePack => "resourcepacks",
+            ContentType::Shader => "shaderpacks",
+            ContentType::DataPack => "datapacks",
+        }
+    }
+
+    // 下载目录，数据包放在存档的 datapacks 中
+    pub fn target_dir(&self, game_dir: &Path, world: Option<&str>) -> LauncherResult<PathBuf> {
+        match (self, world) {
+            (ContentType::DataPack, Some(world)) => Ok(game_dir
+                .join("saves")
+                .join(safe_file_name(world)?)
+                .join(self.folder())),
+            (ContentType::DataPack, None) => Err(LauncherError::InvalidArgument(
+                "数据包需要指定存档".to_string(),
+            )),
+            _ => Ok(game_dir.join(self.folder())),
+        }
+    }
+}
+
+// 两个平台统一的项目信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnifiedProject {
+    pub platform: Platform,
+    pub project_id: String,
+    pub slug: Option<String>,
+    pub name: String,
+    pub summary: String,
+    pub icon_url: Option<String>,
+    pub downloads: u64,
+}
+
+// 两个平台统一的版本信息，CurseForge 的一个文件就是一个版本
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnifiedVersion {
+    pub platform: Platform,
+    pub project_id: String,
+    pub version_id: String,
+    pub name: String,
+    pub date_published: Option<String>,
+    pub game_versions: Vec<String>,
+    pub loaders: Vec<String>,
+    #[serde(default)]
+    pub dependencies: Vec<ContentDependency>,
+    // 与实例不兼容的原因，兼容时为空
+    #[serde(default)]
+    pub incompatible_reason: Option<String>,
+}
+
+// 依赖关系类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DependencyKind {
+    Required,
+    Optional,
+    Incompatible,
+    // 已经打包在文件中，不需要单独安装
+    Embedded,
+}
+
+// 版本声明的依赖，Modrinth 可能只给出 version_id
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContentDependency {
+    pub kind: DependencyKind,
+    pub project_id: Option<String>,
+    pub version_id: Option<String>,
+}
+
+// 可下载的文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnifiedFile {
+    pub platform: Platform,
+    pub file_id: String,
+    pub display_name: String,
+    pub file_name: String,
+    pub download_url: String,
+    pub size: Option<u64>,
+    pub sha1: Option<String>,
+    pub primary: bool,
+}
+
+// 搜索条件，page 从 0 开始
+#[derive(Debug, Clone)]
+pub struct SearchQuery {
+    pub query: String,
+    pub content_type: ContentType,
+    pub game_version: Option<String>,
+    pub loader: Option<String>,
+    pub page: u32,
+    pub page_size: u32,
+}
+
+impl SearchQuery {
+    pub fn offset(&self) -> u64 {
+        self.page as u64 * self.page_size as u64
+    }
+}
+
+// 一页搜索结果，同时搜索两个平台时 total_hits 为两者之和
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ContentPage {
+    pub items: Vec<UnifiedProject>,
+    pub page: u32,
+    pub page_size: u32,
+    pub total_hits: u64,
+}
+
+// 创建带 User-Agent 的 HTTP 客户端
+pub(crate) fn http_client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .user_agent(USER_AGENT)
+        .build()
+        .unwrap_or_default()
+}
+
+// 版本按发布时间从新到旧排列，没有时间的放在最后
+pub fn sort_versions(versions: &mut [UnifiedVersion]) {
+    versions.sort_by(|a, b| match (&a.date_published, &b.date_published) {
+        (Some(date_a), Some(date_b)) => date_b.cmp(date_a),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => a.name.cmp(&b.name),
+    });
+}
+
+// 文件名中不能包含路径
+pub(crate) fn safe_file_name(name: &str) -> LauncherResult<String> {
+    let name = Path::new(name)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .filter(|n| !n.is_empty() && *n != "..")
+        .ok_or_else(|| LauncherError::InvalidArgument(format!("无效的文件名: {}", name)))?;
+    Ok(name.replace(['/', '\\'], "_"))
+}
+
+// 下载文件到游戏目录中对应的文件夹
+pub async fn download_to(
+    game_dir: &Path,
+    content_type: ContentType,
+    world: Option<&str>,
+    file: &UnifiedFile,
+) -> LauncherResult<PathBuf> {
+    if file.download_url.is_empty() {
+        return Err(LauncherError::Unsupported(format!(
+            "{} 没有下载地址，需要手动下载",
+            file.display_name
+        )));
+    }
+    let target = content_type
+        .target_dir(game_dir, world)?
+        .join(safe_file_name(&file.file_name)?);
+    download::range_download(&file.download_url, &target, file.sha1.as_deref()).await?;
+    log::info!("资源下载完成: {}", target.display());
+    Ok(target)
+}
+
+// 搜索两个平台，只有全部失败时才返回错误，搜索到的项目写入元数据缓存
+pub async fn search(search: &SearchQuery, source: ContentSource) -> LauncherResult<ContentPage> {
+    let mut page = ContentPage {
+        page: search.page,
+        page_size: search.page_size,
+        ..Default::default()
+    };
+    let mut last_error = None;
+    if source != ContentSource::CurseForge {
+        match ModrinthApi::new().search(search).await {
+            Ok(found) => {
+                page.items.extend(found.items);
+                page.total_hits += found.total_hits;
+            }
+            Err(e) => {
+                log::warn!("Modrinth 搜索失败: {}", e);
+                last_error = Some(e);
+            }
+        }
+    }
+    if source != ContentSource::Modrinth {
+        match CurseForgeApi::new().search(search).await {
+            Ok(found) => {
+                page.items.extend(found.items);
+                page.total_hits += found.total_hits;
+            }
+            Err(e) => {
+                log::warn!("CurseForge 搜索失败: {}", e);
+                last_error = Some(e);
+            }
+        }
+    }
+    if let Some(e) = last_error {
+        if page.items.is_empty() {
+            return Err(e);
+        }
+    }
+    let cache = MetadataCache::new();
+    for project in &page.items {
+        let _ = cache.put(&project_key(project.platform, &project.project_id), project);
+    }
+    Ok(page)
+}
+
+fn project_key(platform: Platform, project_id: &str) -> String {
+    format!("{}-project-{}", platform.as_str(), project_id)
+}
+
+// 项目的版本列表，带缓存
+pub async fn versions(
+    platform: Platform,
+    project_id: &str,
+    game_version: Option<&str>,
+) -> LauncherResult<Vec<UnifiedVersion>> {
+    let key = format!(
+        "{}-versions-{}-{}",
+        platform.as_str(),
+        project_id,
+        game_version.unwrap_or("all")
+    );
+    let mut versions = MetadataCache::new()
+        .load(&key, VERSIONS_TTL, || async {
+            match platform {
+                Platform::Modrinth => ModrinthApi::new().versions(project_id, game_version).await,
+                Platform::CurseForge => {
+                    CurseForgeApi::new()
+                        .versions(project_id, game_version)
+                        .await
+                }
+            }
+        })
+        .await?;
+    sort_versions(&mut versions);
+    Ok(versions)
+}
+
+fn search_query(
+    query: String,
+    content_type: ContentType,
+    filter: ContentFilter,
+    page: Option<u32>,
+    page_size: Option<u32>,
+) -> SearchQuery {
+    SearchQuery {
+        query,
+        content_type,
+        game_version: filter.game_version,
+        loader: filter.loader,
+        page: page.unwrap_or_default(),
+        page_size: page_size
+            .unwrap_or(DEFAULT_PAGE_SIZE)
+            .clamp(1, MAX_PAGE_SIZE),
+    }
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn search_content(
+    query: String,
+    content_type: ContentType,
+    source: Option<ContentSource>,
+    game_version: Option<String>,
+    loader: Option<String>,
+    page: Option<u32>,
+    page_size: Option<u32>,
+) -> LauncherResult<ContentPage> {
+    let filter = ContentFilter {
+        game_version,
+        loader,
+    };
+    let search_query = search_query(query, content_type, filter, page, page_size);
+    search(&search_query, source.unwrap_or_default()).await
+}
+
+// 在实例中浏览资源，自动使用实例的游戏版本和加载器筛选
+#[tauri::command]
+pub async fn search_instance_content(
+    instance_id: String,
+    query: String,
+    content_type: ContentType,
+    source: Option<ContentSource>,
+    page: Option<u32>,
+    page_size: Option<u32>,
+) -> LauncherResult<ContentPage> {
+    let instance = InstanceStore::new().get(&instance_id)?;
+    let filter = ContentFilter::for_instance(&instance)?;
+    let search_query = search_query(query, content_type, filter, page, page_size);
+    search(&search_query, source.unwrap_or_default()).await
+}
+
+#[tauri::command]
+pub async fn get_content_project(
+    platform: Platform,
+    project_id: String,
+) -> LauncherResult<UnifiedProject> {
+    MetadataCache::new()
+        .load(&project_key(platform, &project_id), PROJECT_TTL, || async {
+            match platform {
+                Platform::Modrinth => ModrinthApi::new().project(&project_id).await,
+                Platform::CurseForge => CurseForgeApi::new().project(&project_id).await,
+            }
+        })
+        .await
+}
+
+#[tauri::command]
+pub async fn get_content_versions(
+    platform: Platform,
+    project_id: String,
+    game_version: Option<String>,
+) -> LauncherResult<Vec<UnifiedVersion>> {
+    versions(platform, &project_id, game_version.as_deref()).await
+}
+
+// 列出项目的所有版本，并标记与实例不兼容的版本
+#[tauri::command]
+pub async fn get_instance_content_versions(
+    instance_id: String,
+    content_type: ContentType,
+    platform: Platform,
+    project_id: String,
+) -> LauncherResult<Vec<UnifiedVersion>> {
+    let instance = InstanceStore::new().get(&instance_id)?;
+    let filter = ContentFilter::for_instance(&instance)?;
+    let mut versions = versions(platform, &project_id, None).await?;
+    filter.flag(content_type, &mut versions);
+    Ok(versions)
+}
+
+#[tauri::command]
+pub async fn get_content_files(
+    platform: Platform,
+    project_id: String,
+    version_id: String,
+) -> LauncherResult<Vec<UnifiedFile>> {
+    match platform {
+        Platform::Modrinth => ModrinthApi::new().files(&version_id).await,
+        Platform::CurseForge => CurseForgeApi::new().files(&project_id, &version_id).await,
+    }
+}
+
+// 下载到实例目录下的 mods / resourcepacks / shaderpacks，数据包需要指定存档
+#[tauri::command]
+pub async fn download_content(
+    instance_id: String,
+    content_type: ContentType,
+    file: UnifiedFile,
+    world: Option<String>,
+) -> LauncherResult<PathBuf> {
+    let instance = InstanceStore::new().get(&instance_id)?;
+    download_to(&instance.game_dir(), content_type, world.as_deref(), &file).await
+}
@@ -0,0 +1,398 @@
+/*
+RTLauncher, a third-party Minecraft launcher built with the newest
+technology and provides innovative funtionalities
+Copyright (C) 2025 lutouna
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+// ***
+// Modrinth API
+// ***
+
+use super::filter::compatible_loaders;
+use super::{
+    http_client, ContentDependency, ContentPage, ContentType, DependencyKind, Platform,
+    SearchQuery, UnifiedFile, UnifiedProject, UnifiedVersion,
+};
+use crate::utils::error::{LauncherError, LauncherResult};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+pub const MODRINTH_API_URL: &str = "https://api.modrinth.com/v2";
+
+#[derive(Debug, Deserialize)]
+struct SearchResponse {
+    #[serde(default)]
+    hits: Vec<SearchHit>,
+    #[serde(default)]
+    total_hits: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchHit {
+    project_id: String,
+    slug: Option<String>,
+    title: String,
+    description: Option<String>,
+    icon_url: Option<String>,
+    #[serde(default)]
+    downloads: u64,
+}
+
+impl SearchHit {
+    fn unified(self) -> UnifiedProject {
+        UnifiedProject {
+            platform: Platform::Modrinth,
+            project_id: self.project_id,
+            slug: self.slug,
+            name: self.title,
+            summary: self.description.unwrap_or_default(),
+            icon_url: self.icon_url,
+            downloads: self.downloads,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ModrinthProject {
+    id: String,
+    slug: Option<String>,
+    title: String,
+    description: Option<String>,
+    icon_url: Option<String>,
+    #[serde(default)]
+    downloads: u64,
+}
+
+impl ModrinthProject {
+    fn unified(self) -> UnifiedProject {
+        SearchHit {
+            project_id: self.id,
+            slug: self.slug,
+            title: self.title,
+            description: self.description,
+            icon_url: self.icon_url,
+            downloads: self.downloads,
+        }
+        .unified()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct ModrinthVersion {
+    pub id: String,
+    pub project_id: String,
+    pub name: Option<String>,
+    pub version_number: Option<String>,
+    pub date_published: Option<String>,
+    #[serde(default)]
+    pub game_versions: Vec<String>,
+    #[serde(default)]
+    pub loaders: Vec<String>,
+    #[serde(default)]
+    pub files: Vec<ModrinthFile>,
+    #[serde(default)]
+    pub dependencies: Vec<ModrinthDependency>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct ModrinthDependency {
+    pub version_id: Option<String>,
+    pub project_id: Option<String>,
+    pub dependency_type: String,
+}
+
+impl ModrinthDependency {
+    fn unified(&self) -> Option<ContentDependency> {
+        let kind = match self.dependency_type.as_str() {
+            "required" => DependencyKind::Required,
+            "optional" => DependencyKind::Optional,
+            "incompatible" => DependencyKind::Incompatible,
+            "embedded" => DependencyKind::Embedded,
+            _ => return None,
+        };
+        Some(ContentDependency {
+            kind,
+            project_id: self.project_id.clone(),
+            version_id: self.version_id.clone(),
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct ModrinthFile {
+    pub filename: String,
+    pub url: String,
+    #[serde(default)]
+    pub primary: bool,
+    pub size: Option<u64>,
+    #[serde(default)]
+    pub hashes: HashMap<String, String>,
+}
+
+impl ModrinthVersion {
+    pub(crate) fn unified(&self) -> UnifiedVersion {
+        UnifiedVersion {
+            platform: Platform::Modrinth,
+            project_id: self.project_id.clone(),
+            version_id: self.id.clone(),
+            name: self
+                .name
+                .clone()
+                .or_else(|| self.version_number.clone())
+                .unwrap_or_else(|| self.id.clone()),
+            date_published: self.date_published.clone(),
+            game_versions: self.game_versions.clone(),
+            loaders: self.loaders.clone(),
+            dependencies: self
+                .dependencies
+                .iter()
+                .filter_map(ModrinthDependency::unified)
+                .collect(),
+            incompatible_reason: None,
+        }
+    }
+
+    pub(crate) fn unified_files(&self) -> Vec<UnifiedFile> {
+        self.files
+            .iter()
+            .map(|file| UnifiedFile {
+                platform: Platform::Modrinth,
+                file_id: file.filename.clone(),
+                display_name: file.filename.clone(),
+                file_name: file.filename.clone(),
+                download_url: file.url.clone(),
+                size: file.size,
+                sha1: file.hashes.get("sha1").cloned(),
+                primary: file.primary,
+            })
+            .collect()
+    }
+}
+
+// Modrinth 中的项目类型
+fn project_type(content_type: ContentType) -> &'static str {
+    match content_type {
+        ContentType::Mod => "mod",
+        ContentType::ResourcePack => "resourcepack",
+        ContentType::Shader => "shader",
+        ContentType::DataPack => "datapack",
+    }
+}
+
+pub struct ModrinthApi {
+    base: String,
+    client: reqwest::Client,
+}
+
+impl Default for ModrinthApi {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ModrinthApi {
+    pub fn new() -> Self {
+        Self::with_base(MODRINTH_API_URL.to_string())
+    }
+
+    pub fn with_base(base: String) -> Self {
+        Self {
+            base: base.trim_end_matches('/').to_string(),
+            client: http_client(),
+        }
+    }
+
+    async fn get<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, String)],
+    ) -> LauncherResult<T> {
+        let url = format!("{}{}", self.base, path);
+        let response = self
+            .client
+            .get(&url)
+            .query(query)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| LauncherError::network(&url, e))?;
+        response
+            .json::<T>()
+            .await
+            .map_err(|e| LauncherError::Parse(format!("{}: {}", url, e)))
+    }
+
+    async fn post<T: DeserializeOwned>(&self, path: &str, body: &Value) -> LauncherResult<T> {
+        let url = format!("{}{}", self.base, path);
+        let response = self
+            .client
+            .post(&url)
+            .json(body)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| LauncherError::network(&url, e))?;
+        response
+            .json::<T>()
+            .await
+            .map_err(|e| LauncherError::Parse(format!("{}: {}", url, e)))
+    }
+
+    // 按哈希查找文件所属的版本，algorithm 为 sha1 或 sha512
+    pub(crate) async fn versions_by_hash(
+        &self,
+        hashes: &[String],
+        algorithm: &str,
+    ) -> LauncherResult<HashMap<String, ModrinthVersion>> {
+        self.post(
+            "/version_files",
+            &json!({ "hashes": hashes, "algorithm": algorithm }),
+        )
+        .await
+    }
+
+    // 按哈希查找符合游戏版本和加载器的最新版本
+    pub(crate) async fn latest_by_hash(
+        &self,
+        hashes: &[String],
+        algorithm: &str,
+        loaders: &[String],
+        game_versions: &[String],
+    ) -> LauncherResult<HashMap<String, ModrinthVersion>> {
+        self.post(
+            "/version_files/update",
+            &json!({
+                "hashes": hashes,
+                "algorithm": algorithm,
+                "loaders": loaders,
+                "game_versions": game_versions,
+            }),
+        )
+        .await
+    }
+
+    // 搜索项目，加载器只用于筛选模组
+    pub async fn search(&self, search: &SearchQuery) -> LauncherResult<ContentPage> {
+        let mut facets = vec![vec![format!(
+            "project_type:{}",
+            project_type(search.content_type)
+        )]];
+        if let Some(version) = &search.game_version {
+            facets.push(vec![format!("versions:{}", version)]);
+        }
+        if let (ContentType::Mod, Some(loader)) = (search.content_type, &search.loader) {
+            facets.push(
+                compatible_loaders(loader)
+                    .iter()
+                    .map(|loader| format!("categories:{}", loader))
+                    .collect(),
+            );
+        }
+        let response: SearchResponse = self
+            .get(
+                "/search",
+                &[
+                    ("query", search.query.clone()),
+                    ("offset", search.offset().to_string()),
+                    ("limit", search.page_size.to_string()),
+                    ("facets", json!(facets).to_string()),
+                ],
+            )
+            .await?;
+        Ok(ContentPage {
+            items: response.hits.into_iter().map(SearchHit::unified).collect(),
+            page: search.page,
+            page_size: search.page_size,
+            total_hits: response.total_hits,
+        })
+    }
+
+    pub async fn project(&self, project_id: &str) -> LauncherResult<UnifiedProject> {
+        let project: ModrinthProject = self.get(&format!("/project/{}", project_id), &[]).await?;
+        Ok(project.unified())
+    }
+
+    // 项目的所有版本，可按游戏版本筛选
+    pub async fn versions(
+        &self,
+        project_id: &str,
+        game_version: Option<&str>,
+    ) -> LauncherResult<Vec<UnifiedVersion>> {
+        let mut query = Vec::new();
+        if let Some(version) = game_version {
+            query.push(("game_versions", json!([version]).to_string()));
+        }
+        let versions: Vec<ModrinthVersion> = self
+            .get(&format!("/project/{}/version", project_id), &query)
+            .await?;
+        Ok(versions.iter().map(|v| v.unified()).collect())
+    }
+
+    // 版本中的文件
+    pub async fn version(&self, version_id: &str) -> LauncherResult<UnifiedVersion> {
+        let version: ModrinthVersion = self.get(&format!("/version/{}", version_id), &[]).await?;
+        Ok(version.unified())
+    }
+
+    pub async fn files(&self, version_id: &str) -> LauncherResult<Vec<UnifiedFile>> {
+        let version: ModrinthVersion = self.get(&format!("/version/{}", version_id), &[]).await?;
+        Ok(version.unified_files())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::module::loader::fake_server;
+
+    #[tokio::test]
+    async fn test_modrinth_search_and_files() {
+        let version = r#"{"id":"v1","project_id":"AANobbMI","name":"Sodium 0.6","date_published":"2024-12-01T00:00:00Z",
+            "game_versions":["1.21.4"],"loaders":["fabric"],
+            "files":[{"filename":"sodium.jar","url":"https://cdn.modrinth.com/sodium.jar","primary":true,"size":10,"hashes":{"sha1":"abc"}}]}"#;
+        let (base, server) = fake_server::serve(HashMap::from([
+            (
+                "/search?query=sodium&offset=20&limit=20&facets=%5B%5B%22project_type%3Amod%22%5D%2C%5B%22categories%3Aquilt%22%2C%22categories%3Afabric%22%5D%5D".to_string(),
+                br#"{"hits":[{"project_id":"AANobbMI","slug":"sodium","title":"Sodium","description":"fast","downloads":5}],"total_hits":21}"#.to_vec(),
+            ),
+            ("/version/v1".to_string(), version.as_bytes().to_vec()),
+        ]))
+        .await;
+
+        let api = ModrinthApi::with_base(base);
+        let page = api
+            .search(&SearchQuery {
+                query: "sodium".to_string(),
+                content_type: ContentType::Mod,
+                game_version: None,
+                loader: Some("quilt".to_string()),
+                page: 1,
+                page_size: 20,
+            })
+            .await
+            .unwrap();
+        assert_eq!(page.total_hits, 21);
+        assert_eq!(page.items[0].name, "Sodium");
+        let files = api.files("v1").await.unwrap();
+        assert_eq!(files[0].sha1.as_deref(), Some("abc"));
+        assert!(files[0].primary);
+        assert!(api.files("missing").await.is_err());
+
+        server.abort();
+    }
+}
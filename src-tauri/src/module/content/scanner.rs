@@ -0,0 +1,662 @@
+/*
+RTLauncher, a third-party Minecraft launcher built with the newest
+technology and provides innovative funtionalities
+Copyright (C) 2025 lutouna
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+// ***
+// 扫描 mods 目录，读取 jar 中的模组信息
+// ***
+
+use super::filter::{compatible_loaders, ContentFilter};
+use super::index::DISABLED_SUFFIX;
+use crate::module::instance::ins_main::{Instance, InstanceStore};
+use crate::utils::error::{LauncherError, LauncherResult};
+use crate::Setting::config;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::io::{Cursor, Read, Seek};
+use std::path::{Path, PathBuf};
+
+// 由加载器或游戏本身提供的模组 id，不算缺失的依赖
+const BUILTIN_IDS: [&str; 10] = [
+    "minecraft",
+    "java",
+    "fabricloader",
+    "fabric-loader",
+    "quilt_loader",
+    "forge",
+    "neoforge",
+    "fml",
+    "mcp",
+    "javafml",
+];
+
+// 模组声明的依赖
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModDependencyInfo {
+    pub id: String,
+    pub version: Option<String>,
+    pub required: bool,
+}
+
+// 从 jar 中读取的模组信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModMetadata {
+    pub file_name: String,
+    pub enabled: bool,
+    pub id: String,
+    pub name: String,
+    pub version: Option<String>,
+    pub authors: Vec<String>,
+    pub description: Option<String>,
+    // 图标在 jar 中的路径
+    pub icon: Option<String>,
+    pub loader: String,
+    pub dependencies: Vec<ModDependencyInfo>,
+    // 同一个 jar 中附带的其他模组 id
+    pub provides: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModIssueKind {
+    Duplicate,
+    MissingDependency,
+    LoaderMismatch,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModIssue {
+    pub kind: ModIssueKind,
+    pub file_name: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ModScanReport {
+    pub mods: Vec<ModMetadata>,
+    // 无法识别的文件
+    pub unreadable: Vec<String>,
+    pub issues: Vec<ModIssue>,
+}
+
+pub(crate) fn read_entry<R: Read + Seek>(
+    archive: &mut zip::ZipArchive<R>,
+    name: &str,
+) -> Option<String> {
+    let mut entry = archive.by_name(name).ok()?;
+    let mut content = String::new();
+    entry.read_to_string(&mut content).ok()?;
+    Some(content)
+}
+
+pub(crate) fn read_bytes<R: Read + Seek>(
+    archive: &mut zip::ZipArchive<R>,
+    name: &str,
+) -> Option<Vec<u8>> {
+    let mut entry = archive.by_name(name.trim_start_matches('/')).ok()?;
+    let mut bytes = Vec::new();
+    entry.read_to_end(&mut bytes).ok()?;
+    Some(bytes)
+}
+
+fn strings(value: &Value) -> Vec<String> {
+    match value {
+        Value::String(s) => vec![s.clone()],
+        Value::Array(items) => items
+            .iter()
+            .filter_map(|item| match item {
+                Value::String(s) => Some(s.clone()),
+                _ => item["name"]
+                    .as_str()
+                    .or(item["id"].as_str())
+                    .map(|s| s.to_string()),
+            })
+            .collect(),
+        Value::Object(map) => map.keys().cloned().collect(),
+        _ => Vec::new(),
+    }
+}
+
+// fabric.mod.json 的图标可以是按尺寸区分的对象，取最大的一个
+fn fabric_icon(value: &Value) -> Option<String> {
+    match value {
+        Value::String(icon) => Some(icon.clone()),
+        Value::Object(map) => map
+            .iter()
+            .max_by_key(|(size, _)| size.parse::<u32>().unwrap_or_default())
+            .and_then(|(_, icon)| icon.as_str().map(|s| s.to_string())),
+        _ => None,
+    }
+}
+
+// depends 等字段是 id 到版本要求的映射
+fn fabric_dependencies(json: &Value, key: &str, required: bool) -> Vec<ModDependencyInfo> {
+    json[key]
+        .as_object()
+        .into_iter()
+        .flatten()
+        .map(|(id, version)| ModDependencyInfo {
+            id: id.clone(),
+            version: strings(version).first().cloned(),
+            required,
+        })
+        .collect()
+}
+
+// 读取 jar-in-jar 中模组的 id，只读取一层
+fn nested_ids<R: Read + Seek>(archive: &mut zip::ZipArchive<R>, jars: &[String]) -> Vec<String> {
+    let mut ids = Vec::new();
+    for jar in jars {
+        let Some(bytes) = read_bytes(archive, jar) else {
+            continue;
+        };
+        let Ok(mut nested) = zip::ZipArchive::new(Cursor::new(bytes)) else {
+            continue;
+        };
+        if let Some(json) = read_entry(&mut nested, "fabric.mod.json")
+            .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+        {
+            ids.extend(json["id"].as_str().map(|s| s.to_string()));
+            ids.extend(strings(&json["provides"]));
+        }
+        if let Some(json) = read_entry(&mut nested, "quilt.mod.json")
+            .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+        {
+            ids.extend(json["quilt_loader"]["id"].as_str().map(|s| s.to_string()));
+        }
+    }
+    ids
+}
+
+fn parse_fabric<R: Read + Seek>(
+    archive: &mut zip::ZipArchive<R>,
+    json: &Value,
+) -> Option<ModMetadata> {
+    let id = json["id"].as_str()?.to_string();
+    let jars: Vec<String> = json["jars"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|jar| jar["file"].as_str().map(|s| s.to_string()))
+        .collect();
+    let mut provides = strings(&json["provides"]);
+    provides.extend(nested_ids(archive, &jars));
+    let mut dependencies = fabric_dependencies(json, "depends", true);
+    dependencies.extend(fabric_dependencies(json, "recommends", false));
+    Some(ModMetadata {
+        file_name: String::new(),
+        enabled: true,
+        name: json["name"].as_str().unwrap_or(&id).to_string(),
+        id,
+        version: json["version"].as_str().map(|s| s.to_string()),
+        authors: strings(&json["authors"]),
+        description: json["description"].as_str().map(|s| s.to_string()),
+        icon: fabric_icon(&json["icon"]),
+        loader: "fabric".to_string(),
+        dependencies,
+        provides,
+    })
+}
+
+fn parse_quilt<R: Read + Seek>(
+    archive: &mut zip::ZipArchive<R>,
+    json: &Value,
+) -> Option<ModMetadata> {
+    let loader = &json["quilt_loader"];
+    let id = loader["id"].as_str()?.to_string();
+    let metadata = &loader["metadata"];
+    let dependencies = loader["depends"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|dep| match dep {
+            Value::String(id) => Some(ModDependencyInfo {
+                id: id.clone(),
+                version: None,
+                required: true,
+            }),
+            _ => Some(ModDependencyInfo {
+                id: dep["id"].as_str()?.to_string(),
+                version: strings(&dep["versions"]).first().cloned(),
+                required: !dep["optional"].as_bool().unwrap_or(false),
+            }),
+        })
+        .collect();
+    let jars = strings(&loader["jars"]);
+    let mut provides = strings(&loader["provides"]);
+    provides.extend(nested_ids(archive, &jars));
+    Some(ModMetadata {
+        file_name: String::new(),
+        enabled: true,
+        name: metadata["name"].as_str().unwrap_or(&id).to_string(),
+        id,
+        version: loader["version"].as_str().map(|s| s.to_string()),
+        authors: strings(&metadata["contributors"]),
+        description: metadata["description"].as_str().map(|s| s.to_string()),
+        icon: fabric_icon(&metadata["icon"]),
+        loader: "quilt".to_string(),
+        dependencies,
+        provides,
+    })
+}
+
+// MANIFEST.MF 中的 Implementation-Version，替换 ${file.jarVersion}
+fn manifest_version<R: Read + Seek>(archive: &mut zip::ZipArchive<R>) -> Option<String> {
+    read_entry(archive, "META-INF/MANIFEST.MF")?
+        .lines()
+        .find_map(|line| line.strip_prefix("Implementation-Version:"))
+        .map(|version| version.trim().to_string())
+}
+
+fn parse_mods_toml<R: Read + Seek>(
+    archive: &mut zip::ZipArchive<R>,
+    content: &str,
+    neoforge: bool,
+) -> Option<ModMetadata> {
+    let toml: toml::Table = content.parse().ok()?;
+    let mods = toml.get("mods")?.as_array()?;
+    let first = mods.first()?;
+    let id = first.get("modId")?.as_str()?.to_string();
+    let text = |value: &toml::Value, key: &str| {
+        value
+            .get(key)
+            .and_then(|v| v.as_str())
+            .map(|s| s.trim().to_string())
+    };
+
+    let mut dependencies = Vec::new();
+    let mut is_neoforge = neoforge;
+    for dep in toml
+        .get("dependencies")
+        .and_then(|deps| deps.get(&id))
+        .and_then(|deps| deps.as_array())
+        .into_iter()
+        .flatten()
+    {
+        let Some(dep_id) = text(dep, "modId") else {
+            continue;
+        };
+        is_neoforge |= dep_id == "neoforge";
+        // 旧格式使用 mandatory，新格式使用 type
+        let required = match text(dep, "type") {
+            Some(kind) => kind.eq_ignore_ascii_case("required"),
+            None => dep
+                .get("mandatory")
+                .and_then(|m| m.as_bool())
+                .unwrap_or(true),
+        };
+        let kind = text(dep, "type").unwrap_or_default().to_lowercase();
+        if kind == "incompatible" || kind == "discouraged" {
+            continue;
+        }
+        dependencies.push(ModDependencyInfo {
+            id: dep_id,
+            version: text(dep, "versionRange"),
+            required,
+        });
+    }
+
+    let version = text(first, "version").and_then(|version| {
+        if version.contains("${file.jarVersion}") {
+            manifest_version(archive)
+        } else {
+            Some(version)
+        }
+    });
+    Some(ModMetadata {
+        file_name: String::new(),
+        enabled: true,
+        name: text(first, "displayName").unwrap_or_else(|| id.clone()),
+        version,
+        authors: text(first, "authors")
+            .map(|authors| {
+                authors
+                    .split(',')
+                    .map(|a| a.trim().to_string())
+                    .filter(|a| !a.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default(),
+        description: text(first, "description"),
+        // 旧格式的 logoFile 写在顶层
+        icon: text(first, "logoFile").or_else(|| {
+            toml.get("logoFile")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string())
+        }),
+        loader: if is_neoforge { "neoforge" } else { "forge" }.to_string(),
+        dependencies,
+        provides: mods
+            .iter()
+            .skip(1)
+            .filter_map(|m| text(m, "modId"))
+            .collect(),
+        id,
+    })
+}
+
+// 1.12 及更早的 mcmod.info，可能是数组或 { modList: [...] }
+fn parse_mcmod_info(content: &str) -> Option<ModMetadata> {
+    let json: Value = serde_json::from_str(content).ok()?;
+    let list = json.as_array().or(json["modList"].as_array())?;
+    let first = list.first()?;
+    let id = first["modid"].as_str()?.to_string();
+    let mut dependencies: Vec<ModDependencyInfo> = strings(&first["requiredMods"])
+        .into_iter()
+        .map(|id| ModDependencyInfo {
+            id,
+            version: None,
+            required: true,
+        })
+        .collect();
+    dependencies.dedup();
+    Some(ModMetadata {
+        file_name: String::new(),
+        enabled: true,
+        name: first["name"].as_str().unwrap_or(&id).to_string(),
+        id,
+        version: first["version"].as_str().map(|s| s.to_string()),
+        authors: strings(&first["authorList"]),
+        description: first["description"].as_str().map(|s| s.to_string()),
+        icon: first["logoFile"]
+            .as_str()
+            .filter(|s| !s.is_empty())
+            .map(|s| s.to_string()),
+        loader: "forge".to_string(),
+        dependencies,
+        provides: list
+            .iter()
+            .skip(1)
+            .filter_map(|m| m["modid"].as_str().map(|s| s.to_string()))
+            .collect(),
+    })
+}
+
+// 读取一个 jar 的模组信息，按 Quilt、Fabric、NeoForge、Forge、旧版 Forge 的顺序识别
+pub fn read_mod<R: Read + Seek>(reader: R) -> Option<ModMetadata> {
+    let mut archive = zip::ZipArchive::new(reader).ok()?;
+    if let Some(json) = read_entry(&mut archive, "quilt.mod.json")
+        .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+    {
+        if let Some(metadata) = parse_quilt(&mut archive, &json) {
+            return Some(metadata);
+        }
+    }
+    if let Some(json) = read_entry(&mut archive, "fabric.mod.json")
+        .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+    {
+        if let Some(metadata) = parse_fabric(&mut archive, &json) {
+            return Some(metadata);
+        }
+    }
+    for (file, neoforge) in [
+        ("META-INF/neoforge.mods.toml", true),
+        ("META-INF/mods.toml", false),
+    ] {
+        if let Some(content) = read_entry(&mut archive, file) {
+            if let Some(metadata) = parse_mods_toml(&mut archive, &content, neoforge) {
+                return Some(metadata);
+            }
+        }
+    }
+    read_entry(&mut archive, "mcmod.info").and_then(|content| parse_mcmod_info(&content))
+}
+
+// mods 目录中的模组文件，包括被禁用的
+fn mod_files(mods_dir: &Path) -> Vec<(PathBuf, bool)> {
+    let mut files: Vec<(PathBuf, bool)> = std::fs::read_dir(mods_dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .filter_map(|path| {
+            let name = path.file_name()?.to_string_lossy().to_lowercase();
+            let enabled = !name.ends_with(DISABLED_SUFFIX);
+            let name = name.trim_end_matches(DISABLED_SUFFIX);
+            (name.ends_with(".jar") || name.ends_with(".zip")).then_some((path, enabled))
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+// 扫描目录并检查重复、缺失依赖和加载器不匹配，只检查启用的模组
+pub fn scan_mods(mods_dir: &Path, loader: Option<&str>) -> ModScanReport {
+    let mut report = ModScanReport::default();
+    for (path, enabled) in mod_files(mods_dir) {
+        let file_name = path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        let metadata = std::fs::File::open(&path)
+            .ok()
+            .and_then(|file| read_mod(std::io::BufReader::new(file)));
+        match metadata {
+            Some(metadata) => report.mods.push(ModMetadata {
+                file_name,
+                enabled,
+                ..metadata
+            }),
+            None => report.unreadable.push(file_name),
+        }
+    }
+
+    let enabled: Vec<&ModMetadata> = report.mods.iter().filter(|m| m.enabled).collect();
+    let mut by_id: HashMap<&str, Vec<&ModMetadata>> = HashMap::new();
+    for metadata in &enabled {
+        by_id
+            .entry(metadata.id.as_str())
+            .or_default()
+            .push(metadata);
+    }
+    let available: HashSet<&str> = enabled
+        .iter()
+        .flat_map(|m| std::iter::once(m.id.as_str()).chain(m.provides.iter().map(|p| p.as_str())))
+        .chain(BUILTIN_IDS)
+        .collect();
+    let accepted = loader.map(compatible_loaders);
+
+    for metadata in &enabled {
+        let copies = &by_id[metadata.id.as_str()];
+        if copies.len() > 1 && copies[0].file_name == metadata.file_name {
+            let files: Vec<&str> = copies.iter().map(|m| m.file_name.as_str()).collect();
+            report.issues.push(ModIssue {
+                kind: ModIssueKind::Duplicate,
+                file_name: metadata.file_name.clone(),
+                message: format!("模组 {} 重复: {}", metadata.id, files.join(", ")),
+            });
+        }
+        for dep in metadata.dependencies.iter().filter(|dep| dep.required) {
+            if !available.contains(dep.id.as_str()) {
+                report.issues.push(ModIssue {
+                    kind: ModIssueKind::MissingDependency,
+                    file_name: metadata.file_name.clone(),
+                    message: format!("{} 缺少前置模组 {}", metadata.name, dep.id),
+                });
+            }
+        }
+        let mismatch = match &accepted {
+            Some(accepted) => (!accepted.contains(&metadata.loader)).then(|| {
+                format!(
+                    "{} 是 {} 模组，与实例的加载器不匹配",
+                    metadata.name, metadata.loader
+                )
+            }),
+            None => Some(format!(
+                "{} 需要 {} 加载器，实例没有安装模组加载器",
+                metadata.name, metadata.loader
+            )),
+        };
+        if let Some(message) = mismatch {
+            report.issues.push(ModIssue {
+                kind: ModIssueKind::LoaderMismatch,
+                file_name: metadata.file_name.clone(),
+                message,
+            });
+        }
+    }
+    report
+}
+
+pub fn scan_instance(instance: &Instance) -> LauncherResult<ModScanReport> {
+    let filter = ContentFilter::for_instance(instance)?;
+    Ok(scan_mods(
+        &instance.game_dir().join("mods"),
+        filter.loader.as_deref(),
+    ))
+}
+
+#[tauri::command]
+pub async fn scan_instance_mods(instance_id: String) -> LauncherResult<ModScanReport> {
+    let instance = InstanceStore::new().get(&instance_id)?;
+    tokio::task::spawn_blocking(move || scan_instance(&instance)).await?
+}
+
+// 把模组图标解压到缓存目录，返回图片路径
+#[tauri::command]
+pub fn get_mod_icon(instance_id: String, file_name: String) -> LauncherResult<Option<PathBuf>> {
+    let instance = InstanceStore::new().get(&instance_id)?;
+    let mods_dir = instance.game_dir().join("mods");
+    let path = mods_dir.join(&file_name);
+    if path.parent() != Some(mods_dir.as_path()) || !path.is_file() {
+        return Err(LauncherError::InvalidArgument(format!(
+            "无效的模组文件: {}",
+            file_name
+        )));
+    }
+    let file = std::fs::File::open(&path).map_err(|e| LauncherError::io(&path, e))?;
+    let Some(icon) = read_mod(std::io::BufReader::new(file)).and_then(|m| m.icon) else {
+        return Ok(None);
+    };
+    let mut archive =
+        zip::ZipArchive::new(std::fs::File::open(&path).map_err(|e| LauncherError::io(&path, e))?)?;
+    let Some(bytes) = read_bytes(&mut archive, &icon) else {
+        return Ok(None);
+    };
+    let extension = Path::new(&icon)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("png");
+    let target = config::cache_dir().join("mod-icons").join(format!(
+        "{}-{}.{}",
+        instance.id,
+        file_name.trim_end_matches(DISABLED_SUFFIX),
+        extension
+    ));
+    if let Some(parent) = target.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| LauncherError::io(parent, e))?;
+    }
+    std::fs::write(&target, bytes).map_err(|e| LauncherError::io(&target, e))?;
+    Ok(Some(target))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::io::Write;
+
+    // 生成只包含指定文件的 jar
+    pub(crate) fn write_jar(path: &Path, entries: &[(&str, &[u8])]) {
+        let mut writer = zip::ZipWriter::new(std::fs::File::create(path).unwrap());
+        for (name, content) in entries {
+            writer
+                .start_file(*name, zip::write::SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(content).unwrap();
+        }
+        writer.finish().unwrap();
+    }
+
+    #[test]
+    fn test_read_mods_toml() {
+        let dir = std::env::temp_dir().join(format!("rtl-mod-toml-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let jar = dir.join("jei.jar");
+        write_jar(
+            &jar,
+            &[
+                (
+                    "META-INF/mods.toml",
+                    br#"modLoader="javafml"
+loaderVersion="[47,)"
+[[mods]]
+modId="jei"
+version="${file.jarVersion}"
+displayName="Just Enough Items"
+authors="mezz, Nerdy"
+logoFile="jei.png"
+[[dependencies.jei]]
+modId="forge"
+mandatory=true
+versionRange="[47,)"
+"#,
+                ),
+                (
+                    "META-INF/MANIFEST.MF",
+                    b"Manifest-Version: 1.0\nImplementation-Version: 15.2.0\n",
+                ),
+            ],
+        );
+        let metadata = read_mod(std::fs::File::open(&jar).unwrap()).unwrap();
+        assert_eq!(metadata.id, "jei");
+        assert_eq!(metadata.version.as_deref(), Some("15.2.0"));
+        assert_eq!(metadata.authors, ["mezz", "Nerdy"]);
+        assert_eq!(metadata.loader, "forge");
+        assert_eq!(metadata.icon.as_deref(), Some("jei.png"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_scan_reports_issues() {
+        let dir = std::env::temp_dir().join(format!("rtl-mod-scan-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let sodium =
+            br#"{"id":"sodium","name":"Sodium","version":"0.6.0","authors":["jellysquid3"],
+            "depends":{"fabricloader":">=0.16","fabric-api-base":"*"}}"#;
+        write_jar(&dir.join("sodium-a.jar"), &[("fabric.mod.json", sodium)]);
+        write_jar(&dir.join("sodium-b.jar"), &[("fabric.mod.json", sodium)]);
+        write_jar(
+            &dir.join("old.jar.disabled"),
+            &[("mcmod.info", br#"[{"modid":"old","name":"Old"}]"#)],
+        );
+        write_jar(
+            &dir.join("create.jar"),
+            &[(
+                "META-INF/neoforge.mods.toml",
+                b"[[mods]]\nmodId=\"create\"\nversion=\"6.0.0\"\n",
+            )],
+        );
+        std::fs::write(dir.join("notes.jar"), b"not a zip").unwrap();
+
+        let report = scan_mods(&dir, Some("fabric"));
+        assert_eq!(report.mods.len(), 4);
+        assert_eq!(report.unreadable, ["notes.jar"]);
+        assert!(!report.mods.iter().find(|m| m.id == "old").unwrap().enabled);
+        let count = |kind| report.issues.iter().filter(|i| i.kind == kind).count();
+        assert_eq!(count(ModIssueKind::Duplicate), 1);
+        assert_eq!(count(ModIssueKind::MissingDependency), 2);
+        assert_eq!(count(ModIssueKind::LoaderMismatch), 1);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
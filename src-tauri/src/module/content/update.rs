@@ -0,0 +1,468 @@
+/*
+RTLauncher, a third-party Minecraft launcher built with the newest
+technology and provides innovative funtionalities
+Copyright (C) 2025 lutouna
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+// ***
+// 通过文件哈希检查模组更新
+// ***
+
+use super::curseforge::{fingerprint, CurseForgeApi};
+use super::filter::{compatible_loaders, ContentFilter};
+use super::index::{ContentIndex, InstalledContent};
+use super::modrinth::{ModrinthApi, ModrinthVersion};
+use super::{
+    download_to, safe_file_name, sort_versions, ContentType, DependencyKind, Platform, UnifiedFile,
+    UnifiedVersion,
+};
+use crate::module::download::verify::sha512_file;
+use crate::module::instance::ins_main::InstanceStore;
+use crate::utils::error::{LauncherError, LauncherResult};
+use serde::{Deserialize, Serialize};
+use sha1::Digest;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+// 被替换的旧文件备份到实例目录下
+pub const BACKUP_DIR: &str = ".rtl-backups";
+
+// 一个可以更新的文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContentUpdate {
+    // mods 目录中的当前文件
+    pub file_name: String,
+    pub platform: Platform,
+    pub project_id: String,
+    pub current_version_id: String,
+    pub current_version: String,
+    pub latest: UnifiedVersion,
+    pub file: UnifiedFile,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpdateReport {
+    pub updates: Vec<ContentUpdate>,
+    pub up_to_date: Vec<String>,
+    // 两个平台都找不到的文件
+    pub unknown: Vec<String>,
+}
+
+// 本地文件的哈希
+pub(super) struct LocalFile {
+    pub(super) file_name: String,
+    pub(super) sha1: String,
+    pub(super) fingerprint: u32,
+}
+
+// 计算 mods 目录中启用的 jar 的哈希
+pub(super) fn hash_mods(mods_dir: &Path) -> Vec<LocalFile> {
+    let mut files: Vec<LocalFile> = std::fs::read_dir(mods_dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .extension()
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case("jar"))
+        })
+        .filter_map(|path| {
+            let bytes = std::fs::read(&path).ok()?;
+            Some(LocalFile {
+                file_name: path.file_name()?.to_string_lossy().to_string(),
+                sha1: format!("{:x}", sha1::Sha1::digest(&bytes)),
+                fingerprint: fingerprint(&bytes),
+            })
+        })
+        .collect();
+    files.sort_by(|a, b| a.file_name.cmp(&b.file_name));
+    files
+}
+
+pub struct UpdateChecker {
+    modrinth: ModrinthApi,
+    curseforge: CurseForgeApi,
+    filter: ContentFilter,
+}
+
+impl UpdateChecker {
+    pub fn new(filter: ContentFilter) -> Self {
+        Self::with_apis(ModrinthApi::new(), CurseForgeApi::new(), filter)
+    }
+
+    pub fn with_apis(
+        modrinth: ModrinthApi,
+        curseforge: CurseForgeApi,
+        filter: ContentFilter,
+    ) -> Self {
+        Self {
+            modrinth,
+            curseforge,
+            filter,
+        }
+    }
+
+    // 先查 Modrinth，找不到的文件再用指纹查 CurseForge
+    pub async fn check(&self, mods_dir: &Path) -> LauncherResult<UpdateReport> {
+        let dir = mods_dir.to_path_buf();
+        let files = tokio::task::spawn_blocking(move || hash_mods(&dir)).await?;
+        let mut report = UpdateReport::default();
+        let mut remaining = Vec::new();
+
+        match self.check_modrinth(mods_dir, &files, &mut report).await {
+            Ok(found) => remaining.extend(files.iter().filter(|f| !found.contains(&f.file_name))),
+            Err(e) => {
+                log::warn!("Modrinth 更新检查失败: {}", e);
+                remaining.extend(files.iter());
+            }
+        }
+
+        if !remaining.is_empty() && self.curseforge.has_keys() {
+            match self.check_curseforge(&remaining, &mut report).await {
+                Ok(found) => remaining.retain(|f| !found.contains(&f.file_name)),
+                Err(e) => log::warn!("CurseForge 更新检查失败: {}", e),
+            }
+        }
+        report.unknown = remaining.into_iter().map(|f| f.file_name.clone()).collect();
+        Ok(report)
+    }
+
+    // 按哈希查找当前版本和最新版本，hashes 为 (文件名, 哈希)，返回以文件名为键的结果
+    async fn modrinth_versions(
+        &self,
+        hashes: Vec<(String, String)>,
+        algorithm: &str,
+    ) -> LauncherResult<HashMap<String, (ModrinthVersion, Option<ModrinthVersion>)>> {
+        if hashes.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let list: Vec<String> = hashes.iter().map(|(_, hash)| hash.clone()).collect();
+        let mut current = self.modrinth.versions_by_hash(&list, algorithm).await?;
+        let known: Vec<String> = list
+            .into_iter()
+            .filter(|h| current.contains_key(h))
+            .collect();
+        if known.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let loaders = self
+            .filter
+            .loader
+            .as_deref()
+            .map(compatible_loaders)
+            .unwrap_or_default();
+        let game_versions: Vec<String> = self.filter.game_version.iter().cloned().collect();
+        let mut latest = self
+            .modrinth
+            .latest_by_hash(&known, algorithm, &loaders, &game_versions)
+            .await?;
+        Ok(hashes
+            .into_iter()
+            .filter_map(|(file_name, hash)| {
+                let version = current.remove(&hash)?;
+                Some((file_name, (version, latest.remove(&hash))))
+            })
+            .collect())
+    }
+
+    // 返回在 Modrinth 上找到的文件，SHA-1 找不到的文件再用 SHA-512 查找
+    async fn check_modrinth(
+        &self,
+        mods_dir: &Path,
+        files: &[LocalFile],
+        report: &mut UpdateReport,
+    ) -> LauncherResult<Vec<String>> {
+        let sha1: Vec<(String, String)> = files
+            .iter()
+            .map(|f| (f.file_name.clone(), f.sha1.clone()))
+            .collect();
+        let mut versions = self.modrinth_versions(sha1, "sha1").await?;
+
+        // SHA-512 只对 SHA-1 找不到的文件计算
+        let missing: Vec<(String, PathBuf)> = files
+            .iter()
+            .filter(|f| !versions.contains_key(&f.file_name))
+            .map(|f| (f.file_name.clone(), mods_dir.join(&f.file_name)))
+            .collect();
+        if !missing.is_empty() {
+            let sha512 = tokio::task::spawn_blocking(move || {
+                missing
+                    .into_iter()
+                    .filter_map(|(name, path)| Some((name, sha512_file(&path).ok()?)))
+                    .collect::<Vec<_>>()
+            })
+            .await?;
+            match self.modrinth_versions(sha512, "sha512").await {
+                Ok(found) => versions.extend(found),
+                Err(e) => log::warn!("Modrinth SHA-512 查找失败: {}", e),
+            }
+        }
+
+        let mut found = Vec::new();
+        for file in files {
+            let Some((current, latest)) = versions.get(&file.file_name) else {
+                continue;
+            };
+            found.push(file.file_name.clone());
+            let update = latest.as_ref().filter(|latest| latest.id != current.id);
+            let primary = update.and_then(|latest| {
+                let files = latest.unified_files();
+                let index = files.iter().position(|f| f.primary).unwrap_or(0);
+                files.into_iter().nth(index)
+            });
+            match (update, primary) {
+                (Some(latest), Some(primary)) => {
+                    let current = current.unified();
+                    report.updates.push(ContentUpdate {
+                        file_name: file.file_name.clone(),
+                        platform: Platform::Modrinth,
+                        project_id: current.project_id,
+                        current_version_id: current.version_id,
+                        current_version: current.name,
+                        latest: latest.unified(),
+                        file: primary,
+                    });
+                }
+                _ => report.up_to_date.push(file.file_name.clone()),
+            }
+        }
+        Ok(found)
+    }
+
+    // 返回在 CurseForge 上找到的文件
+    async fn check_curseforge(
+        &self,
+        files: &[&LocalFile],
+        report: &mut UpdateReport,
+    ) -> LauncherResult<Vec<String>> {
+        let fingerprints: Vec<u32> = files.iter().map(|f| f.fingerprint).collect();
+        let matches = self.curseforge.fingerprint_matches(&fingerprints).await?;
+        let mut found = Vec::new();
+        for matched in matches {
+            let Some(file) = files
+                .iter()
+                .find(|f| matched.file.file_fingerprint == Some(f.fingerprint as u64))
+            else {
+                continue;
+            };
+            found.push(file.file_name.clone());
+            let current = matched.file.unified_version();
+            let mut candidates: Vec<UnifiedVersion> = matched
+                .latest_files
+                .iter()
+                .map(|f| f.unified_version())
+                .collect();
+            sort_versions(&mut candidates);
+            self.filter.flag(ContentType::Mod, &mut candidates);
+            // 只有比当前文件更新的兼容文件才算更新
+            let newer = candidates.into_iter().find(|candidate| {
+                candidate.incompatible_reason.is_none()
+                    && candidate.version_id != current.version_id
+                    && candidate.date_published > current.date_published
+            });
+            let latest_file = newer.as_ref().and_then(|newer| {
+                matched
+                    .latest_files
+                    .iter()
+                    .find(|f| f.id.to_string() == newer.version_id)
+            });
+            match (newer, latest_file) {
+                (Some(latest), Some(latest_file)) => report.updates.push(ContentUpdate {
+                    file_name: file.file_name.clone(),
+                    platform: Platform::CurseForge,
+                    project_id: current.project_id.clone(),
+                    current_version_id: current.version_id.clone(),
+                    current_version: current.name.clone(),
+                    file: latest_file.unified_file(),
+                    latest,
+                }),
+                _ => report.up_to_date.push(file.file_name.clone()),
+            }
+        }
+        Ok(found)
+    }
+}
+
+// 下载新版本，旧文件移动到备份目录，下载失败时恢复旧文件
+pub async fn apply_updates(
+    game_dir: &Path,
+    updates: &[ContentUpdate],
+) -> LauncherResult<Vec<PathBuf>> {
+    let backup_dir = game_dir
+        .join(BACKUP_DIR)
+        .join(chrono::Local::now().format("%Y%m%d-%H%M%S").to_string());
+    let mods_dir = game_dir.join(ContentType::Mod.folder());
+    let mut index = ContentIndex::load(game_dir);
+    let mut updated = Vec::new();
+    let mut result = Ok(());
+
+    for update in updates {
+        let file_name = safe_file_name(&update.file_name)?;
+        let old = mods_dir.join(&file_name);
+        if !old.exists() {
+            log::warn!("要更新的文件不存在: {}", old.display());
+            continue;
+        }
+        std::fs::create_dir_all(&backup_dir).map_err(|e| LauncherError::io(&backup_dir, e))?;
+        let backup = backup_dir.join(&file_name);
+        std::fs::rename(&old, &backup).map_err(|e| LauncherError::io(&old, e))?;
+
+        match download_to(game_dir, ContentType::Mod, None, &update.file).await {
+            Ok(path) => {
+                let dependency = index
+                    .find(update.platform, &update.project_id)
+                    .is_some_and(|entry| entry.dependency);
+                index.remove_path(&format!("{}/{}", ContentType::Mod.folder(), file_name));
+                index.upsert(InstalledContent {
+                    platform: update.platform,
+                    project_id: update.project_id.clone(),
+                    version_id: update.latest.version_id.clone(),
+                    content_type: ContentType::Mod,
+                    path: index.relative_path(&path).unwrap_or_default(),
+                    dependency,
+                    incompatible: update
+                        .latest
+                        .dependencies
+                        .iter()
+                        .filter(|dep| dep.kind == DependencyKind::Incompatible)
+                        .filter_map(|dep| dep.project_id.clone())
+                        .collect(),
+                });
+                log::info!("已更新 {} -> {}", file_name, update.file.file_name);
+                updated.push(path);
+            }
+            Err(e) => {
+                let _ = std::fs::rename(&backup, &old);
+                result = Err(e);
+                break;
+            }
+        }
+    }
+    index.save()?;
+    result.map(|_| updated)
+}
+
+#[tauri::command]
+pub async fn check_content_updates(instance_id: String) -> LauncherResult<UpdateReport> {
+    let instance = InstanceStore::new().get(&instance_id)?;
+    let filter = ContentFilter::for_instance(&instance)?;
+    UpdateChecker::new(filter)
+        .check(&instance.game_dir().join(ContentType::Mod.folder()))
+        .await
+}
+
+#[tauri::command]
+pub async fn update_content(
+    instance_id: String,
+    updates: Vec<ContentUpdate>,
+) -> LauncherResult<Vec<PathBuf>> {
+    let instance = InstanceStore::new().get(&instance_id)?;
+    apply_updates(&instance.game_dir(), &updates).await
+}
+
+// 检查并更新全部模组
+#[tauri::command]
+pub async fn update_all_content(instance_id: String) -> LauncherResult<Vec<PathBuf>> {
+    let report = check_content_updates(instance_id.clone()).await?;
+    update_content(instance_id, report.updates).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::module::loader::fake_server;
+    use std::collections::HashMap;
+
+    fn sha1_hex(bytes: &[u8]) -> String {
+        format!("{:x}", sha1::Sha1::digest(bytes))
+    }
+
+    #[tokio::test]
+    async fn test_check_and_apply_updates() {
+        let dir = std::env::temp_dir().join(format!("rtl-content-update-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let mods_dir = dir.join("mods");
+        std::fs::create_dir_all(&mods_dir).unwrap();
+        std::fs::write(mods_dir.join("lithium-1.jar"), b"old").unwrap();
+        std::fs::write(mods_dir.join("custom.jar"), b"local").unwrap();
+        std::fs::write(mods_dir.join("sodium.jar"), b"sodium").unwrap();
+
+        let old = sha1_hex(b"old");
+        let current = r#"{"id":"v1","project_id":"lithium","name":"Lithium 1","files":[]}"#;
+        let latest = format!(
+            r#"{{"id":"v2","project_id":"lithium","name":"Lithium 2","game_versions":["1.21.1"],"loaders":["fabric"],
+            "files":[{{"filename":"lithium-2.jar","url":"{{base}}/lithium-2.jar","primary":true,"hashes":{{"sha1":"{}"}}}}]}}"#,
+            sha1_hex(b"new")
+        );
+        let (base, server) = fake_server::serve(HashMap::from([
+            (
+                "/version_files".to_string(),
+                format!(
+                    r#"{{"{}":{},"{:x}":{}}}"#,
+                    old,
+                    current,
+                    sha2::Sha512::digest(b"sodium"),
+                    r#"{"id":"s1","project_id":"sodium","name":"Sodium","files":[]}"#
+                )
+                .into_bytes(),
+            ),
+            (
+                "/version_files/update".to_string(),
+                format!(r#"{{"{}":{}}}"#, old, latest).into_bytes(),
+            ),
+            ("/lithium-2.jar".to_string(), b"new".to_vec()),
+        ]))
+        .await;
+
+        let checker = UpdateChecker::with_apis(
+            ModrinthApi::with_base(base.clone()),
+            CurseForgeApi::with_base(base.clone(), Vec::new()),
+            ContentFilter {
+                game_version: Some("1.21.1".to_string()),
+                loader: Some("fabric".to_string()),
+            },
+        );
+        let report = checker.check(&mods_dir).await.unwrap();
+        assert_eq!(report.updates.len(), 1);
+        assert_eq!(report.updates[0].current_version_id, "v1");
+        assert_eq!(report.unknown, ["custom.jar"]);
+        // 只能通过 SHA-512 找到的文件
+        assert_eq!(report.up_to_date, ["sodium.jar"]);
+
+        let updated = apply_updates(&dir, &report.updates).await.unwrap();
+        assert_eq!(updated, [mods_dir.join("lithium-2.jar")]);
+        assert!(!mods_dir.join("lithium-1.jar").exists());
+        let backups: Vec<_> = walkdir::WalkDir::new(dir.join(BACKUP_DIR))
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_name() == "lithium-1.jar")
+            .collect();
+        assert_eq!(backups.len(), 1);
+        let index = ContentIndex::load(&dir);
+        assert_eq!(
+            index
+                .find(Platform::Modrinth, "lithium")
+                .unwrap()
+                .version_id,
+            "v2"
+        );
+
+        server.abort();
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
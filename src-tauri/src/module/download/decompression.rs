@@ -23,6 +23,7 @@ along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
 use super::get_user_os;
 use crate::module::download::paths::MinecraftPaths;
+use crate::utils::error::LauncherResult;
 use std::fs::File;
 use zip;
 
@@ -71,13 +72,9 @@ fn should_extract_file(filename: &str) -> bool {
 }
 
 // 解压文件
-pub fn decompression(path: &str, version_id: &str) -> Result<(), Box<dyn std::error::Error>> {
-    println!("🔄 开始解压文件: {}", path);
-    println!(
-        "💻 当前系统: {}, CPU架构: {}",
-        get_user_os(),
-        get_cpu_arch()
-    );
+pub fn decompression(path: &str, version_id: &str) -> LauncherResult<()> {
+    log::info!("开始解压文件: {}", path);
+    log::info!("当前系统: {}, CPU架构: {}", get_user_os(), get_cpu_arch());
 
     let file = File::open(path)?;
     let mut archive = zip::ZipArchive::new(file)?;
@@ -86,7 +83,7 @@ pub fn decompression(path: &str, version_id: &str) -> Result<(), Box<dyn std::er
     let paths = MinecraftPaths::new();
     let natives_dir = paths.get_natives_dir(version_id);
 
-    println!("📂 解压目标目录: {}", natives_dir.display());
+    log::debug!("解压目标目录: {}", natives_dir.display());
 
     // 确保natives目录存在
     std::fs::create_dir_all(&natives_dir)?;
@@ -113,17 +110,17 @@ pub fn decompression(path: &str, version_id: &str) -> Result<(), Box<dyn std::er
             let mut outfile = File::create(&outpath)?;
             std::io::copy(&mut file, &mut outfile)?;
 
-            println!("✅ 已解压: {}", simple_name);
+            log::debug!("已解压: {}", simple_name);
             extracted_count += 1;
         } else {
-            println!("⏭️ 已跳过: {}", filename);
+            log::debug!("已跳过: {}", filename);
             skipped_count += 1;
         }
     }
 
-    println!("📊 解压完成:");
-    println!("- 成功解压: {} 个文件", extracted_count);
-    println!("- 已跳过: {} 个文件", skipped_count);
+    log::info!("解压完成:");
+    log::info!("- 成功解压: {} 个文件", extracted_count);
+    log::info!("- 已跳过: {} 个文件", skipped_count);
 
     Ok(())
 }
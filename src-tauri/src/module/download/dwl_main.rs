@@ -23,7 +23,14 @@ along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
 use super::decompression::decompression;
 use super::get_user_os;
+use super::installed::rules_allow;
+use super::manifest::load_manifest;
+use super::maven::library_download;
+use super::paths::MinecraftPaths;
+use super::verify::sha1_file;
+use crate::utils::error::{LauncherError, LauncherResult};
 use crate::utils::request;
+use crate::Setting::config;
 use futures::stream::{self, StreamExt};
 use reqwest;
 use sha1::Digest;
@@ -32,7 +39,6 @@ use std::sync::Arc;
 use std::sync::Mutex;
 use std::time::Duration;
 use tokio::io::AsyncWriteExt;
-use walkdir;
 
 pub struct Download {
     pub version_manifest_url: String, // 获取版本url
@@ -88,87 +94,23 @@ impl DownloadProgress {
     }
 }
 
-// 路径管理
-pub struct MinecraftPaths {
-    pub base_dir: std::path::PathBuf,
-    pub versions_dir: std::path::PathBuf,
-    pub libraries_dir: std::path::PathBuf,
-    pub assets_dir: std::path::PathBuf,
-}
-
-impl MinecraftPaths {
-    pub fn new() -> Self {
-        // 游戏文件保存路径
-        let base_dir = std::path::PathBuf::from("D:\\Desktop\\.minecraft");
-        Self {
-            versions_dir: base_dir.join("version"),
-            libraries_dir: base_dir.join("libraries"),
-            assets_dir: base_dir.join("assets"),
-            base_dir,
-        }
-    }
-
-    pub fn get_version_dir(&self, version_id: &str) -> std::path::PathBuf {
-        self.versions_dir.join(version_id)
-    }
-
-    pub fn get_natives_dir(&self, version_id: &str) -> std::path::PathBuf {
-        self.get_version_dir(version_id)
-            .join(format!("{}-natives", version_id))
-    }
-
-    pub fn ensure_dirs(&self) -> std::io::Result<()> {
-        std::fs::create_dir_all(&self.base_dir)?;
-        std::fs::create_dir_all(&self.versions_dir)?;
-        std::fs::create_dir_all(&self.libraries_dir)?;
-        std::fs::create_dir_all(&self.assets_dir)?;
-        Ok(())
-    }
-
-    // 获取绝对路径-公共方法
-    pub fn get_absolute_path(&self, path: std::path::PathBuf) -> String {
-        path.canonicalize()
-            .unwrap_or_default()
-            .to_string_lossy()
-            .to_string()
-            .trim_start_matches(r"\\?\")
-            .to_string()
-    }
-
-    // 获取libraries目录下所有jar文件的路径
-    pub fn get_libraries_classpath(&self) -> Vec<String> {
-        walkdir::WalkDir::new(&self.libraries_dir)
-            .into_iter()
-            .filter_map(|entry| entry.ok())
-            .filter(|entry| entry.path().extension().map_or(false, |ext| ext == "jar"))
-            .map(|entry| self.get_absolute_path(entry.path().to_path_buf()))
-            .collect()
-    }
-}
-
 #[tauri::command]
-pub async fn get_version_manifest() -> Result<serde_json::Value, String> {
-    let download = Download::new(String::from(
-        "https://piston-meta.mojang.com/mc/game/version_manifest.json",
-    ));
-    download
-        .dwl_version_manifest()
-        .await
-        .map_err(|e| e.to_string())
+pub async fn get_version_manifest() -> LauncherResult<serde_json::Value> {
+    // 使用缓存的清单，离线时也能显示版本列表
+    Ok(load_manifest().await?.manifest)
 }
 
 #[tauri::command]
-pub async fn dwl_version_manifest(url: String) -> Result<serde_json::Value, String> {
+pub async fn dwl_version_manifest(url: String) -> LauncherResult<serde_json::Value> {
     let url = if url.starts_with('{') {
         // 如果输入是 JSON 字符串，尝试解析
-        let parsed_json: serde_json::Value =
-            serde_json::from_str(&url).map_err(|e| format!("JSON解析错误: {}", e))?;
+        let parsed_json: serde_json::Value = serde_json::from_str(&url)?;
 
         // 从 JSON 对象中提取 url 字段
         parsed_json
             .get("url")
             .and_then(|u| u.as_str())
-            .ok_or("JSON中未找到有效的url字段")?
+            .ok_or_else(|| LauncherError::InvalidArgument("JSON中未找到有效的url字段".to_string()))?
             .to_string()
     } else {
         // 如果输入是普通URL字符串，直接使用
@@ -176,10 +118,7 @@ pub async fn dwl_version_manifest(url: String) -> Result<serde_json::Value, Stri
     };
 
     let download = DownloadOptions::new(url);
-    let (json_value, _) = download
-        .dwl_version_manifest()
-        .await
-        .map_err(|e| e.to_string())?;
+    let (json_value, _) = download.dwl_version_manifest().await?;
     Ok(json_value)
 }
 
@@ -193,13 +132,6 @@ impl Download {
             version_type: String::from(""),
         }
     }
-
-    async fn dwl_version_manifest(&self) -> Result<serde_json::Value, Box<dyn std::error::Error>> {
-        let request = request::Request::new(self.version_manifest_url.clone());
-        let res = request.fetch_get().await.unwrap();
-        let json_value = serde_json::from_str::<serde_json::Value>(&res)?;
-        Ok(json_value)
-    }
 }
 
 impl DownloadOptions {
@@ -211,10 +143,10 @@ impl DownloadOptions {
     }
 
     // 下载游戏资源
-    pub async fn dwl_version_manifest(
-        &self,
-    ) -> Result<(serde_json::Value, String), Box<dyn std::error::Error + Send + Sync>> {
-        let response = request::Request::new(self.url.clone());
+    pub async fn dwl_version_manifest(&self) -> LauncherResult<(serde_json::Value, String)> {
+        let settings = config::current();
+        let max_retries = settings.download.max_retries;
+        let response = request::Request::new(settings.download.mirror.rewrite(&self.url));
         let res = response.fetch_get().await?;
         let mut timings = Vec::new();
 
@@ -236,6 +168,11 @@ impl DownloadOptions {
         let version_path = paths.get_version_dir(version_id);
         std::fs::create_dir_all(&version_path)?;
 
+        // 保存版本 JSON，校验和启动时读取
+        let version_json_path = paths.get_version_json(version_id);
+        std::fs::write(&version_json_path, &res)
+            .map_err(|e| LauncherError::io(&version_json_path, e))?;
+
         let mut success_count = 0;
         let mut failed_count = 0;
         let current_os = get_user_os(); // 获取当前操作系统
@@ -250,12 +187,14 @@ impl DownloadOptions {
             let client_sha1 = client["sha1"].as_str().unwrap_or_default().to_string(); // 获取SHA1值
             let jar_path = version_path.join(format!("{}.jar", version_id));
 
-            match download_and_verify_file(client_url, jar_path, &client_sha1, None, 3).await {
+            match download_and_verify_file(client_url, jar_path, &client_sha1, None, max_retries)
+                .await
+            {
                 Ok(info) => {
                     let duration = jar_start.elapsed();
                     timings.push(("客户端jar".to_string(), duration));
-                    println!(
-                        "✅ 下载成功: {} -> {} (耗时: {:.2}秒)",
+                    log::debug!(
+                        "下载成功: {} -> {} (耗时: {:.2}秒)",
                         info.url,
                         info.path.display(),
                         duration.as_secs_f64()
@@ -263,7 +202,7 @@ impl DownloadOptions {
                     success_count += 1;
                 }
                 Err(e) => {
-                    println!("❌ 下载失败: {}", e);
+                    log::error!("下载失败: {}", e);
                     failed_count += 1;
                 }
             }
@@ -277,15 +216,15 @@ impl DownloadOptions {
                         let xml_path = version_path.join("client-1.12.xml");
                         match download_file(xml_url.to_string(), xml_path).await {
                             Ok(info) => {
-                                println!(
-                                    "✅ 日志配置文件下载成功: {} -> {}",
+                                log::info!(
+                                    "日志配置文件下载成功: {} -> {}",
                                     info.url,
                                     info.path.display()
                                 );
                                 success_count += 1;
                             }
                             Err(e) => {
-                                println!("❌ 日志配置文件下载失败: {}", e);
+                                log::error!("日志配置文件下载失败: {}", e);
                                 failed_count += 1;
                             }
                         }
@@ -297,14 +236,15 @@ impl DownloadOptions {
         // 创建两个异步任务，分别处理资源索引文件和libraries
         let assets_future = async {
             let assets_start = std::time::Instant::now();
-            let mut result: Result<(), Box<dyn std::error::Error + Send + Sync>> = Ok(());
+            let mut result: LauncherResult<()> = Ok(());
 
             if let Some(asset_index) = json_value.get("assetIndex") {
                 let asset_id = asset_index["id"].as_str().unwrap_or("unknown");
-                println!("asset_id: {}", asset_id);
+                log::debug!("asset_id: {}", asset_id);
                 if let Some(asset_url) = asset_index["url"].as_str() {
                     // 直接解析资源索引文件内容
-                    let response = request::Request::new(asset_url.to_string());
+                    let response =
+                        request::Request::new(settings.download.mirror.rewrite(asset_url));
                     let asset_content = response.fetch_get().await?;
                     let asset_json: serde_json::Value = serde_json::from_str(&asset_content)?;
                     // 保存资源索引文件
@@ -316,7 +256,7 @@ impl DownloadOptions {
                         std::fs::create_dir_all(parent)?;
                     }
                     std::fs::write(&assets_index_path, &asset_content)?;
-                    println!("✅ 资源索引文件已保存到: {}", assets_index_path.display());
+                    log::info!("资源索引文件已保存到: {}", assets_index_path.display());
 
                     if let Some(objects) = asset_json.get("objects") {
                         let paths = MinecraftPaths::new();
@@ -326,7 +266,7 @@ impl DownloadOptions {
                         // 准备下载任务
                         let download_tasks: Vec<(String, std::path::PathBuf, String)> = objects
                             .as_object()
-                            .unwrap()
+                            .ok_or_else(|| LauncherError::Parse("资源索引格式错误".to_string()))?
                             .iter()
                             .filter_map(|(_, value)| {
                                 let hash = value.get("hash").and_then(|h| h.as_str())?;
@@ -350,9 +290,9 @@ impl DownloadOptions {
                         let progress = DownloadProgress::new(total_files);
                         let failed_downloads = Arc::new(Mutex::new(Vec::new()));
 
-                        println!("🚀 开始下载 {} 个资源文件...", total_files);
+                        log::info!("开始下载 {} 个资源文件...", total_files);
 
-                        let batch_size = 250; // 控制并发量
+                        let batch_size = settings.download.asset_concurrency; // 控制并发量
                         let semaphore = Arc::new(tokio::sync::Semaphore::new(batch_size));
 
                         for chunk in download_tasks.chunks(batch_size) {
@@ -373,14 +313,14 @@ impl DownloadOptions {
                                         path.clone(),
                                         &expected_hash,
                                         Some(progress.clone()),
-                                        3,
+                                        max_retries,
                                     )
                                     .await;
 
                                     if let Err(e) = result {
                                         let mut failed = failed_downloads.lock().unwrap();
                                         failed.push((url, path));
-                                        eprintln!("❌ 下载或验证失败: {}", e);
+                                        log::error!("下载或验证失败: {}", e);
                                     }
                                 });
                             }
@@ -394,8 +334,8 @@ impl DownloadOptions {
                             // 显示进度
                             let current = progress.get_current();
                             let total = progress.total.load(Ordering::SeqCst);
-                            println!(
-                                "📊 下载进度: {}/{} ({}%)",
+                            log::info!(
+                                "下载进度: {}/{} ({}%)",
                                 current,
                                 total,
                                 (current as f32 / total as f32 * 100.0) as u32
@@ -405,13 +345,13 @@ impl DownloadOptions {
                         // 处理失败的下载
                         let retry_list = failed_downloads.lock().unwrap().clone();
                         if !retry_list.is_empty() {
-                            println!("🔄 重试 {} 个失败的下载...", retry_list.len());
+                            log::info!("重试 {} 个失败的下载...", retry_list.len());
                             for (url, path) in retry_list {
                                 if let Err(e) =
                                     download_file_with_retry(url.clone(), path.clone(), None, 5)
                                         .await
                                 {
-                                    eprintln!("❌ 最终失败: {} -> {}", url, e);
+                                    log::error!("最终失败: {} -> {}", url, e);
                                     progress.update_failed();
                                 } else {
                                     progress.update_success();
@@ -422,9 +362,9 @@ impl DownloadOptions {
                         // 输出最终统计
                         let final_success = progress.success.load(Ordering::SeqCst);
                         let final_failed = progress.failed.load(Ordering::SeqCst);
-                        println!("📊 下载完成:");
-                        println!("✅ 成功: {} 个文件", final_success);
-                        println!("❌ 失败: {} 个文件", final_failed);
+                        log::info!("下载完成:");
+                        log::info!("成功: {} 个文件", final_success);
+                        log::error!("失败: {} 个文件", final_failed);
 
                         if final_failed > 0 {
                             return Err("部分资源文件下载失败".into());
@@ -433,10 +373,7 @@ impl DownloadOptions {
                         // 在资源下载完成后记录耗时
                         let duration = assets_start.elapsed();
                         timings.push(("资源索引文件".to_string(), duration));
-                        println!(
-                            "✅ 资源文件下载完成 (耗时: {:.2}秒)",
-                            duration.as_secs_f64()
-                        );
+                        log::info!("资源文件下载完成 (耗时: {:.2}秒)", duration.as_secs_f64());
                     }
                 }
             }
@@ -458,6 +395,7 @@ impl DownloadOptions {
                     let download_tasks: Vec<_> = libraries_array
                         .iter()
                         .filter_map(|library| {
+                            // 没有 downloads 的库按 Maven 坐标单独下载
                             let downloads = library.get("downloads")?;
                             let mut is_native = false;
 
@@ -472,8 +410,8 @@ impl DownloadOptions {
                                                 if name == current_os {
                                                     // 如果rules第一项的os.name匹配当前系统，标记为需要解压
                                                     is_native = true;
-                                                    println!(
-                                                        "📦 发现需要解压的natives库: {}",
+                                                    log::debug!(
+                                                        "发现需要解压的natives库: {}",
                                                         library
                                                             .get("name")
                                                             .and_then(|n| n.as_str())
@@ -524,12 +462,12 @@ impl DownloadOptions {
 
                     let total_libs = download_tasks.len();
                     let progress = DownloadProgress::new(total_libs);
-                    let batch_size = 50;
+                    let batch_size = settings.download.library_concurrency;
                     let semaphore = Arc::new(tokio::sync::Semaphore::new(batch_size));
                     let success_counter = Arc::new(AtomicUsize::new(0));
                     let failed_counter = Arc::new(AtomicUsize::new(0));
 
-                    println!("🚀 开始下载 {} 个库文件...", total_libs);
+                    log::info!("开始下载 {} 个库文件...", total_libs);
 
                     // 下载库文件
                     stream::iter(download_tasks)
@@ -548,7 +486,7 @@ impl DownloadOptions {
                                     path.clone(),
                                     &sha1,
                                     Some(progress.clone()),
-                                    3,
+                                    max_retries,
                                 )
                                 .await
                                 {
@@ -557,20 +495,20 @@ impl DownloadOptions {
                                             // 将需要解压的文件信息存储起来
                                             let mut natives = natives_to_extract.lock().unwrap();
                                             natives.push((info.path.clone(), version_id.clone()));
-                                            println!(
-                                                "✅ natives库下载成功，已加入解压队列: {}",
+                                            log::debug!(
+                                                "natives库下载成功，已加入解压队列: {}",
                                                 info.path.display()
                                             );
                                         }
-                                        println!(
-                                            "✅ 库文件下载成功: {} -> {}",
+                                        log::debug!(
+                                            "库文件下载成功: {} -> {}",
                                             info.url,
                                             info.path.display()
                                         );
                                         success_counter.fetch_add(1, Ordering::SeqCst);
                                     }
                                     Err(e) => {
-                                        println!("❌ 库文件下载失败: {} -> {}", url, e);
+                                        log::error!("库文件下载失败: {} -> {}", url, e);
                                         failed_counter.fetch_add(1, Ordering::SeqCst);
                                     }
                                 }
@@ -580,56 +518,89 @@ impl DownloadOptions {
                         .collect::<Vec<_>>()
                         .await;
 
+                    // 只有 Maven 坐标的库依次尝试各个仓库
+                    let maven_libraries: Vec<_> = libraries_array
+                        .iter()
+                        .filter(|library| library.get("downloads").is_none())
+                        .filter(|library| rules_allow(&library["rules"]))
+                        .filter_map(|library| library_download(&paths, library))
+                        .collect();
+                    if !maven_libraries.is_empty() {
+                        log::info!("开始下载 {} 个 Maven 库文件...", maven_libraries.len());
+                    }
+                    stream::iter(maven_libraries)
+                        .map(|library| {
+                            let success_counter = success_counter.clone();
+                            let failed_counter = failed_counter.clone();
+                            async move {
+                                match library.download().await {
+                                    Ok(()) => success_counter.fetch_add(1, Ordering::SeqCst),
+                                    Err(e) => {
+                                        log::error!(
+                                            "库文件下载失败: {} -> {}",
+                                            library.path.display(),
+                                            e
+                                        );
+                                        failed_counter.fetch_add(1, Ordering::SeqCst)
+                                    }
+                                };
+                            }
+                        })
+                        .buffer_unordered(batch_size)
+                        .collect::<Vec<_>>()
+                        .await;
+
                     // 所有文件下载完成后，开始解压natives库
                     let natives = natives_to_extract.lock().unwrap().clone();
 
                     if !natives.is_empty() {
-                        println!("📦 开始解压 {} 个natives库...", natives.len());
+                        log::info!("开始解压 {} 个natives库...", natives.len());
 
                         for (file_path, version_id) in natives {
                             let natives_dir = paths.get_natives_dir(&version_id);
-                            println!("🔄 正在解压: {}", file_path.display());
-                            println!("📂 解压目标目录: {}", natives_dir.display());
+                            log::debug!("正在解压: {}", file_path.display());
+                            log::debug!("解压目标目录: {}", natives_dir.display());
 
                             // 在新线程中执行解压操作
                             if let Err(e) = tokio::task::spawn_blocking(move || {
                                 if let Err(e) = std::fs::create_dir_all(&natives_dir) {
-                                    println!("❌ 创建natives目录失败: {}", e);
-                                    return Err(e.to_string());
+                                    log::error!("创建natives目录失败: {}", e);
+                                    return Err(LauncherError::io(&natives_dir, e));
                                 }
 
-                                match decompression(file_path.to_str().unwrap(), &version_id) {
+                                match decompression(&file_path.to_string_lossy(), &version_id) {
                                     Ok(_) => {
-                                        println!("✅ natives库解压成功: {}", file_path.display());
+                                        log::debug!("natives库解压成功: {}", file_path.display());
                                         Ok(())
                                     }
                                     Err(e) => {
-                                        println!(
-                                            "❌ natives库解压失败: {} -> {}",
+                                        log::error!(
+                                            "natives库解压失败: {} -> {}",
                                             file_path.display(),
                                             e
                                         );
-                                        Err(e.to_string())
+                                        Err(e)
                                     }
                                 }
                             })
                             .await
-                            .unwrap()
+                            .unwrap_or_else(|e| Err(e.into()))
                             {
-                                println!("❌ 解压过程出错: {}", e);
+                                log::error!("解压过程出错: {}", e);
                                 failed_counter.fetch_add(1, Ordering::SeqCst);
                             }
                         }
 
-                        println!("📦 natives库解压完成");
+                        log::info!("natives库解压完成");
                     }
 
                     success_count = success_counter.load(Ordering::SeqCst);
                     failed_count = failed_counter.load(Ordering::SeqCst);
 
-                    println!(
-                        "📊 Libraries下载完成: 成功 {}, 失败 {}",
-                        success_count, failed_count
+                    log::info!(
+                        "Libraries下载完成: 成功 {}, 失败 {}",
+                        success_count,
+                        failed_count
                     );
                 }
             }
@@ -657,15 +628,11 @@ impl DownloadOptions {
                 let mapping_path = version_path.join(format!("{}-mappings.txt", version_id));
                 match download_file(mapping_url.to_string(), mapping_path).await {
                     Ok(info) => {
-                        println!(
-                            "✅ 映射文件下载成功: {} -> {}",
-                            info.url,
-                            info.path.display()
-                        );
+                        log::info!("映射文件下载成功: {} -> {}", info.url, info.path.display());
                         success_count += 1;
                     }
                     Err(e) => {
-                        println!("❌ 映射文件下载失败: {}", e);
+                        log::error!("映射文件下载失败: {}", e);
                         failed_count += 1;
                     }
                 }
@@ -673,16 +640,17 @@ impl DownloadOptions {
         }
 
         // 输出所有资源的下载耗时统计
-        println!("\n📊 下载耗时统计:");
-        println!("----------------------------------------");
+        log::info!("下载耗时统计:");
+        log::info!("----------------------------------------");
         for (resource, duration) in timings {
-            println!("{}: {:.2}秒", resource, duration.as_secs_f64());
+            log::info!("{}: {:.2}秒", resource, duration.as_secs_f64());
         }
-        println!("----------------------------------------");
+        log::info!("----------------------------------------");
 
-        println!(
-            "📊 下载统计: 成功 {} 个文件, 失败 {} 个文件",
-            success_count, failed_count
+        log::info!(
+            "下载统计: 成功 {} 个文件, 失败 {} 个文件",
+            success_count,
+            failed_count
         );
 
         if failed_count > 0 {
@@ -698,9 +666,14 @@ async fn download_with_progress(
     url: String,
     path: std::path::PathBuf,
     progress: Option<DownloadProgress>,
-) -> Result<DownloadInfo, Box<dyn std::error::Error + Send + Sync>> {
+) -> LauncherResult<DownloadInfo> {
     let client = reqwest::Client::new();
-    let response = client.get(&url).send().await?;
+    let mirror = config::current().download.mirror;
+    let response = client
+        .get(mirror.rewrite(&url))
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())?;
     let total_size = response.content_length().unwrap_or(0);
 
     let downloaded = Arc::new(AtomicUsize::new(0));
@@ -731,7 +704,7 @@ async fn download_file_with_retry(
 
     progress: Option<DownloadProgress>,
     max_retries: u32,
-) -> Result<DownloadInfo, Box<dyn std::error::Error + Send + Sync>> {
+) -> LauncherResult<DownloadInfo> {
     let mut retries = 0;
     let mut last_error = None;
 
@@ -747,6 +720,13 @@ async fn download_file_with_retry(
                 }
                 return Ok(info);
             }
+            // 文件不存在等客户端错误重试也不会成功
+            Err(
+                e @ LauncherError::Network {
+                    status: Some(400..=499),
+                    ..
+                },
+            ) => return Err(e),
             Err(e) => {
                 last_error = Some(e);
                 retries += 1;
@@ -759,11 +739,9 @@ async fn download_file_with_retry(
 }
 
 // 下载文件
-async fn download_file(
-    url: String,
-    path: std::path::PathBuf,
-) -> Result<DownloadInfo, Box<dyn std::error::Error + Send + Sync>> {
-    let response = request::Request::new(url.clone());
+async fn download_file(url: String, path: std::path::PathBuf) -> LauncherResult<DownloadInfo> {
+    let mirror = config::current().download.mirror;
+    let response = request::Request::new(mirror.rewrite(&url));
     let content = response.fetch_get().await?;
 
     let size = content.len() as u64;
@@ -785,7 +763,7 @@ async fn download_and_verify_file(
     expected_hash: &str,
     progress: Option<DownloadProgress>,
     max_retries: u32,
-) -> Result<DownloadInfo, Box<dyn std::error::Error + Send + Sync>> {
+) -> LauncherResult<DownloadInfo> {
     let result =
         download_file_with_retry(url.clone(), path.clone(), progress.clone(), max_retries).await?;
 
@@ -801,11 +779,11 @@ async fn download_and_verify_file(
         if let Some(prog) = progress {
             prog.update_failed();
         }
-        return Err(format!(
-            "哈希值验证失败。期望：{}，实际：{}",
-            expected_hash, actual_hash
-        )
-        .into());
+        return Err(LauncherError::HashMismatch {
+            path: path.to_string_lossy().to_string(),
+            expected: expected_hash.to_string(),
+            actual: actual_hash,
+        });
     }
 
     if let Some(prog) = progress {
@@ -815,14 +793,46 @@ async fn download_and_verify_file(
     Ok(result)
 }
 
+// 下载单个文件，提供 SHA1 时校验哈希，本地已有且校验通过的文件直接跳过
+pub(crate) async fn download_verified(
+    url: &str,
+    path: &std::path::Path,
+    sha1: Option<&str>,
+) -> LauncherResult<()> {
+    if path.exists() {
+        match sha1 {
+            Some(expected) if sha1_file(path).ok().as_deref() == Some(expected) => return Ok(()),
+            None => return Ok(()),
+            _ => {}
+        }
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| LauncherError::io(parent, e))?;
+    }
+    let max_retries = config::current().download.max_retries;
+    match sha1 {
+        Some(expected) => {
+            download_and_verify_file(
+                url.to_string(),
+                path.to_path_buf(),
+                expected,
+                None,
+                max_retries,
+            )
+            .await?
+        }
+        None => {
+            download_file_with_retry(url.to_string(), path.to_path_buf(), None, max_retries).await?
+        }
+    };
+    Ok(())
+}
+
 // 获取版本清单[test]
 #[tokio::test]
 pub async fn get_version_manifest_main() -> Result<(), String> {
-    let version_manifest = Download::new(String::from(
-        "https://piston-meta.mojang.com/mc/game/version_manifest.json",
-    ));
-    let latest_version = version_manifest.dwl_version_manifest().await.unwrap();
-    println!("{}", latest_version);
+    let latest_version = load_manifest().await.unwrap().manifest;
+    println!("{}", latest_version["latest"]);
     Ok(())
 }
 
@@ -0,0 +1,325 @@
+/*
+RTLauncher, a third-party Minecraft launcher built with the newest
+technology and provides innovative funtionalities
+Copyright (C) 2025 lutouna
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+// ***
+// 本地已安装版本，启动时只读取本地版本 JSON
+// ***
+
+use super::maven::{maven_path, MavenCoordinate};
+use super::paths::MinecraftPaths;
+use super::verify::read_version_json;
+use crate::utils::error::{LauncherError, LauncherResult};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+// inheritsFrom 的最大层数，防止循环继承
+const MAX_INHERIT_DEPTH: usize = 8;
+
+// 已安装的版本
+#[derive(Debug, Clone, Serialize)]
+pub struct InstalledVersion {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub version_type: Option<String>,
+    pub inherits_from: Option<String>,
+    pub release_time: Option<String>,
+    pub asset_index: Option<String>,
+    pub java_major: Option<u64>,
+}
+
+impl InstalledVersion {
+    fn from_json(id: &str, json: &Value) -> Self {
+        Self {
+            id: id.to_string(),
+            version_type: json["type"].as_str().map(|s| s.to_string()),
+            inherits_from: json["inheritsFrom"].as_str().map(|s| s.to_string()),
+            release_time: json["releaseTime"].as_str().map(|s| s.to_string()),
+            asset_index: json["assetIndex"]["id"].as_str().map(|s| s.to_string()),
+            java_major: json["javaVersion"]["majorVersion"].as_u64(),
+        }
+    }
+}
+
+// 列出版本目录中所有带版本 JSON 的版本
+pub fn list_installed(paths: &MinecraftPaths) -> Vec<InstalledVersion> {
+    let mut versions: Vec<InstalledVersion> = std::fs::read_dir(&paths.versions_dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let id = entry.file_name().to_string_lossy().to_string();
+            resolve_version(paths, &id)
+                .ok()
+                .map(|json| InstalledVersion::from_json(&id, &json))
+        })
+        .collect();
+    versions.sort_by(|a, b| b.release_time.cmp(&a.release_time));
+    versions
+}
+
+// 读取版本 JSON 并合并 inheritsFrom 链
+pub fn resolve_version(paths: &MinecraftPaths, version_id: &str) -> LauncherResult<Value> {
+    let mut json = read_version_json(paths, version_id)?;
+    let mut jar = version_id.to_string();
+    let mut depth = 0;
+
+    while let Some(parent_id) = json["inheritsFrom"].as_str().map(|s| s.to_string()) {
+        depth += 1;
+        if depth > MAX_INHERIT_DEPTH {
+            return Err(LauncherError::Parse(format!(
+                "版本继承层数过多: {}",
+                version_id
+            )));
+        }
+        let parent = read_version_json(paths, &parent_id)?;
+        json = merge(parent, json);
+        jar = parent_id;
+    }
+
+    // 没有客户端jar的版本使用被继承版本的jar
+    if json.get("jar").and_then(|j| j.as_str()).is_none() {
+        json["jar"] = Value::String(jar);
+    }
+    json["id"] = Value::String(version_id.to_string());
+    Ok(json)
+}
+
+// 启动参数列表中允许当前系统的值，带 rules 的条目可能是字符串或数组
+fn argument_values(arguments: &Value) -> Vec<String> {
+    let mut values = Vec::new();
+    for argument in arguments.as_array().into_iter().flatten() {
+        match argument {
+            Value::String(value) => values.push(value.clone()),
+            Value::Object(_) if rules_allow(&argument["rules"]) => match &argument["value"] {
+                Value::String(value) => values.push(value.clone()),
+                Value::Array(items) => values.extend(
+                    items
+                        .iter()
+                        .filter_map(|v| v.as_str())
+                        .map(|v| v.to_string()),
+                ),
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+    values
+}
+
+// 加载器版本添加的 jvm 与游戏参数，原版参数由启动器自己生成
+// 旧版 minecraftArguments 中只取 --tweakClass
+pub fn loader_arguments(paths: &MinecraftPaths, version_id: &str) -> (Vec<String>, Vec<String>) {
+    let mut layers = Vec::new();
+    let mut id = version_id.to_string();
+    for _ in 0..MAX_INHERIT_DEPTH {
+        let Ok(json) = read_version_json(paths, &id) else {
+            break;
+        };
+        let Some(parent) = json["inheritsFrom"].as_str() else {
+            break;
+        };
+        let jvm = argument_values(&json["arguments"]["jvm"]);
+        let mut game = argument_values(&json["arguments"]["game"]);
+        if let Some(legacy) = json["minecraftArguments"].as_str() {
+            let tokens: Vec<&str> = legacy.split_whitespace().collect();
+            for pair in tokens.windows(2).filter(|pair| pair[0] == "--tweakClass") {
+                game.extend([pair[0].to_string(), pair[1].to_string()]);
+            }
+        }
+        layers.push((jvm, game));
+        id = parent.to_string();
+    }
+    // 先加入被继承版本的参数
+    layers
+        .into_iter()
+        .rev()
+        .fold((Vec::new(), Vec::new()), |(mut jvm, mut game), layer| {
+            jvm.extend(layer.0);
+            game.extend(layer.1);
+            (jvm, game)
+        })
+}
+
+// 子版本覆盖父版本，库文件与启动参数合并
+fn merge(mut parent: Value, child: Value) -> Value {
+    let Value::Object(child) = child else {
+        return parent;
+    };
+    for (key, value) in child {
+        match key.as_str() {
+            // 继续沿用父版本的 inheritsFrom
+            "inheritsFrom" => {}
+            "libraries" => {
+                let mut libraries = value.as_array().cloned().unwrap_or_default();
+                libraries.extend(parent["libraries"].as_array().cloned().unwrap_or_default());
+                parent["libraries"] = Value::Array(libraries);
+            }
+            "arguments" => {
+                for kind in ["game", "jvm"] {
+                    let mut args = parent["arguments"][kind]
+                        .as_array()
+                        .cloned()
+                        .unwrap_or_default();
+                    args.extend(value[kind].as_array().cloned().unwrap_or_default());
+                    parent["arguments"][kind] = Value::Array(args);
+                }
+            }
+            _ => parent[key] = value,
+        }
+    }
+    parent
+}
+
+// 版本 JSON rules 使用的系统名
+pub fn rule_os_name() -> &'static str {
+    match std::env::consts::OS {
+        "macos" => "osx",
+        os => os,
+    }
+}
+
+// 判断 rules 是否允许当前系统，带 features 的规则视为不满足
+pub fn rules_allow(rules: &Value) -> bool {
+    let Some(rules) = rules.as_array() else {
+        return true;
+    };
+    let mut allowed = false;
+    for rule in rules {
+        if rule.get("features").is_some() {
+            continue;
+        }
+        if let Some(name) = rule["os"]["name"].as_str() {
+            if name != rule_os_name() {
+                continue;
+            }
+        }
+        allowed = rule["action"].as_str() == Some("allow");
+    }
+    allowed
+}
+
+// 库文件在 libraries 目录中的相对路径
+pub fn library_path(library: &Value) -> Option<String> {
+    library["downloads"]["artifact"]["path"]
+        .as_str()
+        .map(|p| p.to_string())
+        .or_else(|| library["name"].as_str().and_then(maven_path))
+}
+
+// 去掉版本号的坐标，用于子版本覆盖父版本的同名库
+fn library_key(library: &Value) -> Option<String> {
+    library["name"]
+        .as_str()
+        .and_then(MavenCoordinate::parse)
+        .map(|coordinate| coordinate.key())
+}
+
+// 根据合并后的版本 JSON 生成 classpath，最后是客户端jar
+pub fn classpath(paths: &MinecraftPaths, json: &Value) -> Vec<PathBuf> {
+    let mut seen = HashSet::new();
+    let mut classpath = Vec::new();
+    for library in json["libraries"].as_array().into_iter().flatten() {
+        if !rules_allow(&library["rules"]) {
+            continue;
+        }
+        if let Some(key) = library_key(library) {
+            if !seen.insert(key) {
+                continue;
+            }
+        }
+        if let Some(path) = library_path(library) {
+            let path = paths.libraries_dir.join(path);
+            if !classpath.contains(&path) {
+                classpath.push(path);
+            }
+        }
+    }
+    let jar = json["jar"].as_str().unwrap_or_default();
+    classpath.push(paths.get_version_dir(jar).join(format!("{}.jar", jar)));
+    classpath
+}
+
+#[tauri::command]
+pub fn list_installed_versions() -> Vec<InstalledVersion> {
+    list_installed(&MinecraftPaths::new())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_inherited_version() {
+        let dir = std::env::temp_dir().join(format!("rtl-installed-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let paths = MinecraftPaths {
+            base_dir: dir.clone(),
+            versions_dir: dir.join("version"),
+            libraries_dir: dir.join("libraries"),
+            assets_dir: dir.join("assets"),
+            instances_dir: dir.join("instances"),
+        };
+        let write = |id: &str, json: Value| {
+            std::fs::create_dir_all(paths.get_version_dir(id)).unwrap();
+            std::fs::write(paths.get_version_json(id), json.to_string()).unwrap();
+        };
+        write(
+            "1.21.4",
+            serde_json::json!({
+                "id": "1.21.4",
+                "mainClass": "net.minecraft.client.main.Main",
+                "assetIndex": { "id": "19" },
+                "libraries": [
+                    { "name": "org.ow2.asm:asm:9.3", "downloads": { "artifact": { "path": "org/ow2/asm/asm/9.3/asm-9.3.jar" } } },
+                    { "name": "com.example:only-windows:1.0", "rules": [{ "action": "allow", "os": { "name": "not-this-os" } }] }
+                ]
+            }),
+        );
+        write(
+            "fabric",
+            serde_json::json!({
+                "id": "fabric",
+                "inheritsFrom": "1.21.4",
+                "mainClass": "net.fabricmc.loader.impl.launch.knot.KnotClient",
+                "libraries": [{ "name": "org.ow2.asm:asm:9.7.1" }]
+            }),
+        );
+
+        let json = resolve_version(&paths, "fabric").unwrap();
+        assert_eq!(
+            json["mainClass"],
+            "net.fabricmc.loader.impl.launch.knot.KnotClient"
+        );
+        assert_eq!(json["assetIndex"]["id"], "19");
+        assert_eq!(json["jar"], "1.21.4");
+        assert!(json.get("inheritsFrom").is_none());
+
+        let classpath = classpath(&paths, &json);
+        assert_eq!(classpath.len(), 2);
+        assert!(classpath[0].ends_with("org/ow2/asm/asm/9.7.1/asm-9.7.1.jar"));
+        assert!(classpath[1].ends_with("1.21.4/1.21.4.jar"));
+
+        let installed = list_installed(&paths);
+        assert_eq!(installed.len(), 2);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
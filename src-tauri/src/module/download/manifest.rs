@@ -0,0 +1,324 @@
+/*
+RTLauncher, a third-party Minecraft launcher built with the newest
+technology and provides innovative funtionalities
+Copyright (C) 2025 lutouna
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+// ***
+// 版本清单缓存与离线状态
+// ***
+
+use crate::utils::error::{LauncherError, LauncherResult};
+use crate::Setting::config;
+use reqwest::header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub const VERSION_MANIFEST_URL: &str =
+    "https://piston-meta.mojang.com/mc/game/version_manifest_v2.json";
+
+const MANIFEST_FILE: &str = "version_manifest_v2.json";
+const META_FILE: &str = "version_manifest_v2.meta.json";
+// 网络检测超时
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+// 缓存的响应头，用于条件请求
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct CacheMeta {
+    etag: Option<String>,
+    last_modified: Option<String>,
+    fetched_at: u64,
+}
+
+// 清单来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ManifestSource {
+    Network,     // 下载了新的清单
+    NotModified, // 服务器返回 304，使用缓存
+    Cache,       // 网络不可用，使用缓存
+}
+
+#[derive(Debug, Clone)]
+pub struct CachedManifest {
+    pub manifest: serde_json::Value,
+    pub source: ManifestSource,
+    pub fetched_at: u64,
+}
+
+// 版本清单缓存
+pub struct ManifestCache {
+    dir: PathBuf,
+}
+
+impl Default for ManifestCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ManifestCache {
+    pub fn new() -> Self {
+        Self::with_dir(config::cache_dir())
+    }
+
+    pub fn with_dir(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    fn read_meta(&self) -> Option<CacheMeta> {
+        let content = std::fs::read_to_string(self.dir.join(META_FILE)).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    // 读取缓存的清单
+    pub fn cached(&self) -> Option<CachedManifest> {
+        let content = std::fs::read_to_string(self.dir.join(MANIFEST_FILE)).ok()?;
+        let manifest = serde_json::from_str(&content).ok()?;
+        Some(CachedManifest {
+            manifest,
+            source: ManifestSource::Cache,
+            fetched_at: self.read_meta().map(|m| m.fetched_at).unwrap_or_default(),
+        })
+    }
+
+    fn write(&self, file: &str, content: &str) -> LauncherResult<()> {
+        std::fs::create_dir_all(&self.dir).map_err(|e| LauncherError::io(&self.dir, e))?;
+        let path = self.dir.join(file);
+        std::fs::write(&path, content).map_err(|e| LauncherError::io(&path, e))
+    }
+
+    // 使用 ETag / Last-Modified 刷新清单，网络不可用时回退到缓存
+    pub async fn fetch(&self, url: &str) -> LauncherResult<CachedManifest> {
+        match self.refresh(url).await {
+            Ok(result) => Ok(result),
+            Err(e) => match self.cached() {
+                Some(cached) => {
+                    log::warn!("版本清单刷新失败，使用缓存: {}", e);
+                    Ok(cached)
+                }
+                None => Err(e),
+            },
+        }
+    }
+
+    async fn refresh(&self, url: &str) -> LauncherResult<CachedManifest> {
+        let cached = self.cached();
+        let meta = cached
+            .as_ref()
+            .and_then(|_| self.read_meta())
+            .unwrap_or_default();
+
+        let mut request = reqwest::Client::new().get(url);
+        if let Some(etag) = &meta.etag {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = &meta.last_modified {
+            request = request.header(IF_MODIFIED_SINCE, last_modified);
+        }
+        let response = request.send().await?;
+
+        if response.status() == StatusCode::NOT_MODIFIED {
+            if let Some(cached) = cached {
+                let meta = CacheMeta {
+                    fetched_at: now_secs(),
+                    ..meta
+                };
+                self.write(META_FILE, &serde_json::to_string(&meta)?)?;
+                return Ok(CachedManifest {
+                    source: ManifestSource::NotModified,
+                    fetched_at: meta.fetched_at,
+                    ..cached
+                });
+            }
+        }
+
+        let response = response.error_for_status()?;
+        let header = |name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|v: &reqwest::header::HeaderValue| v.to_str().ok())
+                .map(|v| v.to_string())
+        };
+        let meta = CacheMeta {
+            etag: header(ETAG),
+            last_modified: header(LAST_MODIFIED),
+            fetched_at: now_secs(),
+        };
+        let body = response.text().await?;
+        let manifest = serde_json::from_str(&body)?;
+        self.write(MANIFEST_FILE, &body)?;
+        self.write(META_FILE, &serde_json::to_string(&meta)?)?;
+        Ok(CachedManifest {
+            manifest,
+            source: ManifestSource::Network,
+            fetched_at: meta.fetched_at,
+        })
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+// 获取版本清单，优先使用缓存的条件请求
+pub async fn load_manifest() -> LauncherResult<CachedManifest> {
+    let url = config::current()
+        .download
+        .mirror
+        .rewrite(VERSION_MANIFEST_URL);
+    ManifestCache::new().fetch(&url).await
+}
+
+// 需要联网的功能
+#[derive(Debug, Clone, Serialize)]
+pub struct NetworkFeature {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub available: bool,
+    pub note: &'static str,
+}
+
+// 网络状态
+#[derive(Debug, Clone, Serialize)]
+pub struct NetworkStatus {
+    pub online: bool,
+    pub manifest_cached_at: Option<u64>,
+    pub features: Vec<NetworkFeature>,
+}
+
+// 根据网络和缓存情况列出各功能是否可用
+pub fn network_features(online: bool, has_manifest_cache: bool) -> Vec<NetworkFeature> {
+    let feature = |id, name, available, note| NetworkFeature {
+        id,
+        name,
+        available,
+        note,
+    };
+    vec![
+        feature(
+            "launch_installed",
+            "启动已安装版本",
+            true,
+            "只读取本地版本 JSON",
+        ),
+        feature("instances", "实例管理", true, "只读取本地文件"),
+        feature(
+            "version_list",
+            "版本列表",
+            online || has_manifest_cache,
+            "离线时显示缓存的版本清单",
+        ),
+        feature("install_version", "安装新版本", online, "需要下载游戏文件"),
+        feature(
+            "microsoft_login",
+            "微软账户登录",
+            online,
+            "需要访问微软与 Xbox 服务",
+        ),
+        feature(
+            "content_download",
+            "模组与资源下载",
+            online,
+            "需要访问 Modrinth 或 CurseForge",
+        ),
+    ]
+}
+
+// 检测是否能访问版本清单服务器
+async fn probe(url: &str) -> bool {
+    let client = match reqwest::Client::builder().timeout(PROBE_TIMEOUT).build() {
+        Ok(client) => client,
+        Err(_) => return false,
+    };
+    client.head(url).send().await.is_ok()
+}
+
+#[tauri::command]
+pub async fn get_network_status() -> NetworkStatus {
+    let url = config::current()
+        .download
+        .mirror
+        .rewrite(VERSION_MANIFEST_URL);
+    let online = probe(&url).await;
+    let cached_at = ManifestCache::new().cached().map(|c| c.fetched_at);
+    NetworkStatus {
+        online,
+        manifest_cached_at: cached_at,
+        features: network_features(online, cached_at.is_some()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    // 第一次返回清单和 ETag，带 If-None-Match 的请求返回 304
+    async fn serve(listener: TcpListener, requests: usize) {
+        for _ in 0..requests {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buffer = vec![0u8; 4096];
+            let read = stream.read(&mut buffer).await.unwrap();
+            let request = String::from_utf8_lossy(&buffer[..read]).to_lowercase();
+            let response = if request.contains("if-none-match: \"v1\"") {
+                "HTTP/1.1 304 Not Modified\r\nContent-Length: 0\r\n\r\n".to_string()
+            } else {
+                let body = r#"{"latest":{"release":"1.21.4"},"versions":[]}"#;
+                format!(
+                    "HTTP/1.1 200 OK\r\nETag: \"v1\"\r\nContent-Length: {}\r\n\r\n{}",
+                    body.len(),
+                    body
+                )
+            };
+            stream.write_all(response.as_bytes()).await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_manifest_cache_revalidation() {
+        let dir = std::env::temp_dir().join(format!("rtl-manifest-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/manifest.json", listener.local_addr().unwrap());
+        let server = tokio::spawn(serve(listener, 2));
+
+        let cache = ManifestCache::with_dir(dir.clone());
+        let first = cache.fetch(&url).await.unwrap();
+        assert_eq!(first.source, ManifestSource::Network);
+        let second = cache.fetch(&url).await.unwrap();
+        assert_eq!(second.source, ManifestSource::NotModified);
+        assert_eq!(second.manifest["latest"]["release"], "1.21.4");
+        server.await.unwrap();
+
+        // 服务器已关闭，回退到缓存
+        let offline = cache.fetch(&url).await.unwrap();
+        assert_eq!(offline.source, ManifestSource::Cache);
+        assert!(!network_features(false, true)
+            .iter()
+            .any(|f| f.id == "version_list" && !f.available));
+
+        let _ = std::fs::remove_dir_all(&dir);
+        assert!(ManifestCache::with_dir(dir).fetch(&url).await.is_err());
+    }
+}
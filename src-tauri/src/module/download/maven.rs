@@ -0,0 +1,334 @@
+/*
+RTLauncher, a third-party Minecraft launcher built with the newest
+technology and provides innovative funtionalities
+Copyright (C) 2025 lutouna
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+// ***
+// Maven 坐标解析与仓库回退下载
+// ***
+
+use super::dwl_main::download_verified;
+use super::paths::MinecraftPaths;
+use crate::utils::error::{LauncherError, LauncherResult};
+use crate::utils::request;
+use crate::Setting::config;
+use serde_json::Value;
+use std::path::PathBuf;
+
+pub const MOJANG_LIBRARIES_URL: &str = "https://libraries.minecraft.net/";
+pub const MAVEN_CENTRAL_URL: &str = "https://repo1.maven.org/maven2/";
+
+const SNAPSHOT_SUFFIX: &str = "-SNAPSHOT";
+
+// group:artifact:version[:classifier][@ext]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MavenCoordinate {
+    pub group: String,
+    pub artifact: String,
+    pub version: String,
+    pub classifier: Option<String>,
+    pub extension: String,
+}
+
+impl MavenCoordinate {
+    pub fn parse(name: &str) -> Option<Self> {
+        let (coordinate, extension) = name.split_once('@').unwrap_or((name, "jar"));
+        let parts: Vec<&str> = coordinate.split(':').collect();
+        if parts.len() < 3 || parts.len() > 4 || parts.iter().any(|p| p.is_empty()) {
+            return None;
+        }
+        Some(Self {
+            group: parts[0].to_string(),
+            artifact: parts[1].to_string(),
+            version: parts[2].to_string(),
+            classifier: parts.get(3).map(|c| c.to_string()),
+            extension: extension.to_string(),
+        })
+    }
+
+    pub fn is_snapshot(&self) -> bool {
+        self.version.ends_with(SNAPSHOT_SUFFIX)
+    }
+
+    // 去掉版本号的坐标，同名库只保留一个
+    pub fn key(&self) -> String {
+        format!(
+            "{}:{}:{}",
+            self.group,
+            self.artifact,
+            self.classifier.as_deref().unwrap_or_default()
+        )
+    }
+
+    // 版本目录 group/artifact/version
+    fn dir(&self) -> String {
+        format!(
+            "{}/{}/{}",
+            self.group.replace('.', "/"),
+            self.artifact,
+            self.version
+        )
+    }
+
+    // 指定文件版本的文件名，快照在仓库中使用带时间戳的文件版本
+    fn file_name(&self, file_version: &str) -> String {
+        match &self.classifier {
+            Some(classifier) => format!(
+                "{}-{}-{}.{}",
+                self.artifact, file_version, classifier, self.extension
+            ),
+            None => format!("{}-{}.{}", self.artifact, file_version, self.extension),
+        }
+    }
+
+    // 仓库与本地 libraries 目录中的相对路径
+    pub fn path(&self) -> String {
+        format!("{}/{}", self.dir(), self.file_name(&self.version))
+    }
+
+    // 快照在仓库中的路径，timestamp-buildNumber 替换 SNAPSHOT
+    pub fn snapshot_path(&self, timestamp: &str, build_number: &str) -> String {
+        let base = self.version.trim_end_matches(SNAPSHOT_SUFFIX);
+        let file_version = format!("{}-{}-{}", base, timestamp, build_number);
+        format!("{}/{}", self.dir(), self.file_name(&file_version))
+    }
+
+    // 快照版本目录中的 maven-metadata.xml
+    fn metadata_path(&self) -> String {
+        format!("{}/maven-metadata.xml", self.dir())
+    }
+}
+
+// 将 Maven 坐标转为仓库中的相对路径
+pub fn maven_path(name: &str) -> Option<String> {
+    MavenCoordinate::parse(name).map(|coordinate| coordinate.path())
+}
+
+// 读取 XML 中第一个指定标签的内容
+fn xml_value<'a>(xml: &'a str, tag: &str) -> Option<&'a str> {
+    let start = xml.find(&format!("<{}>", tag))? + tag.len() + 2;
+    let end = xml[start..].find(&format!("</{}>", tag))? + start;
+    Some(xml[start..end].trim())
+}
+
+// 从快照 maven-metadata.xml 中读取 timestamp 和 buildNumber
+pub fn parse_snapshot_metadata(xml: &str) -> Option<(String, String)> {
+    let snapshot = xml_value(xml, "snapshot")?;
+    Some((
+        xml_value(snapshot, "timestamp")?.to_string(),
+        xml_value(snapshot, "buildNumber")?.to_string(),
+    ))
+}
+
+// 库文件的下载计划
+#[derive(Debug, Clone)]
+pub struct LibraryDownload {
+    pub coordinate: Option<MavenCoordinate>,
+    pub path: PathBuf,
+    // 按顺序尝试的仓库或完整下载地址
+    pub repositories: Vec<String>,
+    // downloads.artifact 给出的完整地址
+    pub url: Option<String>,
+    pub sha1: Option<String>,
+}
+
+// 库文件依次尝试的仓库：库自带仓库、Mojang、Maven Central，最后是镜像
+pub fn repositories(library: &Value) -> Vec<String> {
+    let mut repositories: Vec<String> = Vec::new();
+    let mut push = |repository: &str| {
+        let repository = format!("{}/", repository.trim_end_matches('/'));
+        if !repositories.contains(&repository) {
+            repositories.push(repository);
+        }
+    };
+    if let Some(url) = library["url"].as_str().filter(|url| !url.is_empty()) {
+        push(url);
+    }
+    push(MOJANG_LIBRARIES_URL);
+    push(MAVEN_CENTRAL_URL);
+    if let Some(mirror) = config::current().download.mirror.maven_repository() {
+        push(&mirror);
+    }
+    repositories
+}
+
+// 解析库文件条目，有 downloads.artifact 时使用其地址，否则按 name 坐标在仓库中查找
+pub fn library_download(paths: &MinecraftPaths, library: &Value) -> Option<LibraryDownload> {
+    let coordinate = library["name"].as_str().and_then(MavenCoordinate::parse);
+    let artifact = &library["downloads"]["artifact"];
+    if let Some(path) = artifact["path"].as_str() {
+        // url 为空的库由安装器生成，不需要下载
+        let url = artifact["url"].as_str().filter(|url| !url.is_empty())?;
+        return Some(LibraryDownload {
+            coordinate,
+            path: paths.libraries_dir.join(path),
+            repositories: Vec::new(),
+            url: Some(url.to_string()),
+            sha1: artifact["sha1"].as_str().map(|s| s.to_string()),
+        });
+    }
+    let coordinate = coordinate?;
+    Some(LibraryDownload {
+        path: paths.libraries_dir.join(coordinate.path()),
+        repositories: repositories(library),
+        url: None,
+        sha1: library["sha1"].as_str().map(|s| s.to_string()),
+        coordinate: Some(coordinate),
+    })
+}
+
+// 读取 .sha1 校验文件，内容可能带有文件名
+async fn fetch_sha1_sidecar(url: &str) -> Option<String> {
+    let body = request::Request::new(format!("{}.sha1", url))
+        .fetch_get()
+        .await
+        .ok()?;
+    let sha1 = body.split_whitespace().next()?.to_lowercase();
+    (sha1.len() == 40 && sha1.chars().all(|c| c.is_ascii_hexdigit())).then_some(sha1)
+}
+
+// 快照在仓库中的实际地址，没有元数据时使用 SNAPSHOT 文件名
+async fn snapshot_url(repository: &str, coordinate: &MavenCoordinate) -> String {
+    let metadata = request::Request::new(format!("{}{}", repository, coordinate.metadata_path()))
+        .fetch_get()
+        .await
+        .ok();
+    match metadata.as_deref().and_then(parse_snapshot_metadata) {
+        Some((timestamp, build_number)) => format!(
+            "{}{}",
+            repository,
+            coordinate.snapshot_path(&timestamp, &build_number)
+        ),
+        None => format!("{}{}", repository, coordinate.path()),
+    }
+}
+
+impl LibraryDownload {
+    // 依次尝试下载地址，没有 SHA1 时使用仓库提供的 .sha1 校验
+    pub async fn download(&self) -> LauncherResult<()> {
+        if self.sha1.is_none() && self.path.exists() {
+            return Ok(());
+        }
+        let mut last_error = None;
+        for url in self.urls().await {
+            let sha1 = match &self.sha1 {
+                Some(sha1) => Some(sha1.clone()),
+                None => fetch_sha1_sidecar(&url).await,
+            };
+            match download_verified(&url, &self.path, sha1.as_deref()).await {
+                Ok(()) => return Ok(()),
+                Err(e) => {
+                    log::debug!("仓库下载失败，尝试下一个: {} -> {}", url, e);
+                    last_error = Some(e);
+                }
+            }
+        }
+        Err(last_error.unwrap_or_else(|| {
+            LauncherError::Other(format!("没有可用的下载地址: {}", self.path.display()))
+        }))
+    }
+
+    async fn urls(&self) -> Vec<String> {
+        if let Some(url) = &self.url {
+            return vec![url.clone()];
+        }
+        let Some(coordinate) = &self.coordinate else {
+            return Vec::new();
+        };
+        let mut urls = Vec::new();
+        for repository in &self.repositories {
+            urls.push(match coordinate.is_snapshot() {
+                true => snapshot_url(repository, coordinate).await,
+                false => format!("{}{}", repository, coordinate.path()),
+            });
+        }
+        urls
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::module::loader::fake_server;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_parse_coordinates() {
+        let coordinate = MavenCoordinate::parse("net.fabricmc:intermediary:1.21.4:v2@zip").unwrap();
+        assert_eq!(
+            coordinate.path(),
+            "net/fabricmc/intermediary/1.21.4/intermediary-1.21.4-v2.zip"
+        );
+        assert_eq!(coordinate.key(), "net.fabricmc:intermediary:v2");
+        assert!(MavenCoordinate::parse("broken:name").is_none());
+
+        let snapshot = MavenCoordinate::parse("com.example:lib:1.0-SNAPSHOT").unwrap();
+        assert!(snapshot.is_snapshot());
+        let xml = "<metadata><versioning><snapshot><timestamp>20240101.120000</timestamp><buildNumber>3</buildNumber></snapshot></versioning></metadata>";
+        let (timestamp, build) = parse_snapshot_metadata(xml).unwrap();
+        assert_eq!(
+            snapshot.snapshot_path(&timestamp, &build),
+            "com/example/lib/1.0-SNAPSHOT/lib-1.0-20240101.120000-3.jar"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_repository_fallback_with_sidecar() {
+        use sha1::Digest;
+        let jar = b"library".to_vec();
+        let sha1 = format!("{:x}", sha1::Sha1::digest(&jar));
+        let (base, server) = fake_server::serve(HashMap::from([
+            ("/second/com/example/lib/1.0/lib-1.0.jar".to_string(), jar),
+            (
+                "/second/com/example/lib/1.0/lib-1.0.jar.sha1".to_string(),
+                format!("{}  lib-1.0.jar", sha1).into_bytes(),
+            ),
+            (
+                "/second/com/example/bad/1.0/bad-1.0.jar".to_string(),
+                b"bad".to_vec(),
+            ),
+            (
+                "/second/com/example/bad/1.0/bad-1.0.jar.sha1".to_string(),
+                sha1.clone().into_bytes(),
+            ),
+        ]))
+        .await;
+
+        let dir = std::env::temp_dir().join(format!("rtl-maven-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let paths = MinecraftPaths::with_base_dir(dir.clone());
+        let plan = |name: &str| {
+            let mut plan = library_download(&paths, &serde_json::json!({ "name": name })).unwrap();
+            plan.repositories = vec![format!("{}/first/", base), format!("{}/second/", base)];
+            plan
+        };
+
+        let lib = plan("com.example:lib:1.0");
+        lib.download().await.unwrap();
+        assert_eq!(std::fs::read(&lib.path).unwrap(), b"library");
+        // 校验文件与内容不符时拒绝
+        let bad = plan("com.example:bad:1.0");
+        assert!(matches!(
+            bad.download().await,
+            Err(LauncherError::HashMismatch { .. })
+        ));
+        assert!(!bad.path.exists());
+
+        server.abort();
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -19,7 +19,11 @@ along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
 pub mod decompression;
 pub mod dwl_main;
+pub mod installed;
+pub mod manifest;
+pub mod maven;
 pub mod paths;
+pub mod verify;
 
 use std::env::consts::OS;
 
@@ -21,6 +21,7 @@ along with this program.  If not, see <https://www.gnu.org/licenses/>.
 // 路径配置管理模块
 // ***
 
+use crate::Setting::config;
 use std::path::PathBuf;
 
 pub struct MinecraftPaths {
@@ -28,15 +29,27 @@ pub struct MinecraftPaths {
     pub versions_dir: PathBuf,
     pub libraries_dir: PathBuf,
     pub assets_dir: PathBuf,
+    pub instances_dir: PathBuf,
+}
+
+impl Default for MinecraftPaths {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl MinecraftPaths {
     pub fn new() -> Self {
-        let base_dir = PathBuf::from("D:\\Desktop\\.minecraft");
+        Self::with_base_dir(config::current().game_dir())
+    }
+
+    // 使用指定的游戏根目录
+    pub fn with_base_dir(base_dir: PathBuf) -> Self {
         Self {
             versions_dir: base_dir.join("version"),
             libraries_dir: base_dir.join("libraries"),
             assets_dir: base_dir.join("assets"),
+            instances_dir: base_dir.join("instances"),
             base_dir: base_dir,
         }
     }
@@ -45,17 +58,29 @@ impl MinecraftPaths {
         self.versions_dir.join(version_id)
     }
 
+    // 版本 JSON 文件
+    pub fn get_version_json(&self, version_id: &str) -> PathBuf {
+        self.get_version_dir(version_id)
+            .join(format!("{}.json", version_id))
+    }
+
     pub fn get_natives_dir(&self, version_id: &str) -> PathBuf {
         self.get_version_dir(version_id)
             .join(format!("{}-natives", version_id))
     }
 
+    // 实例的独立游戏目录
+    pub fn get_instance_dir(&self, instance_id: &str) -> PathBuf {
+        self.instances_dir.join(instance_id)
+    }
+
     #[allow(dead_code)]
     pub fn ensure_dirs(&self) -> std::io::Result<()> {
         std::fs::create_dir_all(&self.base_dir)?;
         std::fs::create_dir_all(&self.versions_dir)?;
         std::fs::create_dir_all(&self.libraries_dir)?;
         std::fs::create_dir_all(&self.assets_dir)?;
+        std::fs::create_dir_all(&self.instances_dir)?;
         Ok(())
     }
 
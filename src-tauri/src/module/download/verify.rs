@@ -0,0 +1,246 @@
+/*
+RTLauncher, a third-party Minecraft launcher built with the newest
+technology and provides innovative funtionalities
+Copyright (C) 2025 lutouna
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+// ***
+// 校验已安装版本的文件完整性
+// ***
+
+use super::installed::rule_os_name;
+use super::paths::MinecraftPaths;
+use crate::utils::error::{LauncherError, LauncherResult};
+use rayon::prelude::*;
+use serde::Serialize;
+use sha1::Digest;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+// 校验结果
+#[derive(Debug, Clone, Serialize)]
+pub struct VerifyReport {
+    pub version_id: String,
+    pub checked: usize,
+    pub missing: Vec<String>,
+    pub corrupted: Vec<String>,
+}
+
+impl VerifyReport {
+    pub fn is_ok(&self) -> bool {
+        self.missing.is_empty() && self.corrupted.is_empty()
+    }
+}
+
+// 计算文件的 SHA-1
+pub fn sha1_file(path: &Path) -> std::io::Result<String> {
+    hash_file::<sha1::Sha1>(path)
+}
+
+// 计算文件的 SHA-512
+pub fn sha512_file(path: &Path) -> std::io::Result<String> {
+    hash_file::<sha2::Sha512>(path)
+}
+
+fn hash_file<D: Digest>(path: &Path) -> std::io::Result<String> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = D::new();
+    let mut buffer = [0u8; 64 * 1024];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect())
+}
+
+// 读取本地版本 JSON
+pub fn read_version_json(
+    paths: &MinecraftPaths,
+    version_id: &str,
+) -> LauncherResult<serde_json::Value> {
+    let path = paths.get_version_json(version_id);
+    let content = std::fs::read_to_string(&path)
+        .map_err(|_| LauncherError::VersionNotFound(version_id.to_string()))?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+// 收集需要校验的文件：路径与期望的 SHA-1
+// natives 分类名，版本 JSON 中 macOS 为 natives-osx，部分库使用 natives-macos
+fn natives_keys(os: &str) -> &'static [&'static str] {
+    match os {
+        "windows" => &["natives-windows"],
+        "osx" => &["natives-osx", "natives-macos"],
+        _ => &["natives-linux"],
+    }
+}
+
+fn collect_files(
+    paths: &MinecraftPaths,
+    version_id: &str,
+    json: &serde_json::Value,
+) -> Vec<(PathBuf, String)> {
+    let mut files = Vec::new();
+
+    // 客户端jar
+    if let Some(sha1) = json["downloads"]["client"]["sha1"].as_str() {
+        files.push((
+            paths
+                .get_version_dir(version_id)
+                .join(format!("{}.jar", version_id)),
+            sha1.to_string(),
+        ));
+    }
+
+    // 库文件
+    let natives_keys = natives_keys(rule_os_name());
+    for library in json["libraries"].as_array().into_iter().flatten() {
+        let downloads = &library["downloads"];
+        let natives = natives_keys
+            .iter()
+            .map(|key| &downloads["classifiers"][*key])
+            .find(|artifact| !artifact.is_null())
+            .unwrap_or(&serde_json::Value::Null);
+        for artifact in [&downloads["artifact"], natives] {
+            if let (Some(path), Some(sha1)) = (artifact["path"].as_str(), artifact["sha1"].as_str())
+            {
+                files.push((paths.libraries_dir.join(path), sha1.to_string()));
+            }
+        }
+    }
+
+    // 资源文件
+    if let Some(asset_id) = json["assetIndex"]["id"].as_str() {
+        let index_path = paths
+            .assets_dir
+            .join("indexes")
+            .join(format!("{}.json", asset_id));
+        if let Some(sha1) = json["assetIndex"]["sha1"].as_str() {
+            files.push((index_path.clone(), sha1.to_string()));
+        }
+        let index = std::fs::read_to_string(&index_path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok());
+        if let Some(objects) = index.as_ref().and_then(|i| i["objects"].as_object()) {
+            for object in objects.values() {
+                if let Some(hash) = object["hash"].as_str().filter(|h| h.len() > 2) {
+                    files.push((
+                        paths.assets_dir.join("objects").join(&hash[..2]).join(hash),
+                        hash.to_string(),
+                    ));
+                }
+            }
+        }
+    }
+
+    files
+}
+
+// 校验版本的客户端、库文件和资源文件
+pub fn verify_installed(paths: &MinecraftPaths, version_id: &str) -> LauncherResult<VerifyReport> {
+    let json = read_version_json(paths, version_id)?;
+    let files = collect_files(paths, version_id, &json);
+
+    let results: Vec<(String, Option<bool>)> = files
+        .par_iter()
+        .map(|(path, expected)| {
+            let name = path.to_string_lossy().to_string();
+            match sha1_file(path) {
+                Ok(actual) => (name, Some(actual.eq_ignore_ascii_case(expected))),
+                Err(_) => (name, None),
+            }
+        })
+        .collect();
+
+    let mut report = VerifyReport {
+        version_id: version_id.to_string(),
+        checked: results.len(),
+        missing: Vec::new(),
+        corrupted: Vec::new(),
+    };
+    for (name, result) in results {
+        match result {
+            None => report.missing.push(name),
+            Some(false) => report.corrupted.push(name),
+            Some(true) => {}
+        }
+    }
+    Ok(report)
+}
+
+#[tauri::command]
+pub async fn verify_version(version_id: String) -> LauncherResult<VerifyReport> {
+    tokio::task::spawn_blocking(move || verify_installed(&MinecraftPaths::new(), &version_id))
+        .await?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify_installed() {
+        let dir = std::env::temp_dir().join(format!("rtl-verify-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let paths = MinecraftPaths {
+            base_dir: dir.clone(),
+            versions_dir: dir.join("version"),
+            libraries_dir: dir.join("libraries"),
+            assets_dir: dir.join("assets"),
+            instances_dir: dir.join("instances"),
+        };
+
+        // "hello" 的 SHA-1
+        let hello = "aaf4c61ddcc5e8a2dabede0f3b482cd9aea9434d";
+        let json = serde_json::json!({
+            "id": "test",
+            "downloads": { "client": { "sha1": hello } },
+            "libraries": [
+                { "downloads": { "artifact": { "path": "a/b/lib.jar", "sha1": hello } } },
+                { "downloads": { "artifact": { "path": "a/b/missing.jar", "sha1": hello } } }
+            ]
+        });
+        std::fs::create_dir_all(paths.get_version_dir("test")).unwrap();
+        std::fs::write(paths.get_version_json("test"), json.to_string()).unwrap();
+        std::fs::write(paths.get_version_dir("test").join("test.jar"), "hello").unwrap();
+        std::fs::create_dir_all(paths.libraries_dir.join("a/b")).unwrap();
+        std::fs::write(paths.libraries_dir.join("a/b/lib.jar"), "broken").unwrap();
+
+        let report = verify_installed(&paths, "test").unwrap();
+        assert_eq!(report.checked, 3);
+        assert_eq!(report.missing.len(), 1);
+        assert_eq!(report.corrupted.len(), 1);
+        assert!(!report.is_ok());
+        assert!(matches!(
+            verify_installed(&paths, "none"),
+            Err(LauncherError::VersionNotFound(_))
+        ));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_natives_keys() {
+        assert_eq!(natives_keys("osx")[0], "natives-osx");
+        assert_eq!(natives_keys("windows"), ["natives-windows"]);
+    }
+}
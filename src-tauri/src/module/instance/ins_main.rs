@@ -0,0 +1,331 @@
+/*
+RTLauncher, a third-party Minecraft launcher built with the newest
+technology and provides innovative funtionalities
+Copyright (C) 2025 lutouna
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+// ***
+// 实例管理，每个实例拥有独立的游戏目录
+// ***
+
+use crate::module::download::paths::MinecraftPaths;
+use crate::utils::error::{LauncherError, LauncherResult};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// 实例配置文件名
+pub const INSTANCE_FILE: &str = "instance.json";
+
+// 实例
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Instance {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub icon: Option<String>,
+    pub version_id: String,
+    #[serde(default)]
+    pub created_at: u64,
+    #[serde(default)]
+    pub last_played: Option<u64>,
+    #[serde(default)]
+    pub java_path: Option<String>,
+    #[serde(default)]
+    pub java_version: Option<String>,
+    #[serde(default)]
+    pub min_memory_mb: Option<u32>,
+    #[serde(default)]
+    pub max_memory_mb: Option<u32>,
+    #[serde(default)]
+    pub jvm_args: Vec<String>,
+    // 实例所在的存储目录，不写入配置文件
+    #[serde(skip)]
+    instances_dir: Option<PathBuf>,
+}
+
+impl Instance {
+    pub fn new(id: String, name: String, version_id: String) -> Self {
+        Self {
+            id,
+            name,
+            icon: None,
+            version_id,
+            created_at: now_millis(),
+            last_played: None,
+            java_path: None,
+            java_version: None,
+            min_memory_mb: None,
+            max_memory_mb: None,
+            jvm_args: Vec::new(),
+            instances_dir: None,
+        }
+    }
+
+    // 实例的游戏目录，位于读取该实例的存储目录下
+    pub fn game_dir(&self) -> PathBuf {
+        match &self.instances_dir {
+            Some(dir) => dir.join(&self.id),
+            None => MinecraftPaths::new().get_instance_dir(&self.id),
+        }
+    }
+
+    // 是否覆盖了全局的内存或JVM参数
+    pub fn has_jvm_override(&self) -> bool {
+        self.min_memory_mb.is_some() || self.max_memory_mb.is_some() || !self.jvm_args.is_empty()
+    }
+}
+
+// 实例存储
+pub struct InstanceStore {
+    instances_dir: PathBuf,
+}
+
+impl Default for InstanceStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl InstanceStore {
+    pub fn new() -> Self {
+        Self::with_dir(MinecraftPaths::new().instances_dir)
+    }
+
+    pub fn with_dir(instances_dir: PathBuf) -> Self {
+        Self { instances_dir }
+    }
+
+    fn instance_file(&self, id: &str) -> PathBuf {
+        self.instances_dir.join(id).join(INSTANCE_FILE)
+    }
+
+    // 实例ID只能是单层目录名
+    fn check_id(id: &str) -> LauncherResult<()> {
+        if id.is_empty() || id == "." || id == ".." || id.contains(['/', '\\']) {
+            return Err(LauncherError::InvalidArgument(format!(
+                "无效的实例ID: {}",
+                id
+            )));
+        }
+        Ok(())
+    }
+
+    // 列出所有实例
+    pub fn list(&self) -> Vec<Instance> {
+        let mut instances: Vec<Instance> = std::fs::read_dir(&self.instances_dir)
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| self.read(&entry.path().join(INSTANCE_FILE)).ok())
+            .collect();
+        instances.sort_by(|a, b| {
+            b.last_played
+                .cmp(&a.last_played)
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        instances
+    }
+
+    pub fn get(&self, id: &str) -> LauncherResult<Instance> {
+        Self::check_id(id)?;
+        let path = self.instance_file(id);
+        if !path.exists() {
+            return Err(LauncherError::InstanceNotFound(id.to_string()));
+        }
+        self.read(&path)
+    }
+
+    fn read(&self, path: &Path) -> LauncherResult<Instance> {
+        let content = std::fs::read_to_string(path).map_err(|e| LauncherError::io(path, e))?;
+        let instance: Instance = serde_json::from_str(&content)?;
+        Ok(Instance {
+            instances_dir: Some(self.instances_dir.clone()),
+            ..instance
+        })
+    }
+
+    // 创建实例并建立独立的游戏目录
+    pub fn create(&self, name: &str, version_id: &str) -> LauncherResult<Instance> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(LauncherError::InvalidArgument(
+                "实例名称不能为空".to_string(),
+            ));
+        }
+        let id = self.unique_id(name);
+        let instance = Instance {
+            instances_dir: Some(self.instances_dir.clone()),
+            ..Instance::new(id, name.to_string(), version_id.to_string())
+        };
+        let game_dir = instance.game_dir();
+        for dir in ["mods", "saves", "config", "resourcepacks"] {
+            let dir = game_dir.join(dir);
+            std::fs::create_dir_all(&dir).map_err(|e| LauncherError::io(&dir, e))?;
+        }
+        self.save(&instance)?;
+        Ok(instance)
+    }
+
+    // 保存实例配置
+    pub fn save(&self, instance: &Instance) -> LauncherResult<()> {
+        Self::check_id(&instance.id)?;
+        let path = self.instance_file(&instance.id);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| LauncherError::io(parent, e))?;
+        }
+        let content = serde_json::to_string_pretty(instance)?;
+        std::fs::write(&path, content).map_err(|e| LauncherError::io(&path, e))
+    }
+
+    // 更新实例，id 与创建时间保持不变
+    pub fn update(&self, instance: Instance) -> LauncherResult<Instance> {
+        let current = self.get(&instance.id)?;
+        let updated = Instance {
+            created_at: current.created_at,
+            instances_dir: current.instances_dir,
+            ..instance
+        };
+        self.save(&updated)?;
+        Ok(updated)
+    }
+
+    // 删除实例及其游戏目录
+    pub fn delete(&self, id: &str) -> LauncherResult<()> {
+        Self::check_id(id)?;
+        let dir = self.instances_dir.join(id);
+        if !self.instance_file(id).exists() {
+            return Err(LauncherError::InstanceNotFound(id.to_string()));
+        }
+        std::fs::remove_dir_all(&dir).map_err(|e| LauncherError::io(&dir, e))
+    }
+
+    // 记录最近游玩时间
+    pub fn touch(&self, id: &str) -> LauncherResult<()> {
+        let mut instance = self.get(id)?;
+        instance.last_played = Some(now_millis());
+        self.save(&instance)
+    }
+
+    // 根据名称生成目录名，重名时追加序号
+    fn unique_id(&self, name: &str) -> String {
+        let base: String = name
+            .chars()
+            .map(|c| {
+                if c.is_alphanumeric() || c == '-' || c == '_' || c == '.' {
+                    c
+                } else {
+                    '-'
+                }
+            })
+            .collect::<String>()
+            .trim_matches(|c| c == '-' || c == '.')
+            .to_string();
+        let base = if base.is_empty() {
+            "instance".to_string()
+        } else {
+            base
+        };
+
+        let mut id = base.clone();
+        let mut index = 1;
+        while self.instances_dir.join(&id).exists() {
+            index += 1;
+            id = format!("{}-{}", base, index);
+        }
+        id
+    }
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+#[tauri::command]
+pub fn list_instances() -> Vec<Instance> {
+    InstanceStore::new().list()
+}
+
+#[tauri::command]
+pub fn get_instance(id: String) -> LauncherResult<Instance> {
+    InstanceStore::new().get(&id)
+}
+
+#[tauri::command]
+pub fn create_instance(
+    name: String,
+    version_id: String,
+    icon: Option<String>,
+) -> LauncherResult<Instance> {
+    let store = InstanceStore::new();
+    let mut instance = store.create(&name, &version_id)?;
+    if icon.is_some() {
+        instance.icon = icon;
+        store.save(&instance)?;
+    }
+    Ok(instance)
+}
+
+#[tauri::command]
+pub fn update_instance(instance: Instance) -> LauncherResult<Instance> {
+    InstanceStore::new().update(instance)
+}
+
+#[tauri::command]
+pub fn delete_instance(id: String) -> LauncherResult<()> {
+    InstanceStore::new().delete(&id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_instance_crud() {
+        let dir = std::env::temp_dir().join(format!("rtl-instance-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let store = InstanceStore::with_dir(dir.clone());
+
+        let first = store.create("My Pack", "1.21.4").unwrap();
+        let second = store.create("My Pack", "1.20.1").unwrap();
+        assert_eq!(first.id, "My-Pack");
+        assert_eq!(second.id, "My-Pack-2");
+        assert!(dir.join("My-Pack").join("mods").is_dir());
+        assert_eq!(first.game_dir(), dir.join("My-Pack"));
+        assert_eq!(
+            store.get("My-Pack").unwrap().game_dir(),
+            dir.join("My-Pack")
+        );
+
+        let mut updated = first.clone();
+        updated.max_memory_mb = Some(4096);
+        updated.jvm_args = vec!["-XX:+UseZGC".to_string()];
+        updated.created_at = 0;
+        let updated = store.update(updated).unwrap();
+        assert_eq!(updated.created_at, first.created_at);
+        assert!(updated.has_jvm_override());
+
+        assert_eq!(store.list().len(), 2);
+        store.delete("My-Pack-2").unwrap();
+        assert_eq!(store.list().len(), 1);
+        assert!(store.delete("missing").is_err());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -17,10 +17,4 @@ You should have received a copy of the GNU General Public License
 along with this program.  If not, see <https://www.gnu.org/licenses/>.
 */
 
-struct log_info {
-    info: String,
-}
-
-impl log::Log for log_info {
-    fn new(self) -> log_info {}
-}
\ No newline at end of file
+pub mod ins_main;
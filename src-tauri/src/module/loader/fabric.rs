@@ -0,0 +1,143 @@
+/*
+RTLauncher, a third-party Minecraft launcher built with the newest
+technology and provides innovative funtionalities
+Copyright (C) 2025 lutouna
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+// ***
+// Fabric 加载器安装
+// ***
+
+use super::meta::{LoaderMeta, MetaLoader};
+use super::{LoaderGameVersion, LoaderInstallResult, LoaderVersion};
+use crate::module::download::paths::MinecraftPaths;
+use crate::utils::error::LauncherResult;
+
+pub const FABRIC_META_URL: &str = "https://meta.fabricmc.net";
+
+pub const FABRIC: MetaLoader = MetaLoader {
+    name: "Fabric",
+    url: FABRIC_META_URL,
+    api: "v2",
+    mappings: "intermediary",
+};
+
+#[tauri::command]
+pub async fn get_fabric_game_versions() -> LauncherResult<Vec<LoaderGameVersion>> {
+    LoaderMeta::new(FABRIC).game_versions().await
+}
+
+#[tauri::command]
+pub async fn get_fabric_loader_versions(
+    game_version: String,
+) -> LauncherResult<Vec<LoaderVersion>> {
+    LoaderMeta::new(FABRIC).loader_versions(&game_version).await
+}
+
+#[tauri::command]
+pub async fn install_fabric(
+    game_version: String,
+    loader_version: Option<String>,
+) -> LauncherResult<LoaderInstallResult> {
+    LoaderMeta::new(FABRIC)
+        .install(&MinecraftPaths::new(), &game_version, loader_version)
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::fake_server;
+    use super::*;
+    use crate::module::download::installed::{classpath, resolve_version};
+    use sha1::Digest;
+    use std::collections::HashMap;
+
+    #[tokio::test]
+    async fn test_install_fabric_from_fake_meta() {
+        let loader_jar = b"fabric-loader".to_vec();
+        let loader_sha1 = format!("{:x}", sha1::Sha1::digest(&loader_jar));
+        let profile = serde_json::json!({
+            "id": "fabric-loader-0.16.9-1.21.4",
+            "inheritsFrom": "1.21.4",
+            "type": "release",
+            "mainClass": "net.fabricmc.loader.impl.launch.knot.KnotClient",
+            "arguments": { "game": [], "jvm": ["-DFabricMcEmu= net.minecraft.client.main.Main "] },
+            "libraries": [
+                { "name": "net.fabricmc:fabric-loader:0.16.9", "url": "{base}/maven/", "sha1": loader_sha1 },
+                { "name": "net.fabricmc:intermediary:1.21.4", "url": "{base}/maven/" }
+            ]
+        });
+        let routes: HashMap<String, Vec<u8>> = [
+            ("/v2/versions/game", r#"[{"version":"1.21.4","stable":true},{"version":"24w14a","stable":false}]"#.as_bytes().to_vec()),
+            (
+                "/v2/versions/loader/1.21.4",
+                r#"[{"loader":{"version":"0.16.10","stable":false},"intermediary":{"version":"1.21.4"}},
+                    {"loader":{"version":"0.16.9","stable":true},"intermediary":{"version":"1.21.4"}}]"#
+                    .as_bytes()
+                    .to_vec(),
+            ),
+            ("/v2/versions/loader/1.21.4/0.16.9/profile/json", profile.to_string().into_bytes()),
+            ("/maven/net/fabricmc/fabric-loader/0.16.9/fabric-loader-0.16.9.jar", loader_jar),
+            ("/maven/net/fabricmc/intermediary/1.21.4/intermediary-1.21.4.jar", b"intermediary".to_vec()),
+        ]
+        .into_iter()
+        .map(|(path, body)| (path.to_string(), body))
+        .collect();
+        let (base, server) = fake_server::serve(routes).await;
+
+        let dir = std::env::temp_dir().join(format!("rtl-fabric-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let paths = MinecraftPaths::with_base_dir(dir.clone());
+        // 原版已安装，不需要联网
+        std::fs::create_dir_all(paths.get_version_dir("1.21.4")).unwrap();
+        std::fs::write(
+            paths.get_version_json("1.21.4"),
+            r#"{"id":"1.21.4","mainClass":"net.minecraft.client.main.Main","libraries":[]}"#,
+        )
+        .unwrap();
+
+        let meta = LoaderMeta::with_base(FABRIC, base);
+        let games = meta.game_versions().await.unwrap();
+        assert_eq!(games.len(), 2);
+        assert!(games[0].stable);
+
+        let result = meta.install(&paths, "1.21.4", None).await.unwrap();
+        assert_eq!(result.version_id, "fabric-loader-0.16.9-1.21.4");
+        assert_eq!(result.inherits_from, "1.21.4");
+        assert_eq!(result.libraries, 2);
+
+        let json = resolve_version(&paths, &result.version_id).unwrap();
+        assert_eq!(
+            json["mainClass"],
+            "net.fabricmc.loader.impl.launch.knot.KnotClient"
+        );
+        let classpath = classpath(&paths, &json);
+        assert_eq!(classpath.len(), 3);
+        assert_eq!(std::fs::read(&classpath[0]).unwrap(), b"fabric-loader");
+
+        // 没有的加载器版本返回错误，不写入版本 JSON
+        assert!(meta
+            .install(&paths, "1.21.4", Some("0.0.1".to_string()))
+            .await
+            .is_err());
+        assert!(!paths
+            .get_version_json("fabric-loader-0.0.1-1.21.4")
+            .exists());
+
+        server.abort();
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
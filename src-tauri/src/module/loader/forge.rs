@@ -0,0 +1,876 @@
+/*
+RTLauncher, a third-party Minecraft launcher built with the newest
+technology and provides innovative funtionalities
+Copyright (C) 2025 lutouna
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+// ***
+// Forge / NeoForge 安装器
+// ***
+
+use super::{
+    download_libraries, ensure_vanilla, installer_java, write_profile, LoaderInstallResult,
+    LoaderVersion,
+};
+use crate::module::download::dwl_main::download_verified;
+use crate::module::download::maven::maven_path;
+use crate::module::download::paths::MinecraftPaths;
+use crate::module::download::verify::sha1_file;
+use crate::module::modpack::safe_relative_path;
+use crate::utils::error::{LauncherError, LauncherResult};
+use crate::utils::request;
+use crate::Setting::config;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+pub const FORGE_MAVEN_URL: &str = "https://maven.minecraftforge.net";
+pub const NEOFORGE_MAVEN_URL: &str = "https://maven.neoforged.net/releases";
+
+// 旧版安装器中库文件使用的仓库地址
+const LEGACY_FORGE_MAVEN: &str = "http://files.minecraftforge.net/maven";
+
+// 加载器类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ForgeKind {
+    Forge,
+    #[serde(rename = "neoforge")]
+    NeoForge,
+}
+
+impl ForgeKind {
+    fn group_artifact(&self) -> &'static str {
+        match self {
+            ForgeKind::Forge => "net.minecraftforge:forge",
+            ForgeKind::NeoForge => "net.neoforged:neoforge",
+        }
+    }
+
+    fn default_maven(&self) -> String {
+        match self {
+            ForgeKind::Forge => config::current().download.mirror.rewrite(FORGE_MAVEN_URL),
+            ForgeKind::NeoForge => NEOFORGE_MAVEN_URL.to_string(),
+        }
+    }
+
+    // 加载器版本对应的游戏版本
+    // Forge: 1.20.1-47.2.0，NeoForge: 21.1.77 对应 1.21.1，20.2.3-beta 对应 1.20.2
+    pub fn game_version(&self, loader_version: &str) -> Option<String> {
+        match self {
+            ForgeKind::Forge => loader_version.split('-').next().map(|v| v.to_string()),
+            ForgeKind::NeoForge => {
+                let mut parts = loader_version.split(['.', '-']);
+                let major = parts.next()?.parse::<u32>().ok()?;
+                let minor = parts.next()?.parse::<u32>().ok()?;
+                Some(match minor {
+                    0 => format!("1.{}", major),
+                    _ => format!("1.{}.{}", major, minor),
+                })
+            }
+        }
+    }
+}
+
+// 从 maven-metadata.xml 中读取所有版本
+fn parse_maven_versions(xml: &str) -> Vec<String> {
+    xml.split("<version>")
+        .skip(1)
+        .filter_map(|part| part.split("</version>").next())
+        .map(|v| v.trim().to_string())
+        .collect()
+}
+
+// Forge / NeoForge 安装器
+pub struct ForgeInstaller {
+    kind: ForgeKind,
+    maven: String,
+}
+
+impl ForgeInstaller {
+    pub fn new(kind: ForgeKind) -> Self {
+        Self::with_maven(kind, kind.default_maven())
+    }
+
+    pub fn with_maven(kind: ForgeKind, maven: String) -> Self {
+        Self {
+            kind,
+            maven: maven.trim_end_matches('/').to_string(),
+        }
+    }
+
+    // 指定游戏版本可用的加载器版本，按新到旧排列
+    pub async fn versions(&self, game_version: &str) -> LauncherResult<Vec<LoaderVersion>> {
+        let url = format!(
+            "{}/{}/maven-metadata.xml",
+            self.maven,
+            self.kind.group_artifact().replace([':', '.'], "/")
+        );
+        let xml = request::Request::new(url).fetch_get().await?;
+        Ok(parse_maven_versions(&xml)
+            .into_iter()
+            .rev()
+            .filter(|v| self.kind.game_version(v).as_deref() == Some(game_version))
+            .map(|version| LoaderVersion {
+                stable: !version.contains("beta"),
+                version,
+                mappings: None,
+            })
+            .collect())
+    }
+
+    // 下载安装器 jar 到 libraries 目录
+    async fn download_installer(
+        &self,
+        paths: &MinecraftPaths,
+        loader_version: &str,
+    ) -> LauncherResult<PathBuf> {
+        let coordinate = format!(
+            "{}:{}:installer",
+            self.kind.group_artifact(),
+            loader_version
+        );
+        let path = maven_path(&coordinate)
+            .ok_or_else(|| LauncherError::InvalidArgument(loader_version.to_string()))?;
+        let installer = paths.libraries_dir.join(&path);
+        download_verified(&format!("{}/{}", self.maven, path), &installer, None).await?;
+        Ok(installer)
+    }
+
+    // 安装指定版本，返回生成的版本号
+    pub async fn install(
+        &self,
+        paths: &MinecraftPaths,
+        loader_version: &str,
+    ) -> LauncherResult<LoaderInstallResult> {
+        let installer = self.download_installer(paths, loader_version).await?;
+        let mut archive = open_zip(&installer)?;
+        let profile: Value =
+            serde_json::from_str(&read_entry(&mut archive, "install_profile.json")?)?;
+
+        // 1.12.2 及更早的安装器直接包含 universal jar 和版本 JSON
+        if profile.get("versionInfo").is_some() {
+            return install_legacy(paths, &mut archive, &profile).await;
+        }
+
+        let minecraft = profile["minecraft"]
+            .as_str()
+            .ok_or_else(|| LauncherError::Parse("install_profile.json 缺少 minecraft".to_string()))?
+            .to_string();
+        let json_entry = profile["json"].as_str().unwrap_or("/version.json");
+        let mut version: Value = serde_json::from_str(&read_entry(&mut archive, json_entry)?)?;
+        if version.get("inheritsFrom").is_none() {
+            version["inheritsFrom"] = Value::String(minecraft.clone());
+        }
+
+        ensure_vanilla(paths, &minecraft).await?;
+        extract_embedded_libraries(paths, &mut archive)?;
+        download_libraries(paths, &profile).await?;
+        let libraries = download_libraries(paths, &version).await?;
+
+        let work_dir = config::cache_dir()
+            .join("forge-installer")
+            .join(loader_version);
+        let data = resolve_data(
+            paths,
+            &mut archive,
+            &profile,
+            &minecraft,
+            &installer,
+            &work_dir,
+        )?;
+        // 只运行客户端需要的处理器，没有处理器时不需要 Java
+        let processors: Vec<&Value> = profile["processors"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter(|processor| runs_on_client(processor))
+            .collect();
+        if !processors.is_empty() {
+            let java = installer_java(paths, &minecraft)?;
+            for processor in processors {
+                run_processor(paths, processor, &data, &java).await?;
+            }
+        }
+        let _ = std::fs::remove_dir_all(&work_dir);
+
+        let version_id = write_profile(paths, &version)?;
+        log::info!("{:?} 安装完成: {}", self.kind, version_id);
+        Ok(LoaderInstallResult {
+            version_id,
+            inherits_from: minecraft,
+            libraries,
+        })
+    }
+}
+
+fn open_zip(path: &Path) -> LauncherResult<zip::ZipArchive<std::fs::File>> {
+    let file = std::fs::File::open(path).map_err(|e| LauncherError::io(path, e))?;
+    Ok(zip::ZipArchive::new(file)?)
+}
+
+fn read_entry<R: std::io::Read + std::io::Seek>(
+    archive: &mut zip::ZipArchive<R>,
+    name: &str,
+) -> LauncherResult<String> {
+    let mut entry = archive.by_name(name.trim_start_matches('/'))?;
+    let mut content = String::new();
+    entry.read_to_string(&mut content)?;
+    Ok(content)
+}
+
+fn extract_entry<R: std::io::Read + std::io::Seek>(
+    archive: &mut zip::ZipArchive<R>,
+    name: &str,
+    target: &Path,
+) -> LauncherResult<()> {
+    let mut entry = archive.by_name(name.trim_start_matches('/'))?;
+    if let Some(parent) = target.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| LauncherError::io(parent, e))?;
+    }
+    let mut file = std::fs::File::create(target).map_err(|e| LauncherError::io(target, e))?;
+    std::io::copy(&mut entry, &mut file).map_err(|e| LauncherError::io(target, e))?;
+    Ok(())
+}
+
+// 安装器提供的相对路径，不允许离开目标目录
+fn safe_join(base: &Path, relative: &str) -> LauncherResult<PathBuf> {
+    safe_relative_path(relative)
+        .map(|path| base.join(path))
+        .ok_or_else(|| LauncherError::InvalidArgument(format!("安装器中的路径无效: {}", relative)))
+}
+
+// 安装器 maven/ 目录中自带的库文件
+fn extract_embedded_libraries<R: std::io::Read + std::io::Seek>(
+    paths: &MinecraftPaths,
+    archive: &mut zip::ZipArchive<R>,
+) -> LauncherResult<()> {
+    let names: Vec<String> = archive
+        .file_names()
+        .filter(|name| name.starts_with("maven/") && !name.ends_with('/'))
+        .map(|name| name.to_string())
+        .collect();
+    for name in names {
+        let target = safe_join(&paths.libraries_dir, name.trim_start_matches("maven/"))?;
+        if !target.exists() {
+            extract_entry(archive, &name, &target)?;
+        }
+    }
+    Ok(())
+}
+
+// 旧版 Forge：解压 universal jar，写入 versionInfo
+async fn install_legacy<R: std::io::Read + std::io::Seek>(
+    paths: &MinecraftPaths,
+    archive: &mut zip::ZipArchive<R>,
+    profile: &Value,
+) -> LauncherResult<LoaderInstallResult> {
+    let install = &profile["install"];
+    let minecraft = install["minecraft"]
+        .as_str()
+        .ok_or_else(|| {
+            LauncherError::Parse("install_profile.json 缺少 install.minecraft".to_string())
+        })?
+        .to_string();
+    let universal = install["path"].as_str().and_then(maven_path);
+    if let (Some(universal), Some(file_path)) = (universal, install["filePath"].as_str()) {
+        extract_entry(archive, file_path, &paths.libraries_dir.join(universal))?;
+    }
+
+    let mut version = profile["versionInfo"].clone();
+    version["inheritsFrom"] = Value::String(minecraft.clone());
+    version["jar"] = Value::String(minecraft.clone());
+    if let Some(libraries) = version["libraries"].as_array_mut() {
+        // 只保留客户端需要的库，旧仓库地址换成当前地址
+        libraries.retain(|library| library["clientreq"].as_bool() != Some(false));
+        for library in libraries.iter_mut() {
+            if let Some(url) = library["url"].as_str() {
+                let url = url.replace(LEGACY_FORGE_MAVEN, FORGE_MAVEN_URL);
+                library["url"] = Value::String(config::current().download.mirror.rewrite(&url));
+            }
+        }
+    }
+
+    ensure_vanilla(paths, &minecraft).await?;
+    let libraries = download_libraries(paths, &version).await?;
+    let version_id = write_profile(paths, &version)?;
+    log::info!("旧版 Forge 安装完成: {}", version_id);
+    Ok(LoaderInstallResult {
+        version_id,
+        inherits_from: minecraft,
+        libraries,
+    })
+}
+
+// 解析 install_profile.json 中的 data 变量，安装器内的文件解压到工作目录
+fn resolve_data<R: std::io::Read + std::io::Seek>(
+    paths: &MinecraftPaths,
+    archive: &mut zip::ZipArchive<R>,
+    profile: &Value,
+    minecraft: &str,
+    installer: &Path,
+    work_dir: &Path,
+) -> LauncherResult<HashMap<String, String>> {
+    let path_string = |path: &Path| path.to_string_lossy().into_owned();
+    let mut data = HashMap::from([
+        ("SIDE".to_string(), "client".to_string()),
+        ("MINECRAFT_VERSION".to_string(), minecraft.to_string()),
+        (
+            "MINECRAFT_JAR".to_string(),
+            path_string(
+                &paths
+                    .get_version_dir(minecraft)
+                    .join(format!("{}.jar", minecraft)),
+            ),
+        ),
+        ("ROOT".to_string(), path_string(&paths.base_dir)),
+        ("INSTALLER".to_string(), path_string(installer)),
+        ("LIBRARY_DIR".to_string(), path_string(&paths.libraries_dir)),
+    ]);
+    for (key, value) in profile["data"].as_object().into_iter().flatten() {
+        let Some(value) = value["client"].as_str() else {
+            continue;
+        };
+        let resolved = if value.starts_with('[') && value.ends_with(']') {
+            path_string(&library_file(paths, &value[1..value.len() - 1])?)
+        } else if value.starts_with('\'') && value.ends_with('\'') && value.len() >= 2 {
+            value[1..value.len() - 1].to_string()
+        } else if value.starts_with('/') {
+            let target = safe_join(work_dir, value.trim_start_matches('/'))?;
+            extract_entry(archive, value, &target)?;
+            path_string(&target)
+        } else {
+            value.to_string()
+        };
+        data.insert(key.clone(), resolved);
+    }
+    Ok(data)
+}
+
+// Maven 坐标对应的本地文件
+fn library_file(paths: &MinecraftPaths, coordinate: &str) -> LauncherResult<PathBuf> {
+    maven_path(coordinate)
+        .map(|path| paths.libraries_dir.join(path))
+        .ok_or_else(|| LauncherError::Parse(format!("无效的 Maven 坐标: {}", coordinate)))
+}
+
+// 替换处理器参数中的 [坐标] 和 {变量}
+fn substitute(
+    paths: &MinecraftPaths,
+    data: &HashMap<String, String>,
+    arg: &str,
+) -> LauncherResult<String> {
+    if arg.starts_with('[') && arg.ends_with(']') {
+        return Ok(library_file(paths, &arg[1..arg.len() - 1])?
+            .to_string_lossy()
+            .into_owned());
+    }
+    let mut result = arg.to_string();
+    for (key, value) in data {
+        result = result.replace(&format!("{{{}}}", key), value);
+    }
+    Ok(result)
+}
+
+// 读取 jar 清单中的 Main-Class
+fn main_class(jar: &Path) -> LauncherResult<String> {
+    let manifest = read_entry(&mut open_zip(jar)?, "META-INF/MANIFEST.MF")?;
+    // 清单每行最长 72 字节，以空格开头的行是上一行的延续
+    let manifest = manifest.replace("\r\n", "\n").replace("\n ", "");
+    manifest
+        .lines()
+        .find_map(|line| line.strip_prefix("Main-Class:"))
+        .map(|class| class.trim().to_string())
+        .ok_or_else(|| LauncherError::Parse(format!("{} 缺少 Main-Class", jar.display())))
+}
+
+// 处理器输出文件与期望的 SHA1
+fn processor_outputs(
+    paths: &MinecraftPaths,
+    processor: &Value,
+    data: &HashMap<String, String>,
+) -> LauncherResult<Vec<(PathBuf, String)>> {
+    processor["outputs"]
+        .as_object()
+        .into_iter()
+        .flatten()
+        .map(|(path, sha1)| {
+            let path = substitute(paths, data, path)?;
+            let sha1 = substitute(paths, data, sha1.as_str().unwrap_or_default())?;
+            Ok((PathBuf::from(path), sha1.trim_matches('\'').to_string()))
+        })
+        .collect()
+}
+
+// 没有 sides 的处理器两端都要运行
+fn runs_on_client(processor: &Value) -> bool {
+    match processor["sides"].as_array() {
+        Some(sides) => sides.iter().any(|side| side.as_str() == Some("client")),
+        None => true,
+    }
+}
+
+// 运行单个处理器并校验输出
+async fn run_processor(
+    paths: &MinecraftPaths,
+    processor: &Value,
+    data: &HashMap<String, String>,
+    java: &str,
+) -> LauncherResult<()> {
+    let jar_name = processor["jar"]
+        .as_str()
+        .ok_or_else(|| LauncherError::Parse("处理器缺少 jar".to_string()))?;
+
+    let outputs = processor_outputs(paths, processor, data)?;
+    let up_to_date = |outputs: &[(PathBuf, String)]| {
+        outputs
+            .iter()
+            .all(|(path, sha1)| sha1_file(path).ok().as_deref() == Some(sha1.as_str()))
+    };
+    if !outputs.is_empty() && up_to_date(&outputs) {
+        log::debug!("处理器输出已存在，跳过: {}", jar_name);
+        return Ok(());
+    }
+
+    let jar = library_file(paths, jar_name)?;
+    let mut classpath = vec![jar.clone()];
+    for coordinate in processor["classpath"].as_array().into_iter().flatten() {
+        if let Some(coordinate) = coordinate.as_str() {
+            classpath.push(library_file(paths, coordinate)?);
+        }
+    }
+    let classpath =
+        std::env::join_paths(&classpath).map_err(|e| LauncherError::Other(e.to_string()))?;
+    let args = processor["args"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|arg| arg.as_str())
+        .map(|arg| substitute(paths, data, arg))
+        .collect::<LauncherResult<Vec<String>>>()?;
+
+    log::info!("运行处理器: {}", jar_name);
+    let output = tokio::process::Command::new(java)
+        .arg("-cp")
+        .arg(classpath)
+        .arg(main_class(&jar)?)
+        .args(&args)
+        .current_dir(&paths.base_dir)
+        .output()
+        .await
+        .map_err(|e| LauncherError::Other(format!("无法启动处理器 {}: {}", jar_name, e)))?;
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        log::debug!("[{}] {}", jar_name, line);
+    }
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(LauncherError::Other(format!(
+            "处理器 {} 运行失败 ({}): {}",
+            jar_name,
+            output.status,
+            stderr.trim()
+        )));
+    }
+
+    for (path, expected) in outputs {
+        let actual = sha1_file(&path).map_err(|e| LauncherError::io(&path, e))?;
+        if actual != expected {
+            return Err(LauncherError::HashMismatch {
+                path: path.to_string_lossy().into_owned(),
+                expected,
+                actual,
+            });
+        }
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_forge_versions(
+    kind: ForgeKind,
+    game_version: String,
+) -> LauncherResult<Vec<LoaderVersion>> {
+    ForgeInstaller::new(kind).versions(&game_version).await
+}
+
+#[tauri::command]
+pub async fn install_forge(
+    kind: ForgeKind,
+    loader_version: String,
+) -> LauncherResult<LoaderInstallResult> {
+    ForgeInstaller::new(kind)
+        .install(&MinecraftPaths::new(), &loader_version)
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::fake_server;
+    use super::*;
+    use sha1::Digest;
+    use std::io::Write;
+
+    fn installer_jar(files: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        for (name, content) in files {
+            writer
+                .start_file(*name, zip::write::SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(content).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn test_installer_paths_stay_inside() {
+        let dir = std::env::temp_dir().join(format!("rtl-forge-paths-{}", std::process::id()));
+        let paths = MinecraftPaths::with_base_dir(dir.clone());
+        let jar = installer_jar(&[
+            ("maven/../../evil.jar", b"evil"),
+            ("data/../../evil.lzma", b"evil"),
+        ]);
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(jar)).unwrap();
+        assert!(matches!(
+            extract_embedded_libraries(&paths, &mut archive),
+            Err(LauncherError::InvalidArgument(_))
+        ));
+        let profile = serde_json::json!({
+            "data": { "BINPATCH": { "client": "/data/../../evil.lzma" } }
+        });
+        assert!(matches!(
+            resolve_data(
+                &paths,
+                &mut archive,
+                &profile,
+                "1.20.1",
+                &dir.join("installer.jar"),
+                &dir.join("work")
+            ),
+            Err(LauncherError::InvalidArgument(_))
+        ));
+        assert!(!dir.join("evil.jar").exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_versions_and_substitution() {
+        assert_eq!(
+            ForgeKind::Forge.game_version("1.20.1-47.2.0").as_deref(),
+            Some("1.20.1")
+        );
+        assert_eq!(
+            ForgeKind::NeoForge.game_version("21.1.77").as_deref(),
+            Some("1.21.1")
+        );
+        assert_eq!(
+            ForgeKind::NeoForge.game_version("21.0.5-beta").as_deref(),
+            Some("1.21")
+        );
+        let xml = "<metadata><versioning><versions><version>21.1.1</version><version>21.1.2</version></versions></versioning></metadata>";
+        assert_eq!(parse_maven_versions(xml), vec!["21.1.1", "21.1.2"]);
+
+        let paths = MinecraftPaths::with_base_dir(PathBuf::from("/mc"));
+        let data = HashMap::from([("SIDE".to_string(), "client".to_string())]);
+        assert_eq!(
+            substitute(&paths, &data, "--side={SIDE}").unwrap(),
+            "--side=client"
+        );
+        assert!(
+            substitute(&paths, &data, "[net.neoforged:neoforge:21.1.77:client]")
+                .unwrap()
+                .ends_with("neoforge-21.1.77-client.jar")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_install_neoforge_from_installer() {
+        let tool = b"installer-tool".to_vec();
+        let tool_sha1 = format!("{:x}", sha1::Sha1::digest(&tool));
+        let version = serde_json::json!({
+            "id": "neoforge-21.1.77",
+            "inheritsFrom": "1.21.1",
+            "mainClass": "cpw.mods.bootstraplauncher.BootstrapLauncher",
+            "libraries": [
+                { "name": "net.neoforged:neoforge:21.1.77:universal", "downloads": { "artifact": {
+                    "path": "net/neoforged/neoforge/21.1.77/neoforge-21.1.77-universal.jar", "url": "" } } },
+                { "name": "net.neoforged:neoforge:21.1.77:client", "downloads": { "artifact": {
+                    "path": "net/neoforged/neoforge/21.1.77/neoforge-21.1.77-client.jar", "url": "" } } }
+            ]
+        });
+        // 安装器里的库地址指向测试服务器
+        let (base, server) = fake_server::serve_with(|base| {
+            let profile = serde_json::json!({
+                "minecraft": "1.21.1",
+                "json": "/version.json",
+                "data": {
+                    "BINPATCH": { "client": "/data/client.lzma", "server": "/data/server.lzma" },
+                    "MAPPINGS": { "client": "[net.neoforged:neoform:1.21.1:mappings@txt]" }
+                },
+                "processors": [{ "sides": ["server"], "jar": "net.neoforged:tool:1.0", "args": [] }],
+                "libraries": [{
+                    "name": "net.neoforged:tool:1.0",
+                    "downloads": { "artifact": {
+                        "path": "net/neoforged/tool/1.0/tool-1.0.jar",
+                        "url": format!("{}/maven/net/neoforged/tool/1.0/tool-1.0.jar", base),
+                        "sha1": tool_sha1
+                    } }
+                }]
+            });
+            let installer = installer_jar(&[
+                ("install_profile.json", profile.to_string().as_bytes()),
+                ("version.json", version.to_string().as_bytes()),
+                ("data/client.lzma", b"patches"),
+                (
+                    "maven/net/neoforged/neoforge/21.1.77/neoforge-21.1.77-universal.jar",
+                    b"universal",
+                ),
+            ]);
+            HashMap::from([
+                ("/maven/net/neoforged/tool/1.0/tool-1.0.jar".to_string(), tool),
+                (
+                    "/maven/net/neoforged/neoforge/21.1.77/neoforge-21.1.77-installer.jar".to_string(),
+                    installer,
+                ),
+            ])
+        })
+        .await;
+
+        let dir = std::env::temp_dir().join(format!("rtl-forge-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let paths = MinecraftPaths::with_base_dir(dir.clone());
+        std::fs::create_dir_all(paths.get_version_dir("1.21.1")).unwrap();
+        std::fs::write(
+            paths.get_version_json("1.21.1"),
+            r#"{"id":"1.21.1","libraries":[]}"#,
+        )
+        .unwrap();
+
+        let installer = ForgeInstaller::with_maven(ForgeKind::NeoForge, format!("{}/maven", base));
+        let result = installer.install(&paths, "21.1.77").await.unwrap();
+        assert_eq!(result.version_id, "neoforge-21.1.77");
+        assert_eq!(result.inherits_from, "1.21.1");
+        let library = |path: &str| paths.libraries_dir.join(path);
+        assert_eq!(
+            std::fs::read(library(
+                "net/neoforged/neoforge/21.1.77/neoforge-21.1.77-universal.jar"
+            ))
+            .unwrap(),
+            b"universal"
+        );
+        assert!(library("net/neoforged/tool/1.0/tool-1.0.jar").exists());
+        assert!(paths.get_version_json("neoforge-21.1.77").exists());
+
+        // data 中的安装器文件解压到工作目录，坐标解析为库文件路径
+        let installer_path =
+            library("net/neoforged/neoforge/21.1.77/neoforge-21.1.77-installer.jar");
+        let mut archive = open_zip(&installer_path).unwrap();
+        let profile: Value =
+            serde_json::from_str(&read_entry(&mut archive, "install_profile.json").unwrap())
+                .unwrap();
+        let work_dir = dir.join("work");
+        let data = resolve_data(
+            &paths,
+            &mut archive,
+            &profile,
+            "1.21.1",
+            &installer_path,
+            &work_dir,
+        )
+        .unwrap();
+        assert_eq!(std::fs::read(&data["BINPATCH"]).unwrap(), b"patches");
+        assert!(data["MAPPINGS"].ends_with("neoform-1.21.1-mappings.txt"));
+
+        server.abort();
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    // 用 shell 脚本代替 java：记录参数并把 --output 后的文件写成 patched
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run_client_processor() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("rtl-forge-processor-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let paths = MinecraftPaths::with_base_dir(dir.clone());
+        std::fs::create_dir_all(&paths.base_dir).unwrap();
+
+        let jar = paths
+            .libraries_dir
+            .join("net/example/patcher/1.0/patcher-1.0.jar");
+        std::fs::create_dir_all(jar.parent().unwrap()).unwrap();
+        std::fs::write(
+            &jar,
+            installer_jar(&[(
+                "META-INF/MANIFEST.MF",
+                b"Manifest-Version: 1.0\r\nMain-Class: net.example.Pat\r\n cher\r\n",
+            )]),
+        )
+        .unwrap();
+
+        let args_log = dir.join("args.txt");
+        let java = dir.join("java");
+        std::fs::write(
+            &java,
+            format!(
+                "#!/bin/sh\nprintf '%s\\n' \"$@\" > '{}'\nwhile [ $# -gt 0 ]; do\n  if [ \"$1\" = --output ]; then mkdir -p \"$(dirname \"$2\")\"; printf patched > \"$2\"; fi\n  shift\ndone\n",
+                args_log.display()
+            ),
+        )
+        .unwrap();
+        std::fs::set_permissions(&java, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let java = java.to_string_lossy().into_owned();
+
+        let patched = dir.join("out/patched.jar");
+        let data = HashMap::from([
+            ("SIDE".to_string(), "client".to_string()),
+            (
+                "PATCHED".to_string(),
+                patched.to_string_lossy().into_owned(),
+            ),
+        ]);
+        let patched_sha1 = format!("{:x}", sha1::Sha1::digest(b"patched"));
+        let processor = serde_json::json!({
+            "sides": ["client"],
+            "jar": "net.example:patcher:1.0",
+            "classpath": [],
+            "args": ["--side", "{SIDE}", "--output", "{PATCHED}"],
+            "outputs": { "{PATCHED}": format!("'{}'", patched_sha1) }
+        });
+        assert!(runs_on_client(&processor));
+        assert!(!runs_on_client(&serde_json::json!({ "sides": ["server"] })));
+
+        run_processor(&paths, &processor, &data, &java)
+            .await
+            .unwrap();
+        let args = std::fs::read_to_string(&args_log).unwrap();
+        let args: Vec<&str> = args.lines().collect();
+        assert_eq!(args[0], "-cp");
+        assert_eq!(args[1], jar.to_string_lossy());
+        assert_eq!(
+            &args[2..],
+            [
+                "net.example.Patcher",
+                "--side",
+                "client",
+                "--output",
+                &patched.to_string_lossy()
+            ]
+        );
+
+        // 输出已存在且哈希一致时不再运行
+        std::fs::remove_file(&args_log).unwrap();
+        run_processor(&paths, &processor, &data, &java)
+            .await
+            .unwrap();
+        assert!(!args_log.exists());
+
+        // 输出哈希不一致
+        let mut broken = processor.clone();
+        broken["outputs"] = serde_json::json!({ "{PATCHED}": "'0000'" });
+        assert!(matches!(
+            run_processor(&paths, &broken, &data, &java).await,
+            Err(LauncherError::HashMismatch { expected, .. }) if expected == "0000"
+        ));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_install_legacy_forge() {
+        let (base, server) = fake_server::serve_with(|base| {
+            let profile = serde_json::json!({
+                "install": {
+                    "minecraft": "1.12.2",
+                    "path": "net.minecraftforge:forge:1.12.2-14.23.5.2860",
+                    "filePath": "forge-1.12.2-14.23.5.2860-universal.jar"
+                },
+                "versionInfo": {
+                    "id": "1.12.2-forge-14.23.5.2860",
+                    "mainClass": "net.minecraft.launchwrapper.Launch",
+                    "minecraftArguments": "--tweakClass net.minecraftforge.fml.common.launcher.FMLTweaker",
+                    "libraries": [
+                        { "name": "net.minecraftforge:forge:1.12.2-14.23.5.2860" },
+                        { "name": "net.minecraft:launchwrapper:1.12", "url": format!("{}/maven/", base) },
+                        { "name": "org.example:server-only:1.0", "clientreq": false, "serverreq": true }
+                    ]
+                }
+            });
+            let installer = installer_jar(&[
+                ("install_profile.json", profile.to_string().as_bytes()),
+                ("forge-1.12.2-14.23.5.2860-universal.jar", b"universal"),
+            ]);
+            HashMap::from([
+                (
+                    "/maven/net/minecraft/launchwrapper/1.12/launchwrapper-1.12.jar".to_string(),
+                    b"launchwrapper".to_vec(),
+                ),
+                (
+                    "/maven/net/minecraftforge/forge/1.12.2-14.23.5.2860/forge-1.12.2-14.23.5.2860-installer.jar"
+                        .to_string(),
+                    installer,
+                ),
+            ])
+        })
+        .await;
+
+        let dir = std::env::temp_dir().join(format!("rtl-forge-legacy-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let paths = MinecraftPaths::with_base_dir(dir.clone());
+        std::fs::create_dir_all(paths.get_version_dir("1.12.2")).unwrap();
+        std::fs::write(
+            paths.get_version_json("1.12.2"),
+            r#"{"id":"1.12.2","libraries":[]}"#,
+        )
+        .unwrap();
+
+        let installer = ForgeInstaller::with_maven(ForgeKind::Forge, format!("{}/maven", base));
+        let result = installer
+            .install(&paths, "1.12.2-14.23.5.2860")
+            .await
+            .unwrap();
+        assert_eq!(result.version_id, "1.12.2-forge-14.23.5.2860");
+        assert_eq!(result.inherits_from, "1.12.2");
+        let library = |path: &str| std::fs::read(paths.libraries_dir.join(path)).unwrap();
+        assert_eq!(
+            library("net/minecraftforge/forge/1.12.2-14.23.5.2860/forge-1.12.2-14.23.5.2860.jar"),
+            b"universal"
+        );
+        assert_eq!(
+            library("net/minecraft/launchwrapper/1.12/launchwrapper-1.12.jar"),
+            b"launchwrapper"
+        );
+
+        let version: Value = serde_json::from_str(
+            &std::fs::read_to_string(paths.get_version_json(&result.version_id)).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(version["inheritsFrom"], "1.12.2");
+        assert_eq!(version["jar"], "1.12.2");
+        let names: Vec<&str> = version["libraries"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|library| library["name"].as_str())
+            .collect();
+        assert!(!names.contains(&"org.example:server-only:1.0"));
+
+        server.abort();
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
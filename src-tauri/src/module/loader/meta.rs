@@ -0,0 +1,129 @@
+/*
+RTLauncher, a third-party Minecraft launcher built with the newest
+technology and provides innovative funtionalities
+Copyright (C) 2025 lutouna
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+// ***
+// Fabric 与 Quilt 共用的 meta 接口
+// ***
+
+use super::{
+    fetch_json, install_profile, pick_loader, LoaderGameVersion, LoaderInstallResult, LoaderVersion,
+};
+use crate::module::download::paths::MinecraftPaths;
+use crate::utils::error::{LauncherError, LauncherResult};
+use crate::Setting::config;
+use serde_json::Value;
+
+// 使用 meta 接口安装的加载器
+#[derive(Debug, Clone, Copy)]
+pub struct MetaLoader {
+    pub name: &'static str,
+    pub url: &'static str,
+    // 接口版本，Fabric 为 v2，Quilt 为 v3
+    pub api: &'static str,
+    // 加载器版本中映射字段的名称
+    pub mappings: &'static str,
+}
+
+pub struct LoaderMeta {
+    loader: MetaLoader,
+    base: String,
+}
+
+impl LoaderMeta {
+    pub fn new(loader: MetaLoader) -> Self {
+        Self::with_base(
+            loader,
+            config::current().download.mirror.rewrite(loader.url),
+        )
+    }
+
+    pub fn with_base(loader: MetaLoader, base: String) -> Self {
+        Self {
+            loader,
+            base: base.trim_end_matches('/').to_string(),
+        }
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}/{}/versions/{}", self.base, self.loader.api, path)
+    }
+
+    // 有映射、可以安装加载器的游戏版本
+    pub async fn game_versions(&self) -> LauncherResult<Vec<LoaderGameVersion>> {
+        let json = fetch_json(&self.url("game")).await?;
+        Ok(serde_json::from_value(json)?)
+    }
+
+    // 指定游戏版本可用的加载器版本，按新到旧排列
+    // 没有 stable 字段时（Quilt），带 -beta 等后缀的视为测试版
+    pub async fn loader_versions(&self, game_version: &str) -> LauncherResult<Vec<LoaderVersion>> {
+        let json = fetch_json(&self.url(&format!("loader/{}", game_version))).await?;
+        Ok(json
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| {
+                let version = entry["loader"]["version"].as_str()?;
+                Some(LoaderVersion {
+                    version: version.to_string(),
+                    stable: entry["loader"]["stable"]
+                        .as_bool()
+                        .unwrap_or(!version.contains('-')),
+                    mappings: entry[self.loader.mappings]["version"]
+                        .as_str()
+                        .map(|v| v.to_string()),
+                })
+            })
+            .collect())
+    }
+
+    // <loader>-<版本>-<游戏版本> 的版本 JSON
+    pub async fn profile(&self, game_version: &str, loader_version: &str) -> LauncherResult<Value> {
+        fetch_json(&self.url(&format!(
+            "loader/{}/{}/profile/json",
+            game_version, loader_version
+        )))
+        .await
+    }
+
+    // 安装加载器，未指定加载器版本时使用最新稳定版
+    pub async fn install(
+        &self,
+        paths: &MinecraftPaths,
+        game_version: &str,
+        loader_version: Option<String>,
+    ) -> LauncherResult<LoaderInstallResult> {
+        let loader_version = match loader_version {
+            Some(version) => version,
+            None => {
+                let versions = self.loader_versions(game_version).await?;
+                pick_loader(&versions)
+                    .map(|v| v.version.clone())
+                    .ok_or_else(|| {
+                        LauncherError::Unsupported(format!(
+                            "{} 不支持 {}",
+                            self.loader.name, game_version
+                        ))
+                    })?
+            }
+        };
+        let profile = self.profile(game_version, &loader_version).await?;
+        install_profile(paths, &profile).await
+    }
+}
@@ -0,0 +1,376 @@
+/*
+RTLauncher, a third-party Minecraft launcher built with the newest
+technology and provides innovative funtionalities
+Copyright (C) 2025 lutouna
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+// ***
+// 模组加载器安装
+// ***
+
+pub mod fabric;
+pub mod forge;
+pub mod meta;
+pub mod optifine;
+pub mod quilt;
+
+use crate::module::download::dwl_main::DownloadOptions;
+use crate::module::download::installed::{resolve_version, rules_allow};
+use crate::module::download::manifest::load_manifest;
+use crate::module::download::maven::{library_download, MavenCoordinate};
+use crate::module::download::paths::MinecraftPaths;
+use crate::utils::error::{LauncherError, LauncherResult};
+use crate::utils::get_java_path::find_java;
+use crate::utils::request;
+use crate::Setting::config;
+use futures::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+// 加载器支持的游戏版本
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoaderGameVersion {
+    pub version: String,
+    pub stable: bool,
+}
+
+// 加载器版本及其对应的映射版本
+#[derive(Debug, Clone, Serialize)]
+pub struct LoaderVersion {
+    pub version: String,
+    pub stable: bool,
+    pub mappings: Option<String>,
+}
+
+// 加载器安装结果
+#[derive(Debug, Clone, Serialize)]
+pub struct LoaderInstallResult {
+    pub version_id: String,
+    pub inherits_from: String,
+    pub libraries: usize,
+}
+
+// 整合包声明的加载器，loader 为 fabric / quilt / forge / neoforge
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LoaderSpec {
+    pub loader: String,
+    pub version: String,
+}
+
+// 安装原版和指定的加载器，返回启动使用的版本号
+pub async fn install_game(
+    paths: &MinecraftPaths,
+    game_version: &str,
+    loader: Option<&LoaderSpec>,
+) -> LauncherResult<String> {
+    let Some(spec) = loader else {
+        ensure_vanilla(paths, game_version).await?;
+        return Ok(game_version.to_string());
+    };
+    let version = Some(spec.version.clone());
+    let result = match spec.loader.as_str() {
+        "fabric" => {
+            meta::LoaderMeta::new(fabric::FABRIC)
+                .install(paths, game_version, version)
+                .await?
+        }
+        "quilt" => {
+            meta::LoaderMeta::new(quilt::QUILT)
+                .install(paths, game_version, version)
+                .await?
+        }
+        // Forge 的 maven 版本号带有游戏版本前缀
+        "forge" => {
+            let full = if spec.version.starts_with(&format!("{}-", game_version)) {
+                spec.version.clone()
+            } else {
+                format!("{}-{}", game_version, spec.version)
+            };
+            forge::ForgeInstaller::new(forge::ForgeKind::Forge)
+                .install(paths, &full)
+                .await?
+        }
+        "neoforge" => {
+            forge::ForgeInstaller::new(forge::ForgeKind::NeoForge)
+                .install(paths, &spec.version)
+                .await?
+        }
+        other => {
+            return Err(LauncherError::Unsupported(format!(
+                "不支持的加载器: {}",
+                other
+            )))
+        }
+    };
+    Ok(result.version_id)
+}
+
+// 请求 meta 接口并解析 JSON
+pub(crate) async fn fetch_json(url: &str) -> LauncherResult<Value> {
+    let body = request::Request::new(url.to_string()).fetch_get().await?;
+    Ok(serde_json::from_str(&body)?)
+}
+
+// 未指定时优先选择最新的稳定版加载器
+pub(crate) fn pick_loader(versions: &[LoaderVersion]) -> Option<&LoaderVersion> {
+    versions
+        .iter()
+        .find(|v| v.stable)
+        .or_else(|| versions.first())
+}
+
+// 确保被继承的原版已经安装
+pub(crate) async fn ensure_vanilla(
+    paths: &MinecraftPaths,
+    game_version: &str,
+) -> LauncherResult<()> {
+    if paths.get_version_json(game_version).exists() {
+        return Ok(());
+    }
+    log::info!("安装加载器前先安装原版: {}", game_version);
+    let manifest = load_manifest().await?.manifest;
+    let url = manifest["versions"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .find(|v| v["id"].as_str() == Some(game_version))
+        .and_then(|v| v["url"].as_str())
+        .ok_or_else(|| LauncherError::VersionNotFound(game_version.to_string()))?;
+    DownloadOptions::new(url.to_string())
+        .dwl_version_manifest()
+        .await?;
+    Ok(())
+}
+
+// 下载加载器版本 JSON 中的 Maven 库文件，返回库文件数量
+pub(crate) async fn download_libraries(
+    paths: &MinecraftPaths,
+    profile: &Value,
+) -> LauncherResult<usize> {
+    let tasks: Vec<_> = profile["libraries"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|library| rules_allow(&library["rules"]))
+        .filter_map(|library| library_download(paths, library))
+        .collect();
+    let total = tasks.len();
+    let concurrency = config::current().download.library_concurrency.max(1);
+    log::info!("开始下载 {} 个加载器库文件...", total);
+
+    let errors: Vec<LauncherError> = stream::iter(tasks)
+        .map(|library| async move {
+            library.download().await.map_err(|e| {
+                log::error!("库文件下载失败: {} -> {}", library.path.display(), e);
+                e
+            })
+        })
+        .buffer_unordered(concurrency)
+        .filter_map(|result| async move { result.err() })
+        .collect()
+        .await;
+
+    match errors.into_iter().next() {
+        Some(e) => Err(e),
+        None => Ok(total),
+    }
+}
+
+// 将加载器版本 JSON 写入 versions 目录，返回版本号
+pub(crate) fn write_profile(paths: &MinecraftPaths, profile: &Value) -> LauncherResult<String> {
+    let id = profile["id"]
+        .as_str()
+        .ok_or_else(|| LauncherError::Parse("加载器版本 JSON 缺少 id".to_string()))?;
+    let dir = paths.get_version_dir(id);
+    std::fs::create_dir_all(&dir).map_err(|e| LauncherError::io(&dir, e))?;
+    let path = paths.get_version_json(id);
+    std::fs::write(&path, serde_json::to_string_pretty(profile)?)
+        .map_err(|e| LauncherError::io(&path, e))?;
+    Ok(id.to_string())
+}
+
+// 运行安装器使用的 Java：设置中的路径优先，否则按原版要求的主版本查找
+pub(crate) fn installer_java(paths: &MinecraftPaths, minecraft: &str) -> LauncherResult<String> {
+    if let Some(java) = config::current().java.java_path {
+        return Ok(java);
+    }
+    let major = resolve_version(paths, minecraft)
+        .ok()
+        .and_then(|json| json["javaVersion"]["majorVersion"].as_u64())
+        .map(|major| major.to_string())
+        .unwrap_or_default();
+    find_java(&major).ok_or(LauncherError::JavaNotFound(major))
+}
+
+// 根据合并后的版本 JSON 判断安装的加载器
+pub fn detect_loader(json: &Value) -> Option<&'static str> {
+    let names: Vec<&str> = json["libraries"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|library| library["name"].as_str())
+        .collect();
+    let has = |prefix: &str| names.iter().any(|name| name.starts_with(prefix));
+    if has("net.fabricmc:fabric-loader:") {
+        Some("fabric")
+    } else if has("org.quiltmc:quilt-loader:") {
+        Some("quilt")
+    } else if has("net.neoforged:neoforge:") || has("net.neoforged.fancymodloader:") {
+        Some("neoforge")
+    } else if has("net.minecraftforge:forge:") || has("net.minecraftforge:fmlloader:") {
+        Some("forge")
+    } else {
+        None
+    }
+}
+
+// 根据合并后的版本 JSON 读取加载器和版本号，Forge 去掉游戏版本前缀
+pub fn loader_spec(json: &Value, game_version: &str) -> Option<LoaderSpec> {
+    let loader = detect_loader(json)?;
+    let artifacts: &[(&str, &str)] = match loader {
+        "fabric" => &[("net.fabricmc", "fabric-loader")],
+        "quilt" => &[("org.quiltmc", "quilt-loader")],
+        "neoforge" => &[("net.neoforged", "neoforge")],
+        _ => &[
+            ("net.minecraftforge", "forge"),
+            ("net.minecraftforge", "fmlloader"),
+        ],
+    };
+    let version = json["libraries"]
+        .as_array()?
+        .iter()
+        .filter_map(|library| library["name"].as_str())
+        .filter_map(MavenCoordinate::parse)
+        .find(|c| {
+            artifacts
+                .iter()
+                .any(|(group, artifact)| c.group == *group && c.artifact == *artifact)
+        })?
+        .version;
+    let version = version
+        .strip_prefix(&format!("{}-", game_version))
+        .map(str::to_string)
+        .unwrap_or(version);
+    Some(LoaderSpec {
+        loader: loader.to_string(),
+        version,
+    })
+}
+
+// 安装 inheritsFrom 形式的加载器版本：先装原版和库文件，最后写入版本 JSON
+pub(crate) async fn install_profile(
+    paths: &MinecraftPaths,
+    profile: &Value,
+) -> LauncherResult<LoaderInstallResult> {
+    let inherits_from = profile["inheritsFrom"]
+        .as_str()
+        .ok_or_else(|| LauncherError::Parse("加载器版本 JSON 缺少 inheritsFrom".to_string()))?
+        .to_string();
+    ensure_vanilla(paths, &inherits_from).await?;
+    let libraries = download_libraries(paths, profile).await?;
+    let version_id = write_profile(paths, profile)?;
+    log::info!("加载器安装完成: {} (继承 {})", version_id, inherits_from);
+    Ok(LoaderInstallResult {
+        version_id,
+        inherits_from,
+        libraries,
+    })
+}
+
+// 测试用的本地 HTTP 服务器，按路径返回固定内容
+#[cfg(test)]
+pub(crate) mod fake_server {
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+    use tokio::task::JoinHandle;
+
+    // 返回服务器地址，内容中的 {base} 会被替换为该地址
+    pub async fn serve(routes: HashMap<String, Vec<u8>>) -> (String, JoinHandle<()>) {
+        serve_with(|base| {
+            routes
+                .into_iter()
+                .map(|(path, body)| {
+                    let body = String::from_utf8(body.clone())
+                        .map(|text| text.replace("{base}", base).into_bytes())
+                        .unwrap_or(body);
+                    (path, body)
+                })
+                .collect()
+        })
+        .await
+    }
+
+    // 根据服务器地址生成路由，用于内容是二进制的情况
+    pub async fn serve_with(
+        routes: impl FnOnce(&str) -> HashMap<String, Vec<u8>>,
+    ) -> (String, JoinHandle<()>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let routes = Arc::new(routes(&base));
+        let handle = tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let routes = routes.clone();
+                tokio::spawn(async move {
+                    let request = read_request(&mut stream).await;
+                    let path = request.split_whitespace().nth(1).unwrap_or("/");
+                    let (status, body) = match routes.get(path) {
+                        Some(body) => ("200 OK", body.clone()),
+                        None => ("404 Not Found", Vec::new()),
+                    };
+                    let head = format!(
+                        "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                        status,
+                        body.len()
+                    );
+                    let _ = stream.write_all(head.as_bytes()).await;
+                    let _ = stream.write_all(&body).await;
+                });
+            }
+        });
+        (base, handle)
+    }
+
+    // 读取完整的请求头和请求体，避免关闭连接时还有未读的数据
+    async fn read_request(stream: &mut tokio::net::TcpStream) -> String {
+        let mut request = Vec::new();
+        let mut buffer = [0u8; 4096];
+        loop {
+            let read = stream.read(&mut buffer).await.unwrap_or(0);
+            if read == 0 {
+                break;
+            }
+            request.extend_from_slice(&buffer[..read]);
+            let text = String::from_utf8_lossy(&request);
+            if let Some(end) = text.find("\r\n\r\n") {
+                let length = text[..end]
+                    .lines()
+                    .find_map(|line| {
+                        let (name, value) = line.split_once(':')?;
+                        name.eq_ignore_ascii_case("content-length")
+                            .then(|| value.trim().parse::<usize>().ok())?
+                    })
+                    .unwrap_or(0);
+                if request.len() >= end + 4 + length {
+                    break;
+                }
+            }
+        }
+        String::from_utf8_lossy(&request).to_string()
+    }
+}
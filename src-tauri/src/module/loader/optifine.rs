@@ -0,0 +1,584 @@
+/*
+RTLauncher, a third-party Minecraft launcher built with the newest
+technology and provides innovative funtionalities
+Copyright (C) 2025 lutouna
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+// ***
+// OptiFine 安装：独立版本或 Forge 模组
+// ***
+
+use super::{
+    detect_loader, ensure_vanilla, installer_java, loader_spec, write_profile, LoaderInstallResult,
+};
+use crate::module::content::update::BACKUP_DIR;
+use crate::module::download::dwl_main::download_verified;
+use crate::module::download::installed::resolve_version;
+use crate::module::download::maven::{library_download, maven_path};
+use crate::module::download::paths::MinecraftPaths;
+use crate::module::download::verify::read_version_json;
+use crate::module::instance::ins_main::InstanceStore;
+use crate::utils::error::{LauncherError, LauncherResult};
+use crate::Setting::config;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+// OptiFine 下载使用 BMCLAPI 提供的镜像
+pub const OPTIFINE_MIRROR_URL: &str = "https://bmclapi2.bangbang93.com/optifine";
+
+const LAUNCH_WRAPPER_MAIN: &str = "net.minecraft.launchwrapper.Launch";
+const OPTIFINE_TWEAKER: &str = "optifine.OptiFineTweaker";
+// 没有自带 launchwrapper 的旧版 OptiFine 使用 Mojang 的版本
+const LEGACY_LAUNCH_WRAPPER: &str = "net.minecraft:launchwrapper:1.12";
+
+// 可下载的 OptiFine 版本
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OptiFineBuild {
+    #[serde(rename = "mcversion")]
+    pub mc_version: String,
+    #[serde(rename = "type")]
+    pub edition_type: String,
+    pub patch: String,
+    pub filename: String,
+    // 兼容的 Forge 版本，例如 "Forge 47.1.0"
+    pub forge: Option<String>,
+}
+
+impl OptiFineBuild {
+    // HD_U_I6 形式的版本名
+    pub fn edition(&self) -> String {
+        format!("{}_{}", self.edition_type, self.patch)
+    }
+}
+
+// 本地的 OptiFine jar
+#[derive(Debug, Clone, Serialize)]
+pub struct OptiFineJar {
+    pub path: PathBuf,
+    pub mc_version: String,
+    pub edition: String,
+    // 需要的最低 Forge 版本，只有从版本列表下载时才知道
+    pub forge: Option<String>,
+}
+
+impl OptiFineJar {
+    // 从 OptiFine_1.20.1_HD_U_I6.jar 形式的文件名读取游戏版本和版本名
+    pub fn open(path: &Path) -> LauncherResult<Self> {
+        let invalid =
+            || LauncherError::InvalidArgument(format!("不是 OptiFine 文件: {}", path.display()));
+        let stem = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .ok_or_else(invalid)?;
+        let name = stem.strip_prefix("preview_").unwrap_or(stem);
+        let (mc_version, edition) = name
+            .strip_prefix("OptiFine_")
+            .and_then(|rest| rest.split_once('_'))
+            .ok_or_else(invalid)?;
+        let archive = zip::ZipArchive::new(
+            std::fs::File::open(path).map_err(|e| LauncherError::io(path, e))?,
+        )?;
+        if !archive
+            .file_names()
+            .any(|name| name.starts_with("optifine/") || name.starts_with("net/optifine/"))
+        {
+            return Err(invalid());
+        }
+        Ok(Self {
+            path: path.to_path_buf(),
+            mc_version: mc_version.to_string(),
+            edition: edition.to_string(),
+            forge: None,
+        })
+    }
+
+    // 独立版本的版本号
+    pub fn version_id(&self) -> String {
+        format!("{}-OptiFine_{}", self.mc_version, self.edition)
+    }
+
+    fn library_name(&self) -> String {
+        format!("optifine:OptiFine:{}_{}", self.mc_version, self.edition)
+    }
+
+    fn archive(&self) -> LauncherResult<zip::ZipArchive<std::fs::File>> {
+        let file = std::fs::File::open(&self.path).map_err(|e| LauncherError::io(&self.path, e))?;
+        Ok(zip::ZipArchive::new(file)?)
+    }
+
+    // 新版 OptiFine 需要用自带的 Patcher 对原版 jar 打补丁
+    fn has_patcher(&self) -> LauncherResult<bool> {
+        Ok(self.archive()?.by_name("optifine/Patcher.class").is_ok())
+    }
+
+    // 自带的 launchwrapper 版本
+    fn launch_wrapper_version(&self) -> LauncherResult<Option<String>> {
+        let mut archive = self.archive()?;
+        let Ok(mut entry) = archive.by_name("launchwrapper-of.txt") else {
+            return Ok(None);
+        };
+        let mut version = String::new();
+        entry.read_to_string(&mut version)?;
+        Ok(Some(version.trim().to_string()))
+    }
+
+    fn extract(&self, name: &str, target: &Path) -> LauncherResult<()> {
+        let mut archive = self.archive()?;
+        let mut entry = archive.by_name(name)?;
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| LauncherError::io(parent, e))?;
+        }
+        let mut file = std::fs::File::create(target).map_err(|e| LauncherError::io(target, e))?;
+        std::io::copy(&mut entry, &mut file).map_err(|e| LauncherError::io(target, e))?;
+        Ok(())
+    }
+}
+
+// 列出指定游戏版本可下载的 OptiFine，按新到旧排列
+pub async fn list_builds(game_version: &str) -> LauncherResult<Vec<OptiFineBuild>> {
+    let json = super::fetch_json(&format!("{}/{}", OPTIFINE_MIRROR_URL, game_version)).await?;
+    let mut builds: Vec<OptiFineBuild> = serde_json::from_value(json)?;
+    builds.reverse();
+    Ok(builds)
+}
+
+// 下载 OptiFine jar 到缓存目录
+pub async fn download_build(build: &OptiFineBuild) -> LauncherResult<OptiFineJar> {
+    let url = format!(
+        "{}/{}/{}/{}",
+        OPTIFINE_MIRROR_URL, build.mc_version, build.edition_type, build.patch
+    );
+    let path = config::cache_dir().join("optifine").join(&build.filename);
+    download_verified(&url, &path, None).await?;
+    Ok(OptiFineJar {
+        forge: build.forge.clone(),
+        ..OptiFineJar::open(&path)?
+    })
+}
+
+// "Forge 47.1.0"、"Forge #2847" 形式的要求转换为版本号，N/A 等无法识别时为空
+fn forge_requirement(forge: &str) -> Option<Vec<u64>> {
+    let version = forge.trim().strip_prefix("Forge").unwrap_or(forge).trim();
+    version_parts(version.strip_prefix('#').unwrap_or(version))
+}
+
+// 47.2.0 或 10.13.4.1614-1.7.10 形式的版本号，忽略 - 之后的部分
+fn version_parts(version: &str) -> Option<Vec<u64>> {
+    version
+        .split('-')
+        .next()?
+        .split('.')
+        .map(|part| part.parse().ok())
+        .collect()
+}
+
+// 已安装的 Forge 是否满足要求，只给出构建号时与最后一段比较
+fn forge_satisfies(installed: &[u64], required: &[u64]) -> bool {
+    match required {
+        [build] => installed.last().is_some_and(|last| last >= build),
+        _ => installed >= required,
+    }
+}
+
+// 检查 OptiFine 能否加入指定版本的 mods 目录
+pub fn check_mod_compatibility(
+    paths: &MinecraftPaths,
+    version_id: &str,
+    optifine: &OptiFineJar,
+) -> LauncherResult<()> {
+    let json = resolve_version(paths, version_id)?;
+    let game_version = json["jar"].as_str().unwrap_or(version_id);
+    if game_version != optifine.mc_version {
+        return Err(LauncherError::Unsupported(format!(
+            "OptiFine {} 适用于 {}，当前版本是 {}",
+            optifine.edition, optifine.mc_version, game_version
+        )));
+    }
+    match detect_loader(&json) {
+        Some("forge") => {}
+        Some(loader) => {
+            return Err(LauncherError::Unsupported(format!(
+                "OptiFine 不能与 {} 一起使用",
+                loader
+            )))
+        }
+        None => {
+            return Err(LauncherError::Unsupported(
+                "作为模组安装需要 Forge，未安装 Forge 时请将 OptiFine 安装为独立版本".to_string(),
+            ))
+        }
+    }
+
+    // 检查 Forge 版本是否满足 OptiFine 的要求
+    let required = optifine.forge.as_deref().and_then(forge_requirement);
+    let installed = loader_spec(&json, game_version);
+    if let (Some(required), Some(spec)) = (required, installed) {
+        let too_old = version_parts(&spec.version)
+            .is_some_and(|installed| !forge_satisfies(&installed, &required));
+        if too_old {
+            return Err(LauncherError::Unsupported(format!(
+                "OptiFine {} 需要 {}，当前 Forge 版本是 {}",
+                optifine.edition,
+                optifine.forge.as_deref().unwrap_or_default(),
+                spec.version
+            )));
+        }
+    }
+    Ok(())
+}
+
+// 将 OptiFine 安装为继承原版的独立版本
+pub async fn install_as_version(
+    paths: &MinecraftPaths,
+    optifine: &OptiFineJar,
+) -> LauncherResult<LoaderInstallResult> {
+    let minecraft = optifine.mc_version.clone();
+    ensure_vanilla(paths, &minecraft).await?;
+    let vanilla_jar = paths
+        .get_version_dir(&minecraft)
+        .join(format!("{}.jar", minecraft));
+    if !vanilla_jar.exists() {
+        return Err(LauncherError::VersionNotFound(minecraft));
+    }
+
+    // 1. 生成 OptiFine 库文件
+    let library = optifine.library_name();
+    let library_path = paths
+        .libraries_dir
+        .join(maven_path(&library).unwrap_or_default());
+    if let Some(parent) = library_path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| LauncherError::io(parent, e))?;
+    }
+    if optifine.has_patcher()? {
+        let java = installer_java(paths, &minecraft)?;
+        let output = tokio::process::Command::new(&java)
+            .arg("-cp")
+            .arg(&optifine.path)
+            .arg("optifine.Patcher")
+            .arg(&vanilla_jar)
+            .arg(&optifine.path)
+            .arg(&library_path)
+            .output()
+            .await
+            .map_err(|e| LauncherError::Other(format!("无法运行 OptiFine Patcher: {}", e)))?;
+        if !output.status.success() {
+            return Err(LauncherError::Other(format!(
+                "OptiFine Patcher 运行失败 ({}): {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+    } else {
+        std::fs::copy(&optifine.path, &library_path)
+            .map_err(|e| LauncherError::io(&library_path, e))?;
+    }
+
+    // 2. launchwrapper
+    let wrapper = match optifine.launch_wrapper_version()? {
+        Some(version) => {
+            let name = format!("optifine:launchwrapper-of:{}", version);
+            let target = paths
+                .libraries_dir
+                .join(maven_path(&name).unwrap_or_default());
+            optifine.extract(&format!("launchwrapper-of-{}.jar", version), &target)?;
+            name
+        }
+        None => {
+            let library = serde_json::json!({ "name": LEGACY_LAUNCH_WRAPPER });
+            if let Some(download) = library_download(paths, &library) {
+                download.download().await?;
+            }
+            LEGACY_LAUNCH_WRAPPER.to_string()
+        }
+    };
+
+    // 3. 版本 JSON，旧版本使用 minecraftArguments
+    let parent = read_version_json(paths, &minecraft)?;
+    let mut profile = serde_json::json!({
+        "id": optifine.version_id(),
+        "inheritsFrom": minecraft,
+        "type": "release",
+        "mainClass": LAUNCH_WRAPPER_MAIN,
+        "libraries": [{ "name": library }, { "name": wrapper }],
+    });
+    match parent["minecraftArguments"].as_str() {
+        Some(arguments) => {
+            profile["minecraftArguments"] =
+                Value::String(format!("{} --tweakClass {}", arguments, OPTIFINE_TWEAKER));
+        }
+        None => {
+            profile["arguments"] =
+                serde_json::json!({ "game": ["--tweakClass", OPTIFINE_TWEAKER] });
+        }
+    }
+    let version_id = write_profile(paths, &profile)?;
+    log::info!("OptiFine 安装完成: {}", version_id);
+    Ok(LoaderInstallResult {
+        version_id,
+        inherits_from: minecraft,
+        libraries: 2,
+    })
+}
+
+// 将 OptiFine 放入游戏目录的 mods 中，已有的 OptiFine 移到备份目录
+pub fn install_as_mod(game_dir: &Path, optifine: &OptiFineJar) -> LauncherResult<PathBuf> {
+    let mods_dir = game_dir.join("mods");
+    std::fs::create_dir_all(&mods_dir).map_err(|e| LauncherError::io(&mods_dir, e))?;
+    let file_name = optifine
+        .path
+        .file_name()
+        .ok_or_else(|| LauncherError::InvalidArgument(optifine.path.display().to_string()))?;
+    let target = mods_dir.join(file_name);
+
+    // 先复制到临时文件，失败时不影响已有的 OptiFine
+    let temp = mods_dir.join(format!("{}.tmp", file_name.to_string_lossy()));
+    if let Err(e) = std::fs::copy(&optifine.path, &temp) {
+        let _ = std::fs::remove_file(&temp);
+        return Err(LauncherError::io(&temp, e));
+    }
+
+    // 只移走能识别为 OptiFine 的 jar，OptiFabric 等附属模组保持不变
+    let old: Vec<PathBuf> = std::fs::read_dir(&mods_dir)
+        .map_err(|e| LauncherError::io(&mods_dir, e))?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("jar"))
+        })
+        .filter(|path| OptiFineJar::open(path).is_ok())
+        .collect();
+    if !old.is_empty() {
+        let backup_dir = game_dir
+            .join(BACKUP_DIR)
+            .join(chrono::Local::now().format("%Y%m%d-%H%M%S").to_string());
+        let moved = std::fs::create_dir_all(&backup_dir)
+            .map_err(|e| LauncherError::io(&backup_dir, e))
+            .and_then(|_| {
+                old.iter().try_for_each(|path| {
+                    log::info!("备份旧的 OptiFine: {}", path.display());
+                    let backup = backup_dir.join(path.file_name().unwrap_or_default());
+                    std::fs::rename(path, &backup).map_err(|e| LauncherError::io(path, e))
+                })
+            });
+        if let Err(e) = moved {
+            let _ = std::fs::remove_file(&temp);
+            return Err(e);
+        }
+    }
+
+    std::fs::rename(&temp, &target).map_err(|e| LauncherError::io(&target, e))?;
+    Ok(target)
+}
+
+// 使用用户提供的 jar，或下载指定游戏版本的 OptiFine（未指定版本名时使用最新版）
+async fn resolve_jar(
+    jar_path: Option<String>,
+    game_version: &str,
+    edition: Option<String>,
+) -> LauncherResult<OptiFineJar> {
+    if let Some(path) = jar_path {
+        let optifine = OptiFineJar::open(Path::new(&path))?;
+        if optifine.mc_version != game_version {
+            return Err(LauncherError::Unsupported(format!(
+                "OptiFine {} 适用于 {}，不能安装到 {}",
+                optifine.edition, optifine.mc_version, game_version
+            )));
+        }
+        return Ok(optifine);
+    }
+    let builds = list_builds(game_version).await?;
+    let build = match &edition {
+        Some(edition) => builds.iter().find(|b| &b.edition() == edition),
+        None => builds.first(),
+    }
+    .ok_or_else(|| {
+        LauncherError::VersionNotFound(format!(
+            "OptiFine {} {}",
+            game_version,
+            edition.unwrap_or_default()
+        ))
+    })?;
+    download_build(build).await
+}
+
+#[tauri::command]
+pub async fn get_optifine_versions(game_version: String) -> LauncherResult<Vec<OptiFineBuild>> {
+    list_builds(&game_version).await
+}
+
+#[tauri::command]
+pub async fn install_optifine(
+    game_version: String,
+    edition: Option<String>,
+    jar_path: Option<String>,
+) -> LauncherResult<LoaderInstallResult> {
+    let optifine = resolve_jar(jar_path, &game_version, edition).await?;
+    install_as_version(&MinecraftPaths::new(), &optifine).await
+}
+
+#[tauri::command]
+pub async fn install_optifine_mod(
+    instance_id: String,
+    edition: Option<String>,
+    jar_path: Option<String>,
+) -> LauncherResult<PathBuf> {
+    let instance = InstanceStore::new().get(&instance_id)?;
+    let paths = MinecraftPaths::new();
+    let game_version = resolve_version(&paths, &instance.version_id)?["jar"]
+        .as_str()
+        .unwrap_or(&instance.version_id)
+        .to_string();
+    let mut optifine = resolve_jar(jar_path, &game_version, edition).await?;
+    // 本地文件没有 Forge 要求，尽量从版本列表中查找
+    if optifine.forge.is_none() {
+        if let Ok(builds) = list_builds(&game_version).await {
+            optifine.forge = builds
+                .into_iter()
+                .find(|build| build.edition() == optifine.edition)
+                .and_then(|build| build.forge);
+        }
+    }
+    check_mod_compatibility(&paths, &instance.version_id, &optifine)?;
+    install_as_mod(&instance.game_dir(), &optifine)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::module::download::installed::loader_arguments;
+    use std::io::Write;
+
+    fn write_json(paths: &MinecraftPaths, id: &str, json: Value) {
+        std::fs::create_dir_all(paths.get_version_dir(id)).unwrap();
+        std::fs::write(paths.get_version_json(id), json.to_string()).unwrap();
+    }
+
+    fn optifine_jar(path: &Path) {
+        let mut writer = zip::ZipWriter::new(std::fs::File::create(path).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        for (name, content) in [
+            ("net/optifine/Config.class", &b"config"[..]),
+            ("launchwrapper-of.txt", b"2.3\n"),
+            ("launchwrapper-of-2.3.jar", b"wrapper"),
+        ] {
+            writer.start_file(name, options).unwrap();
+            writer.write_all(content).unwrap();
+        }
+        writer.finish().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_install_optifine_version_and_mod() {
+        let dir = std::env::temp_dir().join(format!("rtl-optifine-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let paths = MinecraftPaths::with_base_dir(dir.clone());
+        write_json(
+            &paths,
+            "1.20.1",
+            serde_json::json!({ "id": "1.20.1", "arguments": { "game": [] }, "libraries": [] }),
+        );
+        std::fs::write(
+            paths.get_version_dir("1.20.1").join("1.20.1.jar"),
+            b"vanilla",
+        )
+        .unwrap();
+        let jar_path = dir.join("OptiFine_1.20.1_HD_U_I6.jar");
+        optifine_jar(&jar_path);
+
+        let optifine = OptiFineJar::open(&jar_path).unwrap();
+        assert_eq!(optifine.version_id(), "1.20.1-OptiFine_HD_U_I6");
+        let result = install_as_version(&paths, &optifine).await.unwrap();
+        let json = resolve_version(&paths, &result.version_id).unwrap();
+        assert_eq!(json["mainClass"], LAUNCH_WRAPPER_MAIN);
+        assert!(paths
+            .libraries_dir
+            .join("optifine/launchwrapper-of/2.3/launchwrapper-of-2.3.jar")
+            .exists());
+        assert!(paths
+            .libraries_dir
+            .join("optifine/OptiFine/1.20.1_HD_U_I6/OptiFine-1.20.1_HD_U_I6.jar")
+            .exists());
+        let (_, game) = loader_arguments(&paths, &result.version_id);
+        assert_eq!(game, vec!["--tweakClass", OPTIFINE_TWEAKER]);
+
+        // 作为模组只能与同版本的 Forge 一起使用
+        write_json(
+            &paths,
+            "fabric",
+            serde_json::json!({ "id": "fabric", "inheritsFrom": "1.20.1",
+            "libraries": [{ "name": "net.fabricmc:fabric-loader:0.16.9" }] }),
+        );
+        write_json(
+            &paths,
+            "forge",
+            serde_json::json!({ "id": "forge", "inheritsFrom": "1.20.1",
+            "libraries": [{ "name": "net.minecraftforge:fmlloader:1.20.1-47.2.0" }] }),
+        );
+        assert!(check_mod_compatibility(&paths, "fabric", &optifine).is_err());
+        assert!(check_mod_compatibility(&paths, "1.20.1", &optifine).is_err());
+        check_mod_compatibility(&paths, "forge", &optifine).unwrap();
+
+        // Forge 版本低于 OptiFine 的要求
+        let requires = |forge: &str| OptiFineJar {
+            forge: Some(forge.to_string()),
+            ..optifine.clone()
+        };
+        check_mod_compatibility(&paths, "forge", &requires("Forge 47.1.0")).unwrap();
+        check_mod_compatibility(&paths, "forge", &requires("Forge N/A")).unwrap();
+        assert!(matches!(
+            check_mod_compatibility(&paths, "forge", &requires("Forge 47.2.17")),
+            Err(LauncherError::Unsupported(_))
+        ));
+        assert!(forge_satisfies(
+            &version_parts("14.23.5.2860").unwrap(),
+            &forge_requirement("Forge #2847").unwrap()
+        ));
+        assert!(!forge_satisfies(
+            &version_parts("10.13.4.1558-1.7.10").unwrap(),
+            &forge_requirement("Forge 10.13.4.1614").unwrap()
+        ));
+
+        // 旧的 OptiFine 移到备份目录，附属模组保留
+        let game_dir = dir.join("instance");
+        let mods_dir = game_dir.join("mods");
+        std::fs::create_dir_all(&mods_dir).unwrap();
+        optifine_jar(&mods_dir.join("OptiFine_1.20.1_HD_U_I5.jar"));
+        std::fs::write(mods_dir.join("optifine-compat.jar"), b"addon").unwrap();
+        let installed = install_as_mod(&game_dir, &optifine).unwrap();
+        assert!(installed.exists());
+        let mut names: Vec<String> = std::fs::read_dir(&mods_dir)
+            .unwrap()
+            .flatten()
+            .map(|entry| entry.file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        assert_eq!(
+            names,
+            ["OptiFine_1.20.1_HD_U_I6.jar", "optifine-compat.jar"]
+        );
+        let backups = walkdir::WalkDir::new(game_dir.join(BACKUP_DIR))
+            .into_iter()
+            .flatten()
+            .filter(|entry| entry.file_name() == "OptiFine_1.20.1_HD_U_I5.jar")
+            .count();
+        assert_eq!(backups, 1);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
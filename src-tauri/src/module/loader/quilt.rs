@@ -0,0 +1,128 @@
+/*
+RTLauncher, a third-party Minecraft launcher built with the newest
+technology and provides innovative funtionalities
+Copyright (C) 2025 lutouna
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+// ***
+// Quilt 加载器安装
+// ***
+
+use super::meta::{LoaderMeta, MetaLoader};
+use super::{LoaderGameVersion, LoaderInstallResult, LoaderVersion};
+use crate::module::download::paths::MinecraftPaths;
+use crate::utils::error::LauncherResult;
+
+pub const QUILT_META_URL: &str = "https://meta.quiltmc.org";
+
+pub const QUILT: MetaLoader = MetaLoader {
+    name: "Quilt",
+    url: QUILT_META_URL,
+    api: "v3",
+    mappings: "hashed",
+};
+
+#[tauri::command]
+pub async fn get_quilt_game_versions() -> LauncherResult<Vec<LoaderGameVersion>> {
+    LoaderMeta::new(QUILT).game_versions().await
+}
+
+#[tauri::command]
+pub async fn get_quilt_loader_versions(game_version: String) -> LauncherResult<Vec<LoaderVersion>> {
+    LoaderMeta::new(QUILT).loader_versions(&game_version).await
+}
+
+#[tauri::command]
+pub async fn install_quilt(
+    game_version: String,
+    loader_version: Option<String>,
+) -> LauncherResult<LoaderInstallResult> {
+    LoaderMeta::new(QUILT)
+        .install(&MinecraftPaths::new(), &game_version, loader_version)
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::fake_server;
+    use super::*;
+    use std::collections::HashMap;
+
+    #[tokio::test]
+    async fn test_install_quilt_with_maven_libraries() {
+        let profile = serde_json::json!({
+            "id": "quilt-loader-0.27.1-1.21.4",
+            "inheritsFrom": "1.21.4",
+            "mainClass": "org.quiltmc.loader.impl.launch.knot.KnotClient",
+            "libraries": [
+                { "name": "org.quiltmc:quilt-loader:0.27.1", "url": "{base}/repository/release/" },
+                { "name": "org.quiltmc:hashed:1.21.4", "url": "{base}/repository/release/" }
+            ]
+        });
+        let routes: HashMap<String, Vec<u8>> = [
+            (
+                "/v3/versions/loader/1.21.4",
+                r#"[{"loader":{"version":"0.28.0-beta.1"},"hashed":{"version":"1.21.4"}},
+                    {"loader":{"version":"0.27.1"},"hashed":{"version":"1.21.4"}}]"#
+                    .as_bytes()
+                    .to_vec(),
+            ),
+            (
+                "/v3/versions/loader/1.21.4/0.27.1/profile/json",
+                profile.to_string().into_bytes(),
+            ),
+            (
+                "/repository/release/org/quiltmc/quilt-loader/0.27.1/quilt-loader-0.27.1.jar",
+                b"loader".to_vec(),
+            ),
+            (
+                "/repository/release/org/quiltmc/hashed/1.21.4/hashed-1.21.4.jar",
+                b"hashed".to_vec(),
+            ),
+        ]
+        .into_iter()
+        .map(|(path, body)| (path.to_string(), body))
+        .collect();
+        let (base, server) = fake_server::serve(routes).await;
+
+        let dir = std::env::temp_dir().join(format!("rtl-quilt-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let paths = MinecraftPaths::with_base_dir(dir.clone());
+        std::fs::create_dir_all(paths.get_version_dir("1.21.4")).unwrap();
+        std::fs::write(
+            paths.get_version_json("1.21.4"),
+            r#"{"id":"1.21.4","libraries":[]}"#,
+        )
+        .unwrap();
+
+        let meta = LoaderMeta::with_base(QUILT, base);
+        let loaders = meta.loader_versions("1.21.4").await.unwrap();
+        assert!(!loaders[0].stable);
+        assert_eq!(loaders[1].mappings.as_deref(), Some("1.21.4"));
+
+        let result = meta.install(&paths, "1.21.4", None).await.unwrap();
+        assert_eq!(result.version_id, "quilt-loader-0.27.1-1.21.4");
+        assert_eq!(result.libraries, 2);
+        let hashed = paths
+            .libraries_dir
+            .join("org/quiltmc/hashed/1.21.4/hashed-1.21.4.jar");
+        assert_eq!(std::fs::read(hashed).unwrap(), b"hashed");
+        assert!(paths.get_version_json(&result.version_id).exists());
+
+        server.abort();
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -17,5 +17,9 @@ You should have received a copy of the GNU General Public License
 along with this program.  If not, see <https://www.gnu.org/licenses/>.
 */
 
+pub mod content;
 pub mod download;
+pub mod instance;
+pub mod loader;
+pub mod modpack;
 pub mod start_game;
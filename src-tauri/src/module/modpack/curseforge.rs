@@ -0,0 +1,349 @@
+/*
+RTLauncher, a third-party Minecraft launcher built with the newest
+technology and provides innovative funtionalities
+Copyright (C) 2025 lutouna
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+// ***
+// CurseForge 整合包导入
+// ***
+
+use super::{extract_overrides, open_pack, ManualDownload, ModpackImport, PackInstall};
+use crate::module::content::curseforge::{content_type_for_class, CurseForgeApi};
+use crate::module::content::download::range_download;
+use crate::module::content::{safe_file_name, ContentType};
+use crate::module::download::paths::MinecraftPaths;
+use crate::module::instance::ins_main::InstanceStore;
+use crate::module::loader::{install_game, LoaderSpec};
+use crate::utils::error::{LauncherError, LauncherResult};
+use futures::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+const MANIFEST_FILE: &str = "manifest.json";
+// 同时下载的文件数
+const CONCURRENCY: usize = 8;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CurseForgeManifest {
+    pub minecraft: ManifestMinecraft,
+    #[serde(default = "default_manifest_type")]
+    pub manifest_type: String,
+    #[serde(default = "default_manifest_version")]
+    pub manifest_version: u32,
+    pub name: String,
+    #[serde(default)]
+    pub version: String,
+    #[serde(default)]
+    pub author: String,
+    #[serde(default)]
+    pub files: Vec<ManifestFile>,
+    #[serde(default = "default_overrides")]
+    pub overrides: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestMinecraft {
+    pub version: String,
+    #[serde(default)]
+    pub mod_loaders: Vec<ManifestLoader>,
+}
+
+// id 形如 forge-47.2.0、fabric-0.16.9
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestLoader {
+    pub id: String,
+    #[serde(default)]
+    pub primary: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestFile {
+    #[serde(rename = "projectID")]
+    pub project_id: u64,
+    #[serde(rename = "fileID")]
+    pub file_id: u64,
+    #[serde(default = "default_required")]
+    pub required: bool,
+}
+
+pub(crate) fn default_manifest_type() -> String {
+    "minecraftModpack".to_string()
+}
+
+fn default_manifest_version() -> u32 {
+    1
+}
+
+fn default_overrides() -> String {
+    "overrides".to_string()
+}
+
+fn default_required() -> bool {
+    true
+}
+
+impl CurseForgeManifest {
+    // 主加载器，没有标记 primary 时取第一个
+    pub fn loader(&self) -> Option<LoaderSpec> {
+        let loaders = &self.minecraft.mod_loaders;
+        let loader = loaders.iter().find(|l| l.primary).or(loaders.first())?;
+        let (name, version) = loader.id.split_once('-')?;
+        Some(LoaderSpec {
+            loader: name.to_lowercase(),
+            version: version.to_string(),
+        })
+    }
+}
+
+pub fn read_manifest(pack: &Path) -> LauncherResult<CurseForgeManifest> {
+    let mut archive = open_pack(pack)?;
+    let entry = archive
+        .by_name(MANIFEST_FILE)
+        .map_err(|_| LauncherError::Parse(format!("不是 CurseForge 整合包: {}", pack.display())))?;
+    let manifest: CurseForgeManifest = serde_json::from_reader(entry)?;
+    if manifest.manifest_type != "minecraftModpack" {
+        return Err(LauncherError::Unsupported(format!(
+            "不支持的整合包类型: {}",
+            manifest.manifest_type
+        )));
+    }
+    Ok(manifest)
+}
+
+// 通过 API 获取下载地址并下载，没有下载地址的文件加入手动下载列表
+pub async fn install_pack(
+    pack: &Path,
+    manifest: &CurseForgeManifest,
+    game_dir: &Path,
+    api: &CurseForgeApi,
+) -> LauncherResult<PackInstall> {
+    let mut install = PackInstall::default();
+    let (wanted, skipped): (Vec<&ManifestFile>, Vec<&ManifestFile>) =
+        manifest.files.iter().partition(|file| file.required);
+    install.skipped = skipped
+        .iter()
+        .map(|file| format!("{}/{}", file.project_id, file.file_id))
+        .collect();
+
+    if !wanted.is_empty() {
+        let file_ids: Vec<u64> = wanted.iter().map(|file| file.file_id).collect();
+        let mod_ids: Vec<u64> = wanted.iter().map(|file| file.project_id).collect();
+        let files = api.files_by_ids(&file_ids).await?;
+        // 项目信息只用于确定目录和网页地址，失败时默认放入 mods
+        let projects: HashMap<u64, _> = match api.mods_by_ids(&mod_ids).await {
+            Ok(projects) => projects.into_iter().map(|p| (p.id, p)).collect(),
+            Err(e) => {
+                log::warn!("获取 CurseForge 项目信息失败: {}", e);
+                HashMap::new()
+            }
+        };
+        let found: Vec<u64> = files.iter().map(|file| file.id).collect();
+        install.failed.extend(
+            wanted
+                .iter()
+                .filter(|file| !found.contains(&file.file_id))
+                .map(|file| format!("{}/{}", file.project_id, file.file_id)),
+        );
+
+        // 作者关闭第三方下载的文件不下载，直接加入手动下载列表
+        let mut jobs = Vec::new();
+        for file in &files {
+            let project = projects.get(&file.mod_id);
+            let content_type = project
+                .and_then(|p| p.class_id)
+                .and_then(content_type_for_class)
+                .unwrap_or(ContentType::Mod);
+            // 数据包要放进存档才会加载，导入时还没有存档
+            if content_type == ContentType::DataPack {
+                install.failed.push(format!(
+                    "{}: 数据包需要指定存档，请在创建世界后手动安装",
+                    file.file_name
+                ));
+                continue;
+            }
+            let folder = content_type.folder();
+            if file.download_url.as_deref().is_none_or(str::is_empty) {
+                install.manual.push(ManualDownload {
+                    project_id: file.mod_id.to_string(),
+                    file_id: file.id.to_string(),
+                    name: project
+                        .map(|p| p.name.clone())
+                        .unwrap_or_else(|| file.display_name.clone()),
+                    file_name: file.file_name.clone(),
+                    website_url: project
+                        .and_then(|p| p.links.as_ref())
+                        .and_then(|links| links.website_url.as_ref())
+                        .map(|url| format!("{}/files/{}", url.trim_end_matches('/'), file.id)),
+                    target: folder.to_string(),
+                });
+                continue;
+            }
+            match safe_file_name(&file.file_name) {
+                Ok(name) => jobs.push((file.unified_file(), game_dir.join(folder).join(name))),
+                Err(e) => install.failed.push(format!("{}: {}", file.file_name, e)),
+            }
+        }
+
+        let results: Vec<(String, LauncherResult<()>)> = stream::iter(jobs)
+            .map(|(file, target)| async move {
+                let result =
+                    range_download(&file.download_url, &target, file.sha1.as_deref()).await;
+                (file.file_name, result)
+            })
+            .buffer_unordered(CONCURRENCY)
+            .collect()
+            .await;
+        for (file_name, result) in results {
+            match result {
+                Ok(()) => install.downloaded += 1,
+                Err(e) => install.failed.push(format!("{}: {}", file_name, e)),
+            }
+        }
+    }
+
+    let mut archive = open_pack(pack)?;
+    extract_overrides(&mut archive, &manifest.overrides, game_dir)?;
+    Ok(install)
+}
+
+// 导入 CurseForge 整合包：安装游戏和加载器，创建实例后安装整合包文件
+pub async fn import(pack: &Path, name: Option<String>) -> LauncherResult<ModpackImport> {
+    let manifest = read_manifest(pack)?;
+    let version_id = install_game(
+        &MinecraftPaths::new(),
+        &manifest.minecraft.version,
+        manifest.loader().as_ref(),
+    )
+    .await?;
+    let name = name.unwrap_or_else(|| manifest.name.clone());
+    let instance = InstanceStore::new().create(&name, &version_id)?;
+    let install =
+        install_pack(pack, &manifest, &instance.game_dir(), &CurseForgeApi::new()).await?;
+    log::info!(
+        "导入整合包 {} 完成，下载 {} 个文件，{} 个需要手动下载",
+        manifest.name,
+        install.downloaded,
+        install.manual.len()
+    );
+    Ok(install.into_import(instance))
+}
+
+#[tauri::command]
+pub async fn import_curseforge_pack(
+    path: PathBuf,
+    name: Option<String>,
+) -> LauncherResult<ModpackImport> {
+    import(&path, name).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::module::content::scanner::tests::write_jar;
+    use crate::module::loader::fake_server;
+
+    #[tokio::test]
+    async fn test_install_curseforge_pack() {
+        let dir = std::env::temp_dir().join(format!("rtl-cf-pack-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let (base, server) = fake_server::serve(HashMap::from([
+            (
+                "/v1/mods/files".to_string(),
+                br#"{"data":[
+                    {"id":5101,"modId":238222,"displayName":"JEI","fileName":"jei.jar","downloadUrl":"{base}/jei.jar"},
+                    {"id":5102,"modId":300000,"displayName":"Restricted","fileName":"restricted.jar","downloadUrl":null},
+                    {"id":5103,"modId":400000,"displayName":"Pack","fileName":"pack.zip","downloadUrl":"{base}/pack.zip"},
+                    {"id":5105,"modId":600000,"displayName":"Data","fileName":"data.zip","downloadUrl":"{base}/data.zip"}
+                ]}"#
+                .to_vec(),
+            ),
+            (
+                "/v1/mods".to_string(),
+                br#"{"data":[
+                    {"id":238222,"name":"JEI","classId":6},
+                    {"id":300000,"name":"Restricted Mod","classId":6,"links":{"websiteUrl":"https://www.curseforge.com/minecraft/mc-mods/restricted"}},
+                    {"id":400000,"name":"Faithful","classId":12},
+                    {"id":600000,"name":"Datapack","classId":6945}
+                ]}"#
+                .to_vec(),
+            ),
+            ("/jei.jar".to_string(), b"jei".to_vec()),
+            ("/pack.zip".to_string(), b"pack".to_vec()),
+            ("/data.zip".to_string(), b"data".to_vec()),
+        ]))
+        .await;
+
+        let manifest = serde_json::json!({
+            "minecraft": { "version": "1.20.1", "modLoaders": [{ "id": "forge-47.2.0", "primary": true }] },
+            "manifestType": "minecraftModpack",
+            "manifestVersion": 1,
+            "name": "Fixture",
+            "files": [
+                { "projectID": 238222, "fileID": 5101, "required": true },
+                { "projectID": 300000, "fileID": 5102, "required": true },
+                { "projectID": 400000, "fileID": 5103, "required": true },
+                { "projectID": 500000, "fileID": 5104, "required": false },
+                { "projectID": 600000, "fileID": 5105, "required": true }
+            ],
+            "overrides": "overrides"
+        });
+        let pack = dir.join("pack.zip");
+        write_jar(
+            &pack,
+            &[
+                (MANIFEST_FILE, manifest.to_string().as_bytes()),
+                ("overrides/config/jei.toml", b"x"),
+            ],
+        );
+
+        let manifest = read_manifest(&pack).unwrap();
+        assert_eq!(
+            manifest.loader(),
+            Some(LoaderSpec {
+                loader: "forge".to_string(),
+                version: "47.2.0".to_string()
+            })
+        );
+
+        let game_dir = dir.join("game");
+        let api = CurseForgeApi::with_base(base, vec!["key".to_string()]);
+        let install = install_pack(&pack, &manifest, &game_dir, &api)
+            .await
+            .unwrap();
+        assert_eq!(install.downloaded, 2);
+        assert_eq!(install.skipped, ["500000/5104"]);
+        assert_eq!(install.failed.len(), 1);
+        assert!(install.failed[0].starts_with("data.zip: "));
+        assert!(!game_dir.join("datapacks").exists());
+        assert_eq!(install.manual.len(), 1);
+        assert_eq!(install.manual[0].file_name, "restricted.jar");
+        assert_eq!(
+            install.manual[0].website_url.as_deref(),
+            Some("https://www.curseforge.com/minecraft/mc-mods/restricted/files/5102")
+        );
+        assert!(game_dir.join("mods/jei.jar").exists());
+        assert!(game_dir.join("resourcepacks/pack.zip").exists());
+        assert!(game_dir.join("config/jei.toml").exists());
+
+        server.abort();
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,529 @@
+/*
+RTLauncher, a third-party Minecraft launcher built with the newest
+technology and provides innovative funtionalities
+Copyright (C) 2025 lutouna
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+// ***
+// 整合包导出
+// ***
+
+use super::curseforge::{
+    default_manifest_type, CurseForgeManifest, ManifestFile, ManifestLoader, ManifestMinecraft,
+};
+use super::mrpack::{MrpackFile, MrpackIndex};
+use crate::module::content::curseforge::{fingerprint, CurseForgeApi};
+use crate::module::content::filter::ContentFilter;
+use crate::module::content::index::INDEX_FILE;
+use crate::module::content::modrinth::ModrinthApi;
+use crate::module::content::update::BACKUP_DIR;
+use crate::module::download::installed::resolve_version;
+use crate::module::download::paths::MinecraftPaths;
+use crate::module::download::verify::sha1_file;
+use crate::module::instance::ins_main::{InstanceStore, INSTANCE_FILE};
+use crate::module::loader::{loader_spec, LoaderSpec};
+use crate::utils::error::{LauncherError, LauncherResult};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+// 始终不导出的启动器文件
+const ALWAYS_EXCLUDED: [&str; 3] = [INSTANCE_FILE, INDEX_FILE, BACKUP_DIR];
+// 默认不勾选的目录：存档、日志和各种缓存
+const DEFAULT_EXCLUDED: [&str; 10] = [
+    "saves",
+    "logs",
+    "crash-reports",
+    "screenshots",
+    "natives",
+    ".cache",
+    ".fabric",
+    ".mixin.out",
+    "cache",
+    "usercache.json",
+];
+// 可以在平台上查找的目录
+const PLATFORM_DIRS: [&str; 3] = ["mods", "resourcepacks", "shaderpacks"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    Mrpack,
+    CurseForge,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportOptions {
+    pub format: ExportFormat,
+    pub name: String,
+    pub version: String,
+    #[serde(default)]
+    pub author: Option<String>,
+    #[serde(default)]
+    pub summary: Option<String>,
+    // 游戏目录下要导出的文件和目录
+    pub include: Vec<String>,
+}
+
+// 游戏目录下可以选择导出的条目
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportEntry {
+    pub name: String,
+    pub is_dir: bool,
+    // 默认是否勾选
+    pub selected: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModpackExport {
+    pub path: PathBuf,
+    // 引用平台下载地址的文件数
+    pub referenced: usize,
+    // 打包进 overrides 的文件
+    pub bundled: Vec<String>,
+}
+
+// 待导出的文件，relative 使用 / 分隔
+struct PackFile {
+    relative: String,
+    path: PathBuf,
+}
+
+impl PackFile {
+    fn on_platform(&self) -> bool {
+        let mut parts = self.relative.split('/');
+        let dir = parts.next().unwrap_or_default();
+        PLATFORM_DIRS.contains(&dir)
+            && parts.count() == 1
+            && (self.relative.ends_with(".jar") || self.relative.ends_with(".zip"))
+    }
+}
+
+// 列出游戏目录下的条目，存档、日志和缓存默认不勾选
+pub fn list_entries(game_dir: &Path) -> Vec<ExportEntry> {
+    let mut entries: Vec<ExportEntry> = std::fs::read_dir(game_dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            (!ALWAYS_EXCLUDED.contains(&name.as_str())).then(|| ExportEntry {
+                selected: !DEFAULT_EXCLUDED.contains(&name.as_str()),
+                is_dir: entry.path().is_dir(),
+                name,
+            })
+        })
+        .collect();
+    entries.sort_by(|a, b| b.is_dir.cmp(&a.is_dir).then(a.name.cmp(&b.name)));
+    entries
+}
+
+fn collect_files(game_dir: &Path, include: &[String]) -> LauncherResult<Vec<PackFile>> {
+    let mut files = Vec::new();
+    for name in include {
+        if ALWAYS_EXCLUDED.contains(&name.as_str()) || name.contains(['/', '\\']) || name == ".." {
+            continue;
+        }
+        let root = game_dir.join(name);
+        if !root.exists() {
+            continue;
+        }
+        for entry in walkdir::WalkDir::new(&root) {
+            let entry = entry.map_err(|e| LauncherError::Other(e.to_string()))?;
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let Ok(relative) = entry.path().strip_prefix(game_dir) else {
+                continue;
+            };
+            let relative: Vec<String> = relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().into_owned())
+                .collect();
+            files.push(PackFile {
+                relative: relative.join("/"),
+                path: entry.into_path(),
+            });
+        }
+    }
+    files.sort_by(|a, b| a.relative.cmp(&b.relative));
+    Ok(files)
+}
+
+// 按 SHA-1 在 Modrinth 查找文件，找到的文件使用 Modrinth 的下载地址
+async fn modrinth_files(
+    files: &[PackFile],
+    api: &ModrinthApi,
+) -> LauncherResult<HashMap<String, MrpackFile>> {
+    let mut hashes = HashMap::new();
+    for file in files.iter().filter(|file| file.on_platform()) {
+        let sha1 = sha1_file(&file.path).map_err(|e| LauncherError::io(&file.path, e))?;
+        hashes.insert(sha1, file);
+    }
+    if hashes.is_empty() {
+        return Ok(HashMap::new());
+    }
+    let keys: Vec<String> = hashes.keys().cloned().collect();
+    let versions = match api.versions_by_hash(&keys, "sha1").await {
+        Ok(versions) => versions,
+        Err(e) => {
+            log::warn!("Modrinth 查找文件失败，全部打包进 overrides: {}", e);
+            return Ok(HashMap::new());
+        }
+    };
+    let mut resolved = HashMap::new();
+    for (sha1, version) in versions {
+        let Some(file) = hashes.get(&sha1) else {
+            continue;
+        };
+        let Some(remote) = version
+            .files
+            .iter()
+            .find(|f| f.hashes.get("sha1") == Some(&sha1))
+        else {
+            continue;
+        };
+        let Some(sha512) = remote.hashes.get("sha512") else {
+            continue;
+        };
+        let size = std::fs::metadata(&file.path).map(|m| m.len()).ok();
+        resolved.insert(
+            file.relative.clone(),
+            MrpackFile {
+                path: file.relative.clone(),
+                hashes: HashMap::from([
+                    ("sha1".to_string(), sha1.clone()),
+                    ("sha512".to_string(), sha512.clone()),
+                ]),
+                env: None,
+                downloads: vec![remote.url.clone()],
+                file_size: size,
+            },
+        );
+    }
+    Ok(resolved)
+}
+
+// 按指纹在 CurseForge 查找文件
+async fn curseforge_files(
+    files: &[PackFile],
+    api: &CurseForgeApi,
+) -> LauncherResult<HashMap<String, ManifestFile>> {
+    if !api.has_keys() {
+        log::warn!("没有配置 CurseForge API key，全部文件打包进 overrides");
+        return Ok(HashMap::new());
+    }
+    let mut fingerprints = HashMap::new();
+    for file in files.iter().filter(|file| file.on_platform()) {
+        let bytes = std::fs::read(&file.path).map_err(|e| LauncherError::io(&file.path, e))?;
+        fingerprints.insert(fingerprint(&bytes) as u64, file);
+    }
+    if fingerprints.is_empty() {
+        return Ok(HashMap::new());
+    }
+    let keys: Vec<u32> = fingerprints.keys().map(|&f| f as u32).collect();
+    let matches = match api.fingerprint_matches(&keys).await {
+        Ok(matches) => matches,
+        Err(e) => {
+            log::warn!("CurseForge 查找文件失败，全部打包进 overrides: {}", e);
+            return Ok(HashMap::new());
+        }
+    };
+    Ok(matches
+        .into_iter()
+        .filter_map(|matched| {
+            let file = fingerprints.get(&matched.file.file_fingerprint?)?;
+            Some((
+                file.relative.clone(),
+                ManifestFile {
+                    project_id: matched.file.mod_id,
+                    file_id: matched.file.id,
+                    required: true,
+                },
+            ))
+        })
+        .collect())
+}
+
+// Modrinth 整合包中加载器的依赖名
+fn mrpack_loader_key(loader: &str) -> &str {
+    match loader {
+        "fabric" => "fabric-loader",
+        "quilt" => "quilt-loader",
+        other => other,
+    }
+}
+
+// 写入整合包：索引文件和 overrides
+fn write_pack(
+    output: &Path,
+    index_name: &str,
+    index: &[u8],
+    overrides: &[&PackFile],
+) -> LauncherResult<()> {
+    if let Some(parent) = output.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| LauncherError::io(parent, e))?;
+    }
+    let file = std::fs::File::create(output).map_err(|e| LauncherError::io(output, e))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+    zip.start_file(index_name, options)?;
+    zip.write_all(index)?;
+    for file in overrides {
+        let content = std::fs::read(&file.path).map_err(|e| LauncherError::io(&file.path, e))?;
+        zip.start_file(format!("overrides/{}", file.relative), options)?;
+        zip.write_all(&content)?;
+    }
+    zip.finish()?;
+    Ok(())
+}
+
+// 导出游戏目录，平台上能找到的文件只写入下载信息，其余文件打包进 overrides
+pub async fn export_dir(
+    game_dir: &Path,
+    game_version: &str,
+    loader: Option<&LoaderSpec>,
+    options: &ExportOptions,
+    output: &Path,
+    modrinth: &ModrinthApi,
+    curseforge: &CurseForgeApi,
+) -> LauncherResult<ModpackExport> {
+    let files = collect_files(game_dir, &options.include)?;
+    let (index_name, index, referenced) = match options.format {
+        ExportFormat::Mrpack => {
+            let resolved = modrinth_files(&files, modrinth).await?;
+            let mut dependencies =
+                HashMap::from([("minecraft".to_string(), game_version.to_string())]);
+            if let Some(loader) = loader {
+                dependencies.insert(
+                    mrpack_loader_key(&loader.loader).to_string(),
+                    loader.version.clone(),
+                );
+            }
+            let mut referenced: Vec<MrpackFile> = resolved.into_values().collect();
+            referenced.sort_by(|a, b| a.path.cmp(&b.path));
+            let index = MrpackIndex {
+                format_version: 1,
+                game: "minecraft".to_string(),
+                version_id: options.version.clone(),
+                name: options.name.clone(),
+                summary: options.summary.clone(),
+                files: referenced,
+                dependencies,
+            };
+            let paths: Vec<String> = index.files.iter().map(|f| f.path.clone()).collect();
+            (
+                "modrinth.index.json",
+                serde_json::to_vec_pretty(&index)?,
+                paths,
+            )
+        }
+        ExportFormat::CurseForge => {
+            let resolved = curseforge_files(&files, curseforge).await?;
+            let mut paths: Vec<String> = resolved.keys().cloned().collect();
+            paths.sort();
+            let manifest = CurseForgeManifest {
+                minecraft: ManifestMinecraft {
+                    version: game_version.to_string(),
+                    mod_loaders: loader
+                        .map(|loader| ManifestLoader {
+                            id: format!("{}-{}", loader.loader, loader.version),
+                            primary: true,
+                        })
+                        .into_iter()
+                        .collect(),
+                },
+                manifest_type: default_manifest_type(),
+                manifest_version: 1,
+                name: options.name.clone(),
+                version: options.version.clone(),
+                author: options.author.clone().unwrap_or_default(),
+                files: paths.iter().map(|path| resolved[path].clone()).collect(),
+                overrides: "overrides".to_string(),
+            };
+            (
+                "manifest.json",
+                serde_json::to_vec_pretty(&manifest)?,
+                paths,
+            )
+        }
+    };
+
+    let overrides: Vec<&PackFile> = files
+        .iter()
+        .filter(|file| !referenced.contains(&file.relative))
+        .collect();
+    write_pack(output, index_name, &index, &overrides)?;
+    log::info!(
+        "导出整合包 {}: 引用 {} 个文件，打包 {} 个文件",
+        output.display(),
+        referenced.len(),
+        overrides.len()
+    );
+    Ok(ModpackExport {
+        path: output.to_path_buf(),
+        referenced: referenced.len(),
+        bundled: overrides.iter().map(|file| file.relative.clone()).collect(),
+    })
+}
+
+#[tauri::command]
+pub fn list_export_entries(instance_id: String) -> LauncherResult<Vec<ExportEntry>> {
+    let instance = InstanceStore::new().get(&instance_id)?;
+    Ok(list_entries(&instance.game_dir()))
+}
+
+#[tauri::command]
+pub async fn export_modpack(
+    instance_id: String,
+    options: ExportOptions,
+    output: PathBuf,
+) -> LauncherResult<ModpackExport> {
+    let instance = InstanceStore::new().get(&instance_id)?;
+    let paths = MinecraftPaths::new();
+    let game_version = ContentFilter::for_instance(&instance)?
+        .game_version
+        .unwrap_or_else(|| instance.version_id.clone());
+    let loader = resolve_version(&paths, &instance.version_id)
+        .ok()
+        .and_then(|json| loader_spec(&json, &game_version));
+    export_dir(
+        &instance.game_dir(),
+        &game_version,
+        loader.as_ref(),
+        &options,
+        &output,
+        &ModrinthApi::new(),
+        &CurseForgeApi::new(),
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::module::loader::fake_server;
+
+    #[tokio::test]
+    async fn test_export_modpack() {
+        let dir = std::env::temp_dir().join(format!("rtl-export-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let game_dir = dir.join("game");
+        for (path, content) in [
+            ("mods/sodium.jar", "sodium"),
+            ("mods/private.jar", "private"),
+            ("config/sodium.json", "{}"),
+            ("saves/World/level.dat", "level"),
+            (INSTANCE_FILE, "{}"),
+        ] {
+            let path = game_dir.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+        let entries = list_entries(&game_dir);
+        assert!(entries.iter().all(|e| e.name != INSTANCE_FILE));
+        assert!(entries.iter().any(|e| e.name == "saves" && !e.selected));
+        let include: Vec<String> = entries
+            .iter()
+            .filter(|e| e.selected)
+            .map(|e| e.name.clone())
+            .collect();
+
+        let sha1 = sha1_file(&game_dir.join("mods/sodium.jar")).unwrap();
+        let (base, server) = fake_server::serve(HashMap::from([
+            (
+                "/version_files".to_string(),
+                format!(
+                    r#"{{"{sha1}":{{"id":"v1","project_id":"AANobbMI","files":[
+                        {{"filename":"sodium.jar","url":"https://cdn.modrinth.com/sodium.jar","primary":true,
+                          "hashes":{{"sha1":"{sha1}","sha512":"abc"}}}}]}}}}"#
+                )
+                .into_bytes(),
+            ),
+            (
+                "/v1/fingerprints/432".to_string(),
+                format!(
+                    r#"{{"data":{{"exactMatches":[{{"file":{{"id":5101,"modId":394468,"displayName":"Sodium",
+                        "fileName":"sodium.jar","fileFingerprint":{}}}}}]}}}}"#,
+                    fingerprint(b"sodium")
+                )
+                .into_bytes(),
+            ),
+        ]))
+        .await;
+        let modrinth = ModrinthApi::with_base(base.clone());
+        let curseforge = CurseForgeApi::with_base(base, vec!["key".to_string()]);
+        let loader = loader_spec(
+            &serde_json::json!({ "libraries": [{ "name": "net.fabricmc:fabric-loader:0.16.5" }] }),
+            "1.21.1",
+        );
+        let mut options = ExportOptions {
+            format: ExportFormat::Mrpack,
+            name: "Pack".to_string(),
+            version: "1.0.0".to_string(),
+            author: None,
+            summary: None,
+            include,
+        };
+
+        let output = dir.join("pack.mrpack");
+        let export = export_dir(
+            &game_dir,
+            "1.21.1",
+            loader.as_ref(),
+            &options,
+            &output,
+            &modrinth,
+            &curseforge,
+        )
+        .await
+        .unwrap();
+        assert_eq!(export.referenced, 1);
+        assert_eq!(export.bundled, ["config/sodium.json", "mods/private.jar"]);
+        let mut archive = zip::ZipArchive::new(std::fs::File::open(&output).unwrap()).unwrap();
+        let index: MrpackIndex =
+            serde_json::from_reader(archive.by_name("modrinth.index.json").unwrap()).unwrap();
+        assert_eq!(index.files[0].path, "mods/sodium.jar");
+        assert_eq!(index.dependencies["fabric-loader"], "0.16.5");
+        assert!(archive.by_name("overrides/mods/private.jar").is_ok());
+        assert!(archive.by_name("overrides/saves/World/level.dat").is_err());
+
+        options.format = ExportFormat::CurseForge;
+        let output = dir.join("pack.zip");
+        let export = export_dir(
+            &game_dir,
+            "1.21.1",
+            loader.as_ref(),
+            &options,
+            &output,
+            &modrinth,
+            &curseforge,
+        )
+        .await
+        .unwrap();
+        assert_eq!(export.referenced, 1);
+        let mut archive = zip::ZipArchive::new(std::fs::File::open(&output).unwrap()).unwrap();
+        let manifest: CurseForgeManifest =
+            serde_json::from_reader(archive.by_name("manifest.json").unwrap()).unwrap();
+        assert_eq!(manifest.files[0].file_id, 5101);
+        assert_eq!(manifest.minecraft.mod_loaders[0].id, "fabric-0.16.5");
+
+        server.abort();
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,161 @@
+/*
+RTLauncher, a third-party Minecraft launcher built with the newest
+technology and provides innovative funtionalities
+Copyright (C) 2025 lutouna
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+// ***
+// 整合包导入与导出
+// ***
+
+pub mod curseforge;
+pub mod export;
+pub mod mrpack;
+pub mod multimc;
+
+use crate::module::content::index::INDEX_FILE;
+use crate::module::instance::ins_main::{Instance, INSTANCE_FILE};
+use crate::utils::error::{LauncherError, LauncherResult};
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Seek};
+use std::path::{Component, Path, PathBuf};
+
+// 导入结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModpackImport {
+    pub instance: Instance,
+    pub downloaded: usize,
+    // 仅服务端或未选择的可选文件
+    pub skipped: Vec<String>,
+    // 下载失败或校验不通过的文件
+    pub failed: Vec<String>,
+    // 需要手动下载的文件
+    #[serde(default)]
+    pub manual: Vec<ManualDownload>,
+}
+
+// 作者关闭第三方下载的文件，需要用户在网页上下载后放入 target 目录
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManualDownload {
+    pub project_id: String,
+    pub file_id: String,
+    pub name: String,
+    pub file_name: String,
+    pub website_url: Option<String>,
+    // 相对游戏目录的文件夹
+    pub target: String,
+}
+
+// 整合包文件的安装结果，不包含实例信息
+#[derive(Debug, Clone, Default)]
+pub struct PackInstall {
+    pub downloaded: usize,
+    pub skipped: Vec<String>,
+    pub failed: Vec<String>,
+    pub manual: Vec<ManualDownload>,
+}
+
+impl PackInstall {
+    pub fn into_import(self, instance: Instance) -> ModpackImport {
+        ModpackImport {
+            instance,
+            downloaded: self.downloaded,
+            skipped: self.skipped,
+            failed: self.failed,
+            manual: self.manual,
+        }
+    }
+}
+
+pub fn open_pack(path: &Path) -> LauncherResult<zip::ZipArchive<std::fs::File>> {
+    let file = std::fs::File::open(path).map_err(|e| LauncherError::io(path, e))?;
+    Ok(zip::ZipArchive::new(file)?)
+}
+
+// 整合包中的相对路径，不允许绝对路径和 ..
+pub fn safe_relative_path(path: &str) -> Option<PathBuf> {
+    let path = Path::new(path);
+    let mut clean = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => clean.push(part),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    (!clean.as_os_str().is_empty()).then_some(clean)
+}
+
+// 启动器自己的实例配置和资源索引，整合包不能覆盖
+pub fn is_launcher_file(relative: &Path) -> bool {
+    [INSTANCE_FILE, INDEX_FILE]
+        .map(Path::new)
+        .contains(&relative)
+}
+
+// 把压缩包中 prefix 目录下的文件解压到游戏目录，返回解压的文件数
+pub fn extract_overrides<R: Read + Seek>(
+    archive: &mut zip::ZipArchive<R>,
+    prefix: &str,
+    game_dir: &Path,
+) -> LauncherResult<usize> {
+    let prefix = format!("{}/", prefix.trim_end_matches('/'));
+    let mut count = 0;
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i)?;
+        let Some(relative) = entry
+            .name()
+            .strip_prefix(&prefix)
+            .and_then(safe_relative_path)
+        else {
+            continue;
+        };
+        if is_launcher_file(&relative) {
+            log::warn!("跳过整合包中的启动器文件: {}", entry.name());
+            continue;
+        }
+        let target = game_dir.join(relative);
+        if entry.is_dir() {
+            std::fs::create_dir_all(&target).map_err(|e| LauncherError::io(&target, e))?;
+            continue;
+        }
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| LauncherError::io(parent, e))?;
+        }
+        let mut file = std::fs::File::create(&target).map_err(|e| LauncherError::io(&target, e))?;
+        std::io::copy(&mut entry, &mut file).map_err(|e| LauncherError::io(&target, e))?;
+        count += 1;
+    }
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_safe_relative_path() {
+        assert_eq!(
+            safe_relative_path("mods/./sodium.jar"),
+            Some(PathBuf::from("mods/sodium.jar"))
+        );
+        assert_eq!(safe_relative_path("../evil.jar"), None);
+        assert_eq!(safe_relative_path("/etc/passwd"), None);
+        assert_eq!(safe_relative_path(""), None);
+        assert!(is_launcher_file(Path::new(INSTANCE_FILE)));
+        assert!(!is_launcher_file(Path::new("config/instance.json")));
+    }
+}
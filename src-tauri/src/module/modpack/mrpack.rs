@@ -0,0 +1,457 @@
+/*
+RTLauncher, a third-party Minecraft launcher built with the newest
+technology and provides innovative funtionalities
+Copyright (C) 2025 lutouna
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+// ***
+// Modrinth 整合包 (.mrpack) 导入
+// ***
+
+use super::{
+    extract_overrides, is_launcher_file, open_pack, safe_relative_path, ModpackImport, PackInstall,
+};
+use crate::module::content::download::range_download;
+use crate::module::download::paths::MinecraftPaths;
+use crate::module::download::verify::{sha1_file, sha512_file};
+use crate::module::instance::ins_main::InstanceStore;
+use crate::module::loader::{install_game, LoaderSpec};
+use crate::utils::error::{LauncherError, LauncherResult};
+use futures::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+const INDEX_FILE: &str = "modrinth.index.json";
+// 同时下载的文件数
+const CONCURRENCY: usize = 8;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MrpackIndex {
+    pub format_version: u32,
+    pub game: String,
+    pub version_id: String,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    #[serde(default)]
+    pub files: Vec<MrpackFile>,
+    pub dependencies: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MrpackFile {
+    pub path: String,
+    pub hashes: HashMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env: Option<MrpackEnv>,
+    pub downloads: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_size: Option<u64>,
+}
+
+// required / optional / unsupported
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MrpackEnv {
+    pub client: String,
+    pub server: String,
+}
+
+impl MrpackFile {
+    // 客户端是否需要该文件
+    fn wanted(&self, include_optional: bool) -> bool {
+        match self.env.as_ref().map(|env| env.client.as_str()) {
+            Some("unsupported") => false,
+            Some("optional") => include_optional,
+            _ => true,
+        }
+    }
+}
+
+impl MrpackIndex {
+    pub fn game_version(&self) -> LauncherResult<&str> {
+        self.dependencies
+            .get("minecraft")
+            .map(|v| v.as_str())
+            .ok_or_else(|| LauncherError::Parse("整合包没有声明 Minecraft 版本".to_string()))
+    }
+
+    // dependencies 中的加载器
+    pub fn loader(&self) -> Option<LoaderSpec> {
+        [
+            ("fabric-loader", "fabric"),
+            ("quilt-loader", "quilt"),
+            ("neoforge", "neoforge"),
+            ("forge", "forge"),
+        ]
+        .into_iter()
+        .find_map(|(key, loader)| {
+            self.dependencies.get(key).map(|version| LoaderSpec {
+                loader: loader.to_string(),
+                version: version.clone(),
+            })
+        })
+    }
+}
+
+pub fn read_index(pack: &Path) -> LauncherResult<MrpackIndex> {
+    let mut archive = open_pack(pack)?;
+    let entry = archive
+        .by_name(INDEX_FILE)
+        .map_err(|_| LauncherError::Parse(format!("不是 Modrinth 整合包: {}", pack.display())))?;
+    let index: MrpackIndex = serde_json::from_reader(entry)?;
+    if index.game != "minecraft" {
+        return Err(LauncherError::Unsupported(format!(
+            "不支持的游戏: {}",
+            index.game
+        )));
+    }
+    Ok(index)
+}
+
+// 整合包格式允许的下载域名
+pub const ALLOWED_HOSTS: [&str; 4] = [
+    "cdn.modrinth.com",
+    "github.com",
+    "raw.githubusercontent.com",
+    "gitlab.com",
+];
+
+// 下载地址是否在允许的域名内
+fn allowed_url(url: &str, allowed_hosts: &[String]) -> bool {
+    url::Url::parse(url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_lowercase))
+        .is_some_and(|host| allowed_hosts.contains(&host))
+}
+
+// 校验文件声明的 sha1 和 sha512
+fn check_hashes(path: &Path, hashes: &HashMap<String, String>) -> LauncherResult<()> {
+    for algorithm in ["sha1", "sha512"] {
+        let Some(expected) = hashes.get(algorithm) else {
+            continue;
+        };
+        let actual = match algorithm {
+            "sha1" => sha1_file(path),
+            _ => sha512_file(path),
+        }
+        .map_err(|e| LauncherError::io(path, e))?;
+        if !actual.eq_ignore_ascii_case(expected) {
+            return Err(LauncherError::HashMismatch {
+                path: path.display().to_string(),
+                expected: expected.clone(),
+                actual,
+            });
+        }
+    }
+    Ok(())
+}
+
+// 已存在且哈希一致的文件不重新下载
+fn is_installed(path: &Path, hashes: &HashMap<String, String>) -> bool {
+    path.exists() && check_hashes(path, hashes).is_ok()
+}
+
+// 单个文件的下载结果
+enum FileResult {
+    Downloaded,
+    Present,
+}
+
+// 依次尝试每个允许的下载地址，下载后校验全部哈希
+async fn download_file(
+    game_dir: &Path,
+    file: &MrpackFile,
+    allowed_hosts: &[String],
+) -> LauncherResult<FileResult> {
+    let relative = safe_relative_path(&file.path)
+        .filter(|relative| !is_launcher_file(relative))
+        .ok_or_else(|| LauncherError::InvalidArgument(format!("无效的文件路径: {}", file.path)))?;
+    let target = game_dir.join(relative);
+    if is_installed(&target, &file.hashes) {
+        return Ok(FileResult::Present);
+    }
+    let sha1 = file.hashes.get("sha1").map(|s| s.as_str());
+    let mut last_error = LauncherError::Other(format!("{} 没有允许的下载地址", file.path));
+    for url in &file.downloads {
+        if !allowed_url(url, allowed_hosts) {
+            log::warn!("整合包下载地址不在允许的域名内: {}", url);
+            continue;
+        }
+        let result = match range_download(url, &target, sha1).await {
+            Ok(()) => check_hashes(&target, &file.hashes),
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(()) => return Ok(FileResult::Downloaded),
+            Err(e) => {
+                log::warn!("整合包文件下载失败 {}: {}", url, e);
+                let _ = std::fs::remove_file(&target);
+                last_error = e;
+            }
+        }
+    }
+    Err(last_error)
+}
+
+// 校验所有需要的文件，返回缺失或哈希不一致的文件
+pub fn verify_files(game_dir: &Path, index: &MrpackIndex, include_optional: bool) -> Vec<String> {
+    index
+        .files
+        .iter()
+        .filter(|file| file.wanted(include_optional))
+        .filter(|file| {
+            let Some(relative) = safe_relative_path(&file.path) else {
+                return true;
+            };
+            !is_installed(&game_dir.join(relative), &file.hashes)
+        })
+        .map(|file| file.path.clone())
+        .collect()
+}
+
+// 下载文件、解压 overrides 并校验，只从 allowed_hosts 中的域名下载
+pub async fn install_pack(
+    pack: &Path,
+    index: &MrpackIndex,
+    game_dir: &Path,
+    include_optional: bool,
+    allowed_hosts: &[&str],
+) -> LauncherResult<PackInstall> {
+    let (wanted, skipped): (Vec<MrpackFile>, Vec<MrpackFile>) = index
+        .files
+        .iter()
+        .cloned()
+        .partition(|file| file.wanted(include_optional));
+    let allowed_hosts: Arc<Vec<String>> =
+        Arc::new(allowed_hosts.iter().map(|h| h.to_string()).collect());
+    let results: Vec<_> = stream::iter(wanted)
+        .map(|file| {
+            let game_dir = game_dir.to_path_buf();
+            let allowed_hosts = allowed_hosts.clone();
+            async move {
+                let result = download_file(&game_dir, &file, &allowed_hosts).await;
+                (file.path, result)
+            }
+        })
+        .buffer_unordered(CONCURRENCY)
+        .collect()
+        .await;
+    let mut install = PackInstall {
+        skipped: skipped.into_iter().map(|file| file.path).collect(),
+        ..Default::default()
+    };
+    for (path, result) in results {
+        match result {
+            Ok(FileResult::Downloaded) => install.downloaded += 1,
+            Ok(FileResult::Present) => {}
+            Err(e) => {
+                log::error!("整合包文件安装失败 {}: {}", path, e);
+                install.failed.push(path);
+            }
+        }
+    }
+
+    // client-overrides 覆盖 overrides 中的同名文件
+    let mut archive = open_pack(pack)?;
+    for prefix in ["overrides", "client-overrides"] {
+        extract_overrides(&mut archive, prefix, game_dir)?;
+    }
+    // overrides 可能覆盖已下载的文件，最后再校验一次
+    for path in verify_files(game_dir, index, include_optional) {
+        if !install.failed.contains(&path) {
+            install.failed.push(path);
+        }
+    }
+    install.failed.sort();
+    Ok(install)
+}
+
+// 导入 .mrpack：安装游戏和加载器，创建实例后安装整合包文件
+pub async fn import(
+    pack: &Path,
+    name: Option<String>,
+    include_optional: bool,
+) -> LauncherResult<ModpackImport> {
+    let index = read_index(pack)?;
+    let game_version = index.game_version()?.to_string();
+    let version_id = install_game(
+        &MinecraftPaths::new(),
+        &game_version,
+        index.loader().as_ref(),
+    )
+    .await?;
+    let name = name.unwrap_or_else(|| index.name.clone());
+    let instance = InstanceStore::new().create(&name, &version_id)?;
+    let install = install_pack(
+        pack,
+        &index,
+        &instance.game_dir(),
+        include_optional,
+        &ALLOWED_HOSTS,
+    )
+    .await?;
+    log::info!(
+        "导入整合包 {} 完成，下载 {} 个文件，{} 个失败",
+        index.name,
+        install.downloaded,
+        install.failed.len()
+    );
+    Ok(install.into_import(instance))
+}
+
+#[tauri::command]
+pub async fn import_mrpack(
+    path: PathBuf,
+    name: Option<String>,
+    include_optional: Option<bool>,
+) -> LauncherResult<ModpackImport> {
+    import(&path, name, include_optional.unwrap_or(true)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::module::content::scanner::tests::write_jar;
+    use crate::module::loader::fake_server;
+    use sha1::Digest;
+
+    fn sha1_hex(bytes: &[u8]) -> String {
+        format!("{:x}", sha1::Sha1::digest(bytes))
+    }
+
+    fn sha512_hex(bytes: &[u8]) -> String {
+        format!("{:x}", sha2::Sha512::digest(bytes))
+    }
+
+    #[tokio::test]
+    async fn test_install_mrpack_fixture() {
+        let dir = std::env::temp_dir().join(format!("rtl-mrpack-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let (base, server) = fake_server::serve(HashMap::from([
+            ("/sodium.jar".to_string(), b"sodium".to_vec()),
+            ("/shader.zip".to_string(), b"shader".to_vec()),
+        ]))
+        .await;
+
+        let index = serde_json::json!({
+            "formatVersion": 1,
+            "game": "minecraft",
+            "versionId": "1.0.0",
+            "name": "Fixture Pack",
+            "files": [
+                {
+                    "path": "mods/sodium.jar",
+                    "hashes": { "sha1": sha1_hex(b"sodium"), "sha512": sha512_hex(b"sodium") },
+                    "env": { "client": "required", "server": "required" },
+                    "downloads": [format!("{}/missing.jar", base), format!("{}/sodium.jar", base)],
+                    "fileSize": 6
+                },
+                {
+                    "path": "shaderpacks/shader.zip",
+                    "hashes": { "sha1": sha1_hex(b"shader") },
+                    "env": { "client": "optional", "server": "unsupported" },
+                    "downloads": [format!("{}/shader.zip", base)]
+                },
+                {
+                    "path": "mods/server-only.jar",
+                    "hashes": { "sha1": "00" },
+                    "env": { "client": "unsupported", "server": "required" },
+                    "downloads": []
+                },
+                {
+                    "path": "mods/broken.jar",
+                    "hashes": { "sha1": "00" },
+                    "downloads": [format!("{}/sodium.jar", base)]
+                },
+                {
+                    "path": "mods/bad-sha512.jar",
+                    "hashes": { "sha1": sha1_hex(b"sodium"), "sha512": "00" },
+                    "downloads": [format!("{}/sodium.jar", base)]
+                },
+                {
+                    "path": "mods/untrusted.jar",
+                    "hashes": { "sha1": sha1_hex(b"sodium") },
+                    "downloads": [base.replace("127.0.0.1", "localhost") + "/sodium.jar"]
+                }
+            ],
+            "dependencies": { "minecraft": "1.21.1", "fabric-loader": "0.16.9" }
+        });
+        let pack = dir.join("fixture.mrpack");
+        write_jar(
+            &pack,
+            &[
+                (INDEX_FILE, index.to_string().as_bytes()),
+                ("overrides/config/sodium.json", b"{}"),
+                ("overrides/options.txt", b"lang:en_us"),
+                ("client-overrides/options.txt", b"lang:zh_cn"),
+                ("overrides/instance.json", b"{\"id\":\"evil\"}"),
+                ("overrides/../escape.txt", b"no"),
+            ],
+        );
+
+        let index = read_index(&pack).unwrap();
+        assert_eq!(index.game_version().unwrap(), "1.21.1");
+        assert_eq!(
+            index.loader(),
+            Some(LoaderSpec {
+                loader: "fabric".to_string(),
+                version: "0.16.9".to_string()
+            })
+        );
+
+        // 已存在的文件不计入下载数
+        let game_dir = dir.join("game");
+        std::fs::create_dir_all(game_dir.join("shaderpacks")).unwrap();
+        std::fs::write(game_dir.join("shaderpacks/shader.zip"), b"shader").unwrap();
+        std::fs::write(game_dir.join("instance.json"), b"{}").unwrap();
+        let install = install_pack(&pack, &index, &game_dir, true, &["127.0.0.1"])
+            .await
+            .unwrap();
+        assert_eq!(install.downloaded, 1);
+        assert_eq!(install.skipped, ["mods/server-only.jar"]);
+        assert_eq!(
+            install.failed,
+            [
+                "mods/bad-sha512.jar",
+                "mods/broken.jar",
+                "mods/untrusted.jar"
+            ]
+        );
+        assert!(!game_dir.join("mods/bad-sha512.jar").exists());
+        assert_eq!(
+            std::fs::read(game_dir.join("instance.json")).unwrap(),
+            b"{}"
+        );
+        assert_eq!(
+            std::fs::read(game_dir.join("mods/sodium.jar")).unwrap(),
+            b"sodium"
+        );
+        assert!(game_dir.join("config/sodium.json").exists());
+        assert_eq!(
+            std::fs::read_to_string(game_dir.join("options.txt")).unwrap(),
+            "lang:zh_cn"
+        );
+        assert!(!dir.join("escape.txt").exists());
+
+        server.abort();
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
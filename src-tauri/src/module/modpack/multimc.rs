@@ -0,0 +1,339 @@
+/*
+RTLauncher, a third-party Minecraft launcher built with the newest
+technology and provides innovative funtionalities
+Copyright (C) 2025 lutouna
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+// ***
+// MultiMC / Prism Launcher 实例导入
+// ***
+
+use super::is_launcher_file;
+use crate::module::download::paths::MinecraftPaths;
+use crate::module::instance::ins_main::{Instance, InstanceStore};
+use crate::module::loader::{install_game, LoaderSpec};
+use crate::utils::error::{LauncherError, LauncherResult};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+const INSTANCE_CFG: &str = "instance.cfg";
+const PACK_FILE: &str = "mmc-pack.json";
+const MINECRAFT_UID: &str = "net.minecraft";
+
+// 组件 uid 对应的加载器
+const LOADER_UIDS: [(&str, &str); 4] = [
+    ("net.fabricmc.fabric-loader", "fabric"),
+    ("org.quiltmc.quilt-loader", "quilt"),
+    ("net.minecraftforge", "forge"),
+    ("net.neoforged", "neoforge"),
+];
+
+#[derive(Debug, Deserialize)]
+struct MmcPack {
+    #[serde(default)]
+    components: Vec<MmcComponent>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MmcComponent {
+    uid: String,
+    version: Option<String>,
+    // 旧版本只记录缓存的版本号
+    cached_version: Option<String>,
+}
+
+impl MmcComponent {
+    fn version(&self) -> Option<&str> {
+        self.version
+            .as_deref()
+            .or(self.cached_version.as_deref())
+            .filter(|v| !v.is_empty())
+    }
+}
+
+// 从 MultiMC 实例读取的信息
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MultiMcInstance {
+    pub name: String,
+    pub game_version: String,
+    pub loader: Option<LoaderSpec>,
+    pub min_memory_mb: Option<u32>,
+    pub max_memory_mb: Option<u32>,
+    pub jvm_args: Vec<String>,
+    // .minecraft 或 minecraft 目录
+    pub game_dir: Option<PathBuf>,
+}
+
+// instance.cfg 为 key=value 格式，新版 Prism 带有 [General] 分组
+fn parse_cfg(content: &str) -> HashMap<String, String> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with(['[', '#', ';']))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| {
+            let value = value.trim();
+            let value = value
+                .strip_prefix('"')
+                .and_then(|v| v.strip_suffix('"'))
+                .unwrap_or(value);
+            (key.trim().to_string(), value.to_string())
+        })
+        .collect()
+}
+
+// Override 开关为 false 时不使用实例自己的设置，旧版 MultiMC 没有开关
+fn overridden(cfg: &HashMap<String, String>, key: &str) -> bool {
+    cfg.get(key).is_none_or(|value| value == "true")
+}
+
+pub fn read_instance(dir: &Path) -> LauncherResult<MultiMcInstance> {
+    let cfg_path = dir.join(INSTANCE_CFG);
+    let content =
+        std::fs::read_to_string(&cfg_path).map_err(|e| LauncherError::io(&cfg_path, e))?;
+    let cfg = parse_cfg(&content);
+
+    let pack_path = dir.join(PACK_FILE);
+    let components = if pack_path.exists() {
+        let content =
+            std::fs::read_to_string(&pack_path).map_err(|e| LauncherError::io(&pack_path, e))?;
+        serde_json::from_str::<MmcPack>(&content)?.components
+    } else {
+        Vec::new()
+    };
+
+    // 没有 mmc-pack.json 的旧实例使用 IntendedVersion
+    let game_version = components
+        .iter()
+        .find(|c| c.uid == MINECRAFT_UID)
+        .and_then(MmcComponent::version)
+        .or(cfg.get("IntendedVersion").map(String::as_str))
+        .ok_or_else(|| LauncherError::Parse(format!("实例没有 Minecraft 版本: {}", dir.display())))?
+        .to_string();
+
+    let loader = components.iter().find_map(|component| {
+        let (_, loader) = LOADER_UIDS.iter().find(|(uid, _)| *uid == component.uid)?;
+        Some(LoaderSpec {
+            loader: loader.to_string(),
+            version: component.version()?.to_string(),
+        })
+    });
+    for component in &components {
+        let known = component.uid == MINECRAFT_UID
+            || component.uid == "org.lwjgl"
+            || component.uid == "org.lwjgl3"
+            || component.uid == "net.fabricmc.intermediary"
+            || component.uid == "org.quiltmc.hashed"
+            || LOADER_UIDS.iter().any(|(uid, _)| *uid == component.uid);
+        if !known {
+            log::warn!("不支持的 MultiMC 组件: {}", component.uid);
+        }
+    }
+
+    let memory = |key: &str| {
+        overridden(&cfg, "OverrideMemory")
+            .then(|| cfg.get(key)?.parse().ok())
+            .flatten()
+    };
+    let jvm_args = if overridden(&cfg, "OverrideJavaArgs") {
+        cfg.get("JvmArgs")
+            .map(|args| split_args(args))
+            .unwrap_or_default()
+    } else {
+        Vec::new()
+    };
+
+    let name = cfg
+        .get("name")
+        .filter(|name| !name.trim().is_empty())
+        .cloned()
+        .unwrap_or_else(|| {
+            dir.file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        });
+
+    Ok(MultiMcInstance {
+        name,
+        game_version,
+        loader,
+        min_memory_mb: memory("MinMemAlloc"),
+        max_memory_mb: memory("MaxMemAlloc"),
+        jvm_args,
+        game_dir: [".minecraft", "minecraft"]
+            .into_iter()
+            .map(|name| dir.join(name))
+            .find(|path| path.is_dir()),
+    })
+}
+
+// 复制游戏目录，不覆盖启动器自己的文件，返回复制的文件数
+pub fn copy_game_dir(from: &Path, to: &Path) -> LauncherResult<usize> {
+    let mut count = 0;
+    for entry in walkdir::WalkDir::new(from).min_depth(1) {
+        let entry = entry.map_err(|e| LauncherError::Other(e.to_string()))?;
+        let Ok(relative) = entry.path().strip_prefix(from) else {
+            continue;
+        };
+        if is_launcher_file(relative) {
+            continue;
+        }
+        let target = to.join(relative);
+        if entry.file_type().is_dir() {
+            std::fs::create_dir_all(&target).map_err(|e| LauncherError::io(&target, e))?;
+        } else if entry.file_type().is_file() {
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent).map_err(|e| LauncherError::io(parent, e))?;
+            }
+            std::fs::copy(entry.path(), &target).map_err(|e| LauncherError::io(&target, e))?;
+            count += 1;
+        }
+    }
+    Ok(count)
+}
+
+// 导入 MultiMC / Prism 实例：安装游戏和加载器，复制游戏目录并保留内存与JVM参数
+pub async fn import(dir: &Path, name: Option<String>) -> LauncherResult<Instance> {
+    let source = read_instance(dir)?;
+    let version_id = install_game(
+        &MinecraftPaths::new(),
+        &source.game_version,
+        source.loader.as_ref(),
+    )
+    .await?;
+    let store = InstanceStore::new();
+    let mut instance = store.create(&name.unwrap_or(source.name), &version_id)?;
+    instance.min_memory_mb = source.min_memory_mb;
+    instance.max_memory_mb = source.max_memory_mb;
+    instance.jvm_args = source.jvm_args;
+    store.save(&instance)?;
+
+    if let Some(game_dir) = &source.game_dir {
+        let count = copy_game_dir(game_dir, &instance.game_dir())?;
+        log::info!("从 {} 复制了 {} 个文件", game_dir.display(), count);
+    }
+    Ok(instance)
+}
+
+#[tauri::command]
+pub async fn import_multimc_instance(
+    path: PathBuf,
+    name: Option<String>,
+) -> LauncherResult<Instance> {
+    import(&path, name).await
+}
+
+// 按空白拆分 JvmArgs，引号内的空白不拆分，反斜杠转义下一个字符（单引号内除外）
+fn split_args(line: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_arg = false;
+    let mut quote: Option<char> = None;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some('\''), c) => current.push(c),
+            (_, '\\') => {
+                in_arg = true;
+                if let Some(next) = chars.next() {
+                    current.push(next);
+                }
+            }
+            (Some(_), c) => current.push(c),
+            (None, '"' | '\'') => {
+                in_arg = true;
+                quote = Some(c);
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_arg {
+                    args.push(std::mem::take(&mut current));
+                    in_arg = false;
+                }
+            }
+            (None, c) => {
+                in_arg = true;
+                current.push(c);
+            }
+        }
+    }
+    if in_arg {
+        args.push(current);
+    }
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_prism_instance() {
+        let dir = std::env::temp_dir().join(format!("rtl-multimc-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let source = dir.join("Fabulously Optimized");
+        std::fs::create_dir_all(source.join(".minecraft/mods")).unwrap();
+        std::fs::write(source.join(".minecraft/mods/sodium.jar"), b"jar").unwrap();
+        std::fs::write(source.join(".minecraft/options.txt"), b"fov:0.5").unwrap();
+        std::fs::write(
+            source.join(INSTANCE_CFG),
+            "[General]\nConfigVersion=1.2\nname=FO 1.21\nOverrideMemory=true\nMinMemAlloc=1024\nMaxMemAlloc=6144\nOverrideJavaArgs=true\nJvmArgs=-XX:+UseZGC -Dfoo=\"a b\"\n",
+        )
+        .unwrap();
+        std::fs::write(
+            source.join(PACK_FILE),
+            r#"{"components":[
+                {"uid":"org.lwjgl3","version":"3.3.3"},
+                {"uid":"net.minecraft","version":"1.21.1"},
+                {"uid":"net.fabricmc.intermediary","version":"1.21.1"},
+                {"uid":"net.fabricmc.fabric-loader","version":"0.16.5"}
+            ],"formatVersion":1}"#,
+        )
+        .unwrap();
+
+        let instance = read_instance(&source).unwrap();
+        assert_eq!(instance.name, "FO 1.21");
+        assert_eq!(instance.game_version, "1.21.1");
+        assert_eq!(
+            instance.loader,
+            Some(LoaderSpec {
+                loader: "fabric".to_string(),
+                version: "0.16.5".to_string()
+            })
+        );
+        assert_eq!(instance.min_memory_mb, Some(1024));
+        assert_eq!(instance.max_memory_mb, Some(6144));
+        assert_eq!(instance.jvm_args, ["-XX:+UseZGC", "-Dfoo=a b"]);
+
+        let target = dir.join("target");
+        let count = copy_game_dir(instance.game_dir.as_ref().unwrap(), &target).unwrap();
+        assert_eq!(count, 2);
+        assert!(target.join("mods/sodium.jar").exists());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_split_args() {
+        assert_eq!(
+            split_args(r#"-Xss2m  -Dfoo="a b" '-Dbar=c d' -Dq=\"x\" """#),
+            ["-Xss2m", "-Dfoo=a b", "-Dbar=c d", "-Dq=\"x\"", ""]
+        );
+        assert!(split_args("   ").is_empty());
+    }
+}
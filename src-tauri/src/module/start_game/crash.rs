@@ -0,0 +1,411 @@
+/*
+RTLauncher, a third-party Minecraft launcher built with the newest
+technology and provides innovative funtionalities
+Copyright (C) 2025 lutouna
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+// ***
+// 崩溃检测与崩溃报告分析
+// ***
+
+use crate::utils::error::{LauncherError, LauncherResult};
+use serde::Serialize;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+// latest.log 保留的行数
+const LOG_TAIL_LINES: usize = 200;
+
+// 崩溃原因分类
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CrashCause {
+    WrongJavaVersion,
+    MissingNatives,
+    OutOfMemory,
+    DuplicateMods,
+    MixinFailure,
+    MissingDependencies,
+    GpuDriver,
+}
+
+// 单条诊断结果
+#[derive(Debug, Clone, Serialize)]
+pub struct CrashFinding {
+    pub cause: CrashCause,
+    pub title: String,
+    pub suggestion: String,
+    pub evidence: String, // 命中的日志行
+}
+
+// 收集到的文件
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CrashFileKind {
+    CrashReport,
+    JvmErrorLog,
+    LatestLog,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CrashFile {
+    pub kind: CrashFileKind,
+    pub path: PathBuf,
+}
+
+// 返回给前端的崩溃诊断
+#[derive(Debug, Clone, Serialize)]
+pub struct CrashReport {
+    pub exit_code: Option<i32>,
+    pub files: Vec<CrashFile>,
+    pub log_tail: Vec<String>,
+    pub findings: Vec<CrashFinding>,
+}
+
+impl CrashReport {
+    // 收集崩溃文件并分析，game_output 为进程捕获的输出
+    pub fn collect(
+        game_dir: &Path,
+        started: SystemTime,
+        exit_code: Option<i32>,
+        game_output: &[String],
+    ) -> Self {
+        let mut files = Vec::new();
+        let mut texts = Vec::new();
+
+        // crash-reports/*.txt，只取本次启动之后生成的
+        for path in list_files(&game_dir.join("crash-reports"), |name| {
+            name.ends_with(".txt")
+        }) {
+            if is_newer_than(&path, started) {
+                if let Ok(content) = std::fs::read_to_string(&path) {
+                    texts.push(content);
+                }
+                files.push(CrashFile {
+                    kind: CrashFileKind::CrashReport,
+                    path,
+                });
+            }
+        }
+
+        // JVM 崩溃日志 hs_err_pid*.log
+        for path in list_files(game_dir, |name| {
+            name.starts_with("hs_err_pid") && name.ends_with(".log")
+        }) {
+            if is_newer_than(&path, started) {
+                if let Ok(content) = std::fs::read(&path) {
+                    texts.push(String::from_utf8_lossy(&content).into_owned());
+                }
+                files.push(CrashFile {
+                    kind: CrashFileKind::JvmErrorLog,
+                    path,
+                });
+            }
+        }
+
+        // logs/latest.log 末尾
+        let latest_log = game_dir.join("logs").join("latest.log");
+        let log_tail = match std::fs::read(&latest_log) {
+            Ok(content) => {
+                files.push(CrashFile {
+                    kind: CrashFileKind::LatestLog,
+                    path: latest_log,
+                });
+                let content = String::from_utf8_lossy(&content);
+                let lines: Vec<&str> = content.lines().collect();
+                lines[lines.len().saturating_sub(LOG_TAIL_LINES)..]
+                    .iter()
+                    .map(|l| l.to_string())
+                    .collect()
+            }
+            Err(_) => Vec::new(),
+        };
+        texts.push(log_tail.join("\n"));
+        texts.push(game_output.join("\n"));
+
+        Self {
+            exit_code,
+            files,
+            log_tail,
+            findings: analyze(&texts.join("\n")),
+        }
+    }
+
+    // 导出为 zip，包含收集到的文件和 diagnosis.json
+    pub fn export_zip(&self, output_path: &Path) -> LauncherResult<()> {
+        let file =
+            std::fs::File::create(output_path).map_err(|e| LauncherError::io(output_path, e))?;
+        let mut zip = zip::ZipWriter::new(file);
+        let options = zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Deflated);
+
+        zip.start_file("diagnosis.json", options)?;
+        zip.write_all(serde_json::to_string_pretty(self)?.as_bytes())?;
+
+        for file in &self.files {
+            let name = file
+                .path
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let dir = match file.kind {
+                CrashFileKind::CrashReport => "crash-reports",
+                CrashFileKind::JvmErrorLog => "jvm",
+                CrashFileKind::LatestLog => "logs",
+            };
+            if let Ok(content) = std::fs::read(&file.path) {
+                zip.start_file(format!("{}/{}", dir, name), options)?;
+                zip.write_all(&content)?;
+            }
+        }
+
+        zip.finish()?;
+        Ok(())
+    }
+}
+
+// 基于规则的崩溃原因分析
+pub fn analyze(text: &str) -> Vec<CrashFinding> {
+    let mut findings: Vec<CrashFinding> = Vec::new();
+    let mut add = |cause: CrashCause, evidence: &str, title: String, suggestion: &str| {
+        if findings.iter().all(|f| f.cause != cause) {
+            findings.push(CrashFinding {
+                cause,
+                title,
+                suggestion: suggestion.to_string(),
+                evidence: evidence.trim().to_string(),
+            });
+        }
+    };
+
+    for line in text.lines() {
+        let lower = line.to_lowercase();
+
+        // Java 版本不匹配
+        if line.contains("UnsupportedClassVersionError")
+            || lower.contains("unsupported class file major version")
+        {
+            let title = match required_java_major(line) {
+                Some(major) => format!("Java 版本过低，需要 Java {} 或更高版本", major),
+                None => "Java 版本不匹配".to_string(),
+            };
+            add(
+                CrashCause::WrongJavaVersion,
+                line,
+                title,
+                "请在设置中为该版本选择匹配的 Java",
+            );
+        }
+
+        // natives 缺失
+        if line.contains("UnsatisfiedLinkError")
+            || lower.contains("in java.library.path")
+            || lower.contains("failed to locate library")
+        {
+            add(
+                CrashCause::MissingNatives,
+                line,
+                "本地库 (natives) 缺失或不匹配".to_string(),
+                "请重新下载该版本以补全 natives 文件",
+            );
+        }
+
+        // 内存不足
+        if line.contains("java.lang.OutOfMemoryError")
+            || lower.contains("could not reserve enough space")
+        {
+            add(
+                CrashCause::OutOfMemory,
+                line,
+                "内存不足".to_string(),
+                "请调大最大内存，或减少模组数量",
+            );
+        }
+
+        // 重复模组
+        if line.contains("DuplicateModsFoundException")
+            || lower.contains("found duplicate mods")
+            || lower.contains("duplicate mods found")
+            || (lower.contains("mod id") && lower.contains("duplicate"))
+        {
+            add(
+                CrashCause::DuplicateMods,
+                line,
+                "存在重复的模组".to_string(),
+                "请删除 mods 文件夹中重复的模组",
+            );
+        }
+
+        // Mixin 注入失败
+        if line.contains("MixinApplyError")
+            || line.contains("InvalidMixinException")
+            || line.contains("MixinTransformerError")
+            || lower.contains("mixin apply failed")
+        {
+            let title = match mixin_culprit(line) {
+                Some(id) => format!("Mixin 注入失败，相关模组: {}", id),
+                None => "Mixin 注入失败".to_string(),
+            };
+            add(
+                CrashCause::MixinFailure,
+                line,
+                title,
+                "相关模组可能与当前版本或其他模组不兼容，请更新或移除",
+            );
+        }
+
+        // 缺少前置
+        if lower.contains("missing or unsupported mandatory dependencies")
+            || lower.contains("some of your mods are incompatible")
+            || lower.contains("incompatible mods found")
+            || (lower.contains("requires") && lower.contains("which is missing"))
+        {
+            add(
+                CrashCause::MissingDependencies,
+                line,
+                "缺少前置模组或前置版本不兼容".to_string(),
+                "请根据日志安装缺少的前置模组",
+            );
+        }
+
+        // 显卡驱动
+        if [
+            "atio6axx.dll",
+            "atioglxx.dll",
+            "nvoglv64.dll",
+            "nvoglv32.dll",
+            "ig9icd64.dll",
+            "ig75icd64.dll",
+            "ig8icd64.dll",
+        ]
+        .iter()
+        .any(|dll| lower.contains(dll))
+            || lower.contains("pixel format not accelerated")
+            || lower.contains("glfw error 65542")
+            || lower.contains("no opengl context")
+        {
+            add(
+                CrashCause::GpuDriver,
+                line,
+                "显卡驱动崩溃或不支持 OpenGL".to_string(),
+                "请更新显卡驱动，笔记本请确认使用独立显卡运行",
+            );
+        }
+    }
+
+    findings
+}
+
+// 从 "class file version 65.0" 推算需要的 Java 主版本
+fn required_java_major(line: &str) -> Option<u32> {
+    let index = line.find("class file version ")? + "class file version ".len();
+    let version: String = line[index..]
+        .chars()
+        .take_while(|c| c.is_ascii_digit())
+        .collect();
+    version.parse::<u32>().ok().map(|v| v.saturating_sub(44))
+}
+
+// 从 "from mod xxx" 中提取模组ID
+fn mixin_culprit(line: &str) -> Option<String> {
+    let index = line.find("from mod ")? + "from mod ".len();
+    let id: String = line[index..]
+        .chars()
+        .take_while(|c| c.is_alphanumeric() || *c == '_' || *c == '-')
+        .collect();
+    (!id.is_empty()).then_some(id)
+}
+
+fn list_files(dir: &Path, filter: impl Fn(&str) -> bool) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .file_name()
+                    .map(|n| filter(&n.to_string_lossy()))
+                    .unwrap_or(false)
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+fn is_newer_than(path: &Path, time: SystemTime) -> bool {
+    std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .map(|modified| modified >= time)
+        .unwrap_or(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_analyze_common_causes() {
+        let log = "\
+Exception in thread \"main\" java.lang.UnsupportedClassVersionError: net/minecraft/client/main/Main has been compiled by a more recent version of the Java Runtime (class file version 65.0), this version of the Java Runtime only recognizes class file versions up to 52.0
+java.lang.OutOfMemoryError: Java heap space
+org.spongepowered.asm.mixin.transformer.throwables.MixinTransformerError: Mixin [foo.mixins.json:MixinRender from mod foo_bar] failed
+# C  [atio6axx.dll+0x1234]";
+        let findings = analyze(log);
+        let causes: Vec<CrashCause> = findings.iter().map(|f| f.cause).collect();
+
+        assert_eq!(
+            causes,
+            vec![
+                CrashCause::WrongJavaVersion,
+                CrashCause::OutOfMemory,
+                CrashCause::MixinFailure,
+                CrashCause::GpuDriver
+            ]
+        );
+        assert!(findings[0].title.contains("21"));
+        assert!(findings[2].title.contains("foo_bar"));
+    }
+
+    #[test]
+    fn test_collect_and_export() {
+        let dir = std::env::temp_dir().join(format!("rtl-crash-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let started = SystemTime::now() - std::time::Duration::from_secs(5);
+        std::fs::create_dir_all(dir.join("crash-reports")).unwrap();
+        std::fs::create_dir_all(dir.join("logs")).unwrap();
+        std::fs::write(
+            dir.join("crash-reports").join("crash-client.txt"),
+            "net.fabricmc.loader.impl.FormattedException: Some of your mods are incompatible",
+        )
+        .unwrap();
+        std::fs::write(dir.join("logs").join("latest.log"), "line 1\nline 2\n").unwrap();
+
+        let report = CrashReport::collect(&dir, started, Some(1), &[]);
+        assert_eq!(report.files.len(), 2);
+        assert_eq!(report.log_tail, vec!["line 1", "line 2"]);
+        assert_eq!(report.findings[0].cause, CrashCause::MissingDependencies);
+
+        let zip_path = dir.join("crash.zip");
+        report.export_zip(&zip_path).unwrap();
+        let archive = zip::ZipArchive::new(std::fs::File::open(&zip_path).unwrap()).unwrap();
+        assert_eq!(archive.len(), 3);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,233 @@
+/*
+RTLauncher, a third-party Minecraft launcher built with the newest
+technology and provides innovative funtionalities
+Copyright (C) 2025 lutouna
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+// ***
+// 游戏日志解析（log4j XML layout）
+// ***
+
+use serde::Serialize;
+
+// 日志来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogStream {
+    Stdout,
+    Stderr,
+}
+
+// 结构化的游戏日志
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GameLogRecord {
+    pub stream: LogStream,
+    pub timestamp: Option<i64>, // 毫秒时间戳
+    pub level: String,
+    pub logger: Option<String>,
+    pub thread: Option<String>,
+    pub message: String,
+    pub throwable: Option<String>,
+}
+
+// 按行喂入的解析器，log4j 事件会跨越多行
+pub struct Log4jParser {
+    stream: LogStream,
+    buffer: Option<String>,
+}
+
+impl Log4jParser {
+    pub fn new(stream: LogStream) -> Self {
+        Self {
+            stream,
+            buffer: None,
+        }
+    }
+
+    // 输入一行，返回解析完成的记录
+    pub fn feed(&mut self, line: &str) -> Option<GameLogRecord> {
+        if let Some(buffer) = self.buffer.as_mut() {
+            buffer.push('\n');
+            buffer.push_str(line);
+            if line.contains("</log4j:Event>") {
+                let event = self.buffer.take().unwrap_or_default();
+                return Some(self.parse_event(&event));
+            }
+            return None;
+        }
+
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("<log4j:Event") {
+            if trimmed.contains("</log4j:Event>") || trimmed.ends_with("/>") {
+                return Some(self.parse_event(trimmed));
+            }
+            self.buffer = Some(trimmed.to_string());
+            return None;
+        }
+
+        if line.trim().is_empty() {
+            return None;
+        }
+        Some(self.parse_plain(line))
+    }
+
+    // 进程结束时输出残留的半个事件
+    pub fn flush(&mut self) -> Option<GameLogRecord> {
+        self.buffer.take().map(|event| GameLogRecord {
+            stream: self.stream,
+            timestamp: None,
+            level: self.default_level().to_string(),
+            logger: None,
+            thread: None,
+            message: event,
+            throwable: None,
+        })
+    }
+
+    fn default_level(&self) -> &'static str {
+        match self.stream {
+            LogStream::Stdout => "INFO",
+            LogStream::Stderr => "ERROR",
+        }
+    }
+
+    fn parse_event(&self, event: &str) -> GameLogRecord {
+        let head_end = event.find('>').unwrap_or(event.len());
+        let head = &event[..head_end];
+
+        GameLogRecord {
+            stream: self.stream,
+            timestamp: get_attribute(head, "timestamp").and_then(|t| t.parse().ok()),
+            level: get_attribute(head, "level").unwrap_or_else(|| self.default_level().to_string()),
+            logger: get_attribute(head, "logger"),
+            thread: get_attribute(head, "thread"),
+            message: get_element(event, "log4j:Message").unwrap_or_default(),
+            throwable: get_element(event, "log4j:Throwable"),
+        }
+    }
+
+    // 解析普通文本行，如 "[12:00:00] [main/WARN]: message"
+    fn parse_plain(&self, line: &str) -> GameLogRecord {
+        let mut record = GameLogRecord {
+            stream: self.stream,
+            timestamp: None,
+            level: self.default_level().to_string(),
+            logger: None,
+            thread: None,
+            message: line.to_string(),
+            throwable: None,
+        };
+
+        let rest = line
+            .strip_prefix('[')
+            .and_then(|s| s.split_once("] ["))
+            .map(|(_, rest)| rest);
+        if let Some((head, message)) = rest.and_then(|s| s.split_once("]: ")) {
+            if let Some((thread, level)) = head.rsplit_once('/') {
+                record.thread = Some(thread.to_string());
+                record.level = level.to_uppercase();
+                record.message = message.to_string();
+            }
+        }
+        record
+    }
+}
+
+// 读取XML属性值
+fn get_attribute(head: &str, name: &str) -> Option<String> {
+    let pattern = format!(" {}=\"", name);
+    let start = head.find(&pattern)? + pattern.len();
+    let end = head[start..].find('"')? + start;
+    Some(unescape_xml(&head[start..end]))
+}
+
+// 读取XML元素内容，支持 CDATA
+fn get_element(event: &str, tag: &str) -> Option<String> {
+    let open = format!("<{}>", tag);
+    let close = format!("</{}>", tag);
+    let start = event.find(&open)? + open.len();
+    let end = event[start..].find(&close)? + start;
+    let content = event[start..end].trim();
+
+    match content
+        .strip_prefix("<![CDATA[")
+        .and_then(|c| c.strip_suffix("]]>"))
+    {
+        Some(cdata) => Some(cdata.to_string()),
+        None => Some(unescape_xml(content)),
+    }
+}
+
+fn unescape_xml(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&#10;", "\n")
+        .replace("&#13;", "\r")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_multiline_event() {
+        let mut parser = Log4jParser::new(LogStream::Stdout);
+        let lines = [
+            r#"<log4j:Event logger="net.minecraft.client.Minecraft" timestamp="1700000000000" level="WARN" thread="Render thread">"#,
+            r#"  <log4j:Message><![CDATA[Setting user: <Steve> & co]]></log4j:Message>"#,
+            r#"</log4j:Event>"#,
+        ];
+        assert!(parser.feed(lines[0]).is_none());
+        assert!(parser.feed(lines[1]).is_none());
+        let record = parser.feed(lines[2]).unwrap();
+
+        assert_eq!(record.level, "WARN");
+        assert_eq!(record.timestamp, Some(1700000000000));
+        assert_eq!(record.thread.as_deref(), Some("Render thread"));
+        assert_eq!(
+            record.logger.as_deref(),
+            Some("net.minecraft.client.Minecraft")
+        );
+        assert_eq!(record.message, "Setting user: <Steve> & co");
+        assert!(record.throwable.is_none());
+    }
+
+    #[test]
+    fn test_parse_throwable_and_plain_line() {
+        let mut parser = Log4jParser::new(LogStream::Stderr);
+        let event = r#"<log4j:Event logger="a&amp;b" timestamp="1" level="ERROR" thread="main"><log4j:Message><![CDATA[boom]]></log4j:Message><log4j:Throwable><![CDATA[java.lang.RuntimeException]]></log4j:Throwable></log4j:Event>"#;
+        let record = parser.feed(event).unwrap();
+        assert_eq!(record.logger.as_deref(), Some("a&b"));
+        assert_eq!(
+            record.throwable.as_deref(),
+            Some("java.lang.RuntimeException")
+        );
+
+        let record = parser
+            .feed("[12:00:00] [Worker-Main-1/INFO]: Loaded 7 recipes")
+            .unwrap();
+        assert_eq!(record.level, "INFO");
+        assert_eq!(record.thread.as_deref(), Some("Worker-Main-1"));
+        assert_eq!(record.message, "Loaded 7 recipes");
+
+        let record = parser.feed("Exception in thread \"main\"").unwrap();
+        assert_eq!(record.level, "ERROR");
+        assert_eq!(record.stream, LogStream::Stderr);
+    }
+}
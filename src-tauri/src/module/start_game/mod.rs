@@ -17,4 +17,7 @@ You should have received a copy of the GNU General Public License
 along with this program.  If not, see <https://www.gnu.org/licenses/>.
 */
 
+pub mod crash;
+pub mod log_parser;
+pub mod process;
 pub mod stg_main;
@@ -0,0 +1,455 @@
+/*
+RTLauncher, a third-party Minecraft launcher built with the newest
+technology and provides innovative funtionalities
+Copyright (C) 2025 lutouna
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+// ***
+// 游戏进程管理
+// ***
+
+use super::crash::CrashReport;
+use super::log_parser::{GameLogRecord, Log4jParser, LogStream};
+use crate::utils::error::{LauncherError, LauncherResult};
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, State};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::process::Command;
+use tokio::sync::oneshot;
+
+// 每个进程保留的日志条数
+const MAX_LOG_HISTORY: usize = 5000;
+
+// 前端事件名
+pub const GAME_LOG_EVENT: &str = "game-log";
+pub const GAME_EXIT_EVENT: &str = "game-exit";
+pub const GAME_CRASH_EVENT: &str = "game-crash";
+
+// 交给崩溃分析的输出行数
+const CRASH_OUTPUT_LINES: usize = 500;
+
+// 进程状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GameStatus {
+    Running,
+    Exited,
+    Killed,
+}
+
+// 返回给前端的进程信息
+#[derive(Debug, Clone, Serialize)]
+pub struct GameProcessInfo {
+    pub id: String,
+    pub version_id: String,
+    pub pid: Option<u32>,
+    pub started_at: u64, // 毫秒时间戳
+    pub uptime_secs: u64,
+    pub status: GameStatus,
+    pub exit_code: Option<i32>,
+    pub crashed: bool,
+}
+
+// 日志事件
+#[derive(Debug, Clone, Serialize)]
+pub struct GameLogEvent {
+    pub id: String,
+    pub record: GameLogRecord,
+}
+
+// 崩溃事件
+#[derive(Debug, Clone, Serialize)]
+pub struct GameCrashEvent {
+    pub id: String,
+    pub report: CrashReport,
+}
+
+// 进程事件的接收方，GUI 转发为 Tauri 事件
+pub trait GameEventSink: Send + Sync {
+    fn on_log(&self, id: &str, record: &GameLogRecord);
+    fn on_exit(&self, info: &GameProcessInfo);
+    fn on_crash(&self, id: &str, report: &CrashReport);
+}
+
+impl GameEventSink for AppHandle {
+    fn on_log(&self, id: &str, record: &GameLogRecord) {
+        let event = GameLogEvent {
+            id: id.to_string(),
+            record: record.clone(),
+        };
+        if let Err(e) = self.emit(GAME_LOG_EVENT, event) {
+            log::error!("发送游戏日志事件失败: {}", e);
+        }
+    }
+
+    fn on_exit(&self, info: &GameProcessInfo) {
+        if let Err(e) = self.emit(GAME_EXIT_EVENT, info.clone()) {
+            log::error!("发送游戏退出事件失败: {}", e);
+        }
+    }
+
+    fn on_crash(&self, id: &str, report: &CrashReport) {
+        let event = GameCrashEvent {
+            id: id.to_string(),
+            report: report.clone(),
+        };
+        if let Err(e) = self.emit(GAME_CRASH_EVENT, event) {
+            log::error!("发送游戏崩溃事件失败: {}", e);
+        }
+    }
+}
+
+// 单个游戏进程
+struct GameProcess {
+    id: String,
+    version_id: String,
+    pid: Option<u32>,
+    started_at: u64,
+    started: Instant,
+    game_dir: PathBuf,
+    state: Mutex<ProcessState>,
+    logs: Mutex<VecDeque<GameLogRecord>>,
+    kill_tx: Mutex<Option<oneshot::Sender<()>>>,
+    crash: Mutex<Option<CrashReport>>,
+}
+
+struct ProcessState {
+    status: GameStatus,
+    exit_code: Option<i32>,
+    uptime_secs: Option<u64>,
+}
+
+impl GameProcess {
+    fn info(&self) -> GameProcessInfo {
+        let state = self.state.lock().unwrap();
+        GameProcessInfo {
+            id: self.id.clone(),
+            version_id: self.version_id.clone(),
+            pid: self.pid,
+            started_at: self.started_at,
+            uptime_secs: state
+                .uptime_secs
+                .unwrap_or_else(|| self.started.elapsed().as_secs()),
+            status: state.status,
+            exit_code: state.exit_code,
+            crashed: self.crash.lock().unwrap().is_some(),
+        }
+    }
+
+    // 最近的输出文本，供崩溃分析使用
+    fn output_tail(&self) -> Vec<String> {
+        let logs = self.logs.lock().unwrap();
+        logs.iter()
+            .skip(logs.len().saturating_sub(CRASH_OUTPUT_LINES))
+            .flat_map(|r| std::iter::once(r.message.clone()).chain(r.throwable.clone()))
+            .collect()
+    }
+
+    fn push_log(&self, record: GameLogRecord) {
+        let mut logs = self.logs.lock().unwrap();
+        if logs.len() >= MAX_LOG_HISTORY {
+            logs.pop_front();
+        }
+        logs.push_back(record);
+    }
+}
+
+// 进程管理器，作为 Tauri 状态注册
+#[derive(Default)]
+pub struct GameProcessManager {
+    next_id: AtomicU64,
+    processes: Mutex<HashMap<String, Arc<GameProcess>>>,
+}
+
+impl GameProcessManager {
+    // 启动进程并开始捕获输出，返回进程编号
+    pub fn spawn(
+        &self,
+        mut command: Command,
+        version_id: &str,
+        game_dir: PathBuf,
+        sink: Arc<dyn GameEventSink>,
+    ) -> LauncherResult<String> {
+        command
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .kill_on_drop(false);
+
+        let mut child = command.spawn()?;
+        let id = format!(
+            "{}-{}",
+            version_id,
+            self.next_id.fetch_add(1, Ordering::SeqCst) + 1
+        );
+        let started_system = SystemTime::now();
+        let started_at = started_system
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or_default();
+
+        let (kill_tx, kill_rx) = oneshot::channel();
+        let process = Arc::new(GameProcess {
+            id: id.clone(),
+            version_id: version_id.to_string(),
+            pid: child.id(),
+            started_at,
+            started: Instant::now(),
+            game_dir,
+            state: Mutex::new(ProcessState {
+                status: GameStatus::Running,
+                exit_code: None,
+                uptime_secs: None,
+            }),
+            logs: Mutex::new(VecDeque::new()),
+            kill_tx: Mutex::new(Some(kill_tx)),
+            crash: Mutex::new(None),
+        });
+        log::info!("游戏启动成功，进程ID: {:?}", process.pid);
+
+        self.processes
+            .lock()
+            .unwrap()
+            .insert(id.clone(), process.clone());
+
+        // 逐行读取 stdout 和 stderr
+        let mut readers = Vec::new();
+        if let Some(stdout) = child.stdout.take() {
+            readers.push(tokio::spawn(read_lines(
+                stdout,
+                LogStream::Stdout,
+                process.clone(),
+                sink.clone(),
+            )));
+        }
+        if let Some(stderr) = child.stderr.take() {
+            readers.push(tokio::spawn(read_lines(
+                stderr,
+                LogStream::Stderr,
+                process.clone(),
+                sink.clone(),
+            )));
+        }
+
+        // 等待进程退出或被结束
+        tokio::spawn(async move {
+            let (status, killed) = tokio::select! {
+                status = child.wait() => (status, false),
+                _ = kill_rx => {
+                    if let Err(e) = child.kill().await {
+                        log::error!("结束游戏进程失败: {}", e);
+                    }
+                    (child.wait().await, true)
+                }
+            };
+
+            // 确保输出已经全部读完
+            for reader in readers {
+                let _ = reader.await;
+            }
+
+            {
+                let mut state = process.state.lock().unwrap();
+                state.status = if killed {
+                    GameStatus::Killed
+                } else {
+                    GameStatus::Exited
+                };
+                state.exit_code = status.as_ref().ok().and_then(|s| s.code());
+                state.uptime_secs = Some(process.started.elapsed().as_secs());
+            }
+            process.kill_tx.lock().unwrap().take();
+
+            // 非正常退出时收集崩溃信息
+            let exit_code = process.state.lock().unwrap().exit_code;
+            if !killed && exit_code != Some(0) {
+                let game_dir = process.game_dir.clone();
+                let output = process.output_tail();
+                let report = tokio::task::spawn_blocking(move || {
+                    CrashReport::collect(&game_dir, started_system, exit_code, &output)
+                })
+                .await;
+                match report {
+                    Ok(report) => {
+                        log::warn!("游戏异常退出，发现 {} 条可能原因", report.findings.len());
+                        *process.crash.lock().unwrap() = Some(report.clone());
+                        sink.on_crash(&process.id, &report);
+                    }
+                    Err(e) => log::error!("崩溃分析失败: {}", e),
+                }
+            }
+
+            let info = process.info();
+            log::info!(
+                "游戏进程已结束: {}，退出状态: {:?}",
+                info.id,
+                info.exit_code
+            );
+            sink.on_exit(&info);
+        });
+
+        Ok(id)
+    }
+
+    pub fn list(&self) -> Vec<GameProcessInfo> {
+        let mut list: Vec<_> = self
+            .processes
+            .lock()
+            .unwrap()
+            .values()
+            .map(|p| p.info())
+            .collect();
+        list.sort_by_key(|p| p.started_at);
+        list
+    }
+
+    pub fn get(&self, id: &str) -> Option<GameProcessInfo> {
+        self.processes.lock().unwrap().get(id).map(|p| p.info())
+    }
+
+    pub fn logs(&self, id: &str) -> Option<Vec<GameLogRecord>> {
+        self.processes
+            .lock()
+            .unwrap()
+            .get(id)
+            .map(|p| p.logs.lock().unwrap().iter().cloned().collect())
+    }
+
+    pub fn crash_report(&self, id: &str) -> Option<CrashReport> {
+        self.processes
+            .lock()
+            .unwrap()
+            .get(id)
+            .and_then(|p| p.crash.lock().unwrap().clone())
+    }
+
+    // 结束正在运行的游戏
+    pub fn kill(&self, id: &str) -> LauncherResult<()> {
+        let process = self
+            .processes
+            .lock()
+            .unwrap()
+            .get(id)
+            .cloned()
+            .ok_or_else(|| LauncherError::ProcessNotFound(id.to_string()))?;
+        let sender = process.kill_tx.lock().unwrap().take();
+        sender
+            .and_then(|tx| tx.send(()).ok())
+            .ok_or_else(|| LauncherError::Other("游戏进程已结束".to_string()))
+    }
+
+    // 清除已经结束的进程记录
+    pub fn remove_finished(&self) {
+        self.processes
+            .lock()
+            .unwrap()
+            .retain(|_, p| p.state.lock().unwrap().status == GameStatus::Running);
+    }
+}
+
+async fn read_lines<R: AsyncRead + Unpin>(
+    reader: R,
+    stream: LogStream,
+    process: Arc<GameProcess>,
+    sink: Arc<dyn GameEventSink>,
+) {
+    let mut parser = Log4jParser::new(stream);
+    let mut lines = BufReader::new(reader).lines();
+    let emit = |record: GameLogRecord| {
+        sink.on_log(&process.id, &record);
+        process.push_log(record);
+    };
+
+    loop {
+        match lines.next_line().await {
+            Ok(Some(line)) => {
+                if let Some(record) = parser.feed(&line) {
+                    emit(record);
+                }
+            }
+            Ok(None) => break,
+            Err(e) => {
+                log::error!("读取游戏输出失败: {}", e);
+                break;
+            }
+        }
+    }
+    if let Some(record) = parser.flush() {
+        emit(record);
+    }
+}
+
+#[tauri::command]
+pub fn list_game_processes(manager: State<'_, GameProcessManager>) -> Vec<GameProcessInfo> {
+    manager.list()
+}
+
+#[tauri::command]
+pub fn get_game_process(
+    id: String,
+    manager: State<'_, GameProcessManager>,
+) -> LauncherResult<GameProcessInfo> {
+    manager.get(&id).ok_or(LauncherError::ProcessNotFound(id))
+}
+
+#[tauri::command]
+pub fn get_game_logs(
+    id: String,
+    manager: State<'_, GameProcessManager>,
+) -> LauncherResult<Vec<GameLogRecord>> {
+    manager.logs(&id).ok_or(LauncherError::ProcessNotFound(id))
+}
+
+#[tauri::command]
+pub fn get_crash_report(
+    id: String,
+    manager: State<'_, GameProcessManager>,
+) -> LauncherResult<Option<CrashReport>> {
+    manager
+        .get(&id)
+        .map(|_| manager.crash_report(&id))
+        .ok_or(LauncherError::ProcessNotFound(id))
+}
+
+// 导出崩溃报告为 zip
+#[tauri::command]
+pub async fn export_crash_report(
+    id: String,
+    output_path: String,
+    manager: State<'_, GameProcessManager>,
+) -> LauncherResult<String> {
+    let report = manager
+        .crash_report(&id)
+        .ok_or_else(|| LauncherError::Other(format!("游戏进程没有崩溃报告: {}", id)))?;
+    tokio::task::spawn_blocking(move || report.export_zip(std::path::Path::new(&output_path)))
+        .await??;
+    Ok("崩溃报告已成功导出".to_string())
+}
+
+#[tauri::command]
+pub fn kill_game(id: String, manager: State<'_, GameProcessManager>) -> LauncherResult<()> {
+    manager.kill(&id)
+}
+
+#[tauri::command]
+pub fn clear_finished_games(manager: State<'_, GameProcessManager>) {
+    manager.remove_finished();
+}
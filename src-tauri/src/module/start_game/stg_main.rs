@@ -21,39 +21,80 @@ along with this program.  If not, see <https://www.gnu.org/licenses/>.
 // 启动游戏主函数
 // ***
 
-use crate::utils::get_java_path::get_java_path;
+use super::process::{GameEventSink, GameProcessManager};
+use crate::utils::error::{LauncherError, LauncherResult};
+use crate::utils::get_java_path::find_java;
 use os_info;
 use std::env::consts::OS;
+use std::sync::Arc;
+use tauri::{AppHandle, State};
 
-use crate::module::download::dwl_main::MinecraftPaths;
-use std::process::Command;
+use crate::module::content::scanner;
+use crate::module::download::installed::{self, resolve_version};
+use crate::module::download::paths::MinecraftPaths;
+use crate::module::instance::ins_main::{Instance, InstanceStore};
+use crate::Setting::config::{self, LauncherSettings};
+use std::path::{Path, PathBuf};
+
+// 版本 JSON 缺失 mainClass 时使用的主类
+const DEFAULT_MAIN_CLASS: &str = "net.minecraft.client.main.Main";
 
 // 启动游戏结构体
 pub struct StartGame {
     pub java_path: String,
+    pub java_version: String,
     pub launch_args: Vec<String>,
+    pub version_id: String,
+    pub game_dir: PathBuf,
 }
 
 // 共享方法到前端
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn stg(
     startup_parameter: String,
     version_id: String,
     java_version: String,
     asset_index_id: String,
     username: String,
-) -> Result<String, String> {
-    let start_game = StartGame::new(
-        startup_parameter,
-        version_id,
-        java_version,
-        asset_index_id,
-        username,
-    );
-    match start_game.start_game() {
-        Ok(output) => Ok(output),
-        Err(e) => Err(format!("游戏启动失败: {}", e)),
-    }
+    instance_id: Option<String>,
+    app: AppHandle,
+    manager: State<'_, GameProcessManager>,
+) -> LauncherResult<String> {
+    let start_game = match instance_id {
+        // 使用实例的独立目录和配置启动
+        Some(instance_id) => {
+            let store = InstanceStore::new();
+            let instance = store.get(&instance_id)?;
+            let _ = store.touch(&instance_id);
+            // 启动前检查模组，问题只记录到日志，不阻止启动
+            let scanned = instance.clone();
+            let scan = tokio::task::spawn_blocking(move || scanner::scan_instance(&scanned)).await;
+            match scan.map_err(LauncherError::from).and_then(|result| result) {
+                Ok(report) => {
+                    for issue in &report.issues {
+                        log::warn!("模组检查: {}", issue.message);
+                    }
+                }
+                Err(e) => log::warn!("模组检查失败: {}", e),
+            }
+            StartGame::from_instance(
+                &instance,
+                startup_parameter,
+                java_version,
+                asset_index_id,
+                username,
+            )?
+        }
+        None => StartGame::new(
+            startup_parameter,
+            version_id,
+            java_version,
+            asset_index_id,
+            username,
+        ),
+    };
+    start_game.start_game(&manager, Arc::new(app))
 }
 
 // 获取游戏jar路径
@@ -74,49 +115,115 @@ impl StartGame {
         asset_index_id: String,
         username: String,
     ) -> Self {
-        let java_paths = get_java_path();
-        let java_path = java_paths
-            .iter()
-            .find_map(|path| {
-                let possible_paths = match OS {
-                    "windows" => vec![
-                        format!("{}\\bin\\java.exe", path),
-                        format!("{}\\java.exe", path),
-                        format!("{}\\javapath\\java.exe", path),
-                    ],
-                    _ => vec![format!("{}/bin/java", path), format!("{}/java", path)],
-                };
-
-                // 遍历所有可能的路径，检查Java版本
-                for p in possible_paths {
-                    if let Ok(version) = Self::get_java_version(&p) {
-                        if version.contains(&java_version) {
-                            return Some(p);
-                        }
-                    }
-                }
-                None
-            })
-            .unwrap_or_default();
+        let settings = config::current();
+        let java_path = Self::settings_java_path(&java_version, &settings);
+        let mut start_game = Self::with_game_dir(
+            Self::resolve_jvm_args(startup_parameter, &settings),
+            version_id,
+            Self::resolve_java_version(java_version, &settings),
+            asset_index_id,
+            username,
+            MinecraftPaths::new().base_dir,
+        );
+        if let Some(java_path) = java_path {
+            start_game.java_path = java_path;
+        }
+        start_game
+    }
 
-        let launch_args =
-            Self::load_launch_args(startup_parameter, &version_id, &asset_index_id, username);
+    // 使用实例配置，实例设置优先于全局设置
+    pub fn from_instance(
+        instance: &Instance,
+        startup_parameter: String,
+        java_version: String,
+        asset_index_id: String,
+        username: String,
+    ) -> LauncherResult<Self> {
+        let settings = config::current().for_instance(instance)?;
+        let startup_parameter = if instance.has_jvm_override() {
+            settings.jvm_args_string()
+        } else {
+            Self::resolve_jvm_args(startup_parameter, &settings)
+        };
+        let java_path = Self::settings_java_path(&java_version, &settings);
+        let mut start_game = Self::with_game_dir(
+            startup_parameter,
+            instance.version_id.clone(),
+            Self::resolve_java_version(java_version, &settings),
+            asset_index_id,
+            username,
+            instance.game_dir(),
+        );
+        if let Some(java_path) = java_path {
+            start_game.java_path = java_path;
+        }
+        Ok(start_game)
+    }
 
-        Self {
-            java_path,
-            launch_args,
+    // 未传入启动参数时使用设置中的内存和JVM参数
+    fn resolve_jvm_args(startup_parameter: String, settings: &LauncherSettings) -> String {
+        if startup_parameter.trim().is_empty() {
+            settings.jvm_args_string()
+        } else {
+            startup_parameter
         }
     }
 
-    // 获取java版本
-    fn get_java_version(java_path: &str) -> Result<String, String> {
-        let output = Command::new(java_path)
-            .arg("-version")
-            .output()
-            .map_err(|e| e.to_string())?;
+    // 传入的 Java 版本优先，为空时使用设置中的版本
+    fn resolve_java_version(java_version: String, settings: &LauncherSettings) -> String {
+        if java_version.trim().is_empty() {
+            settings.java.java_version.clone().unwrap_or_default()
+        } else {
+            java_version
+        }
+    }
 
-        let version_info = String::from_utf8_lossy(&output.stderr).to_string();
-        Ok(version_info)
+    // 只有未传入 Java 版本时才使用设置中的 Java 路径
+    fn settings_java_path(java_version: &str, settings: &LauncherSettings) -> Option<String> {
+        if java_version.trim().is_empty() {
+            settings.java.java_path.clone()
+        } else {
+            None
+        }
+    }
+
+    pub fn with_game_dir(
+        startup_parameter: String,
+        version_id: String,
+        java_version: String,
+        asset_index_id: String,
+        username: String,
+        game_dir: PathBuf,
+    ) -> Self {
+        // 未指定 Java 版本时读取本地版本 JSON 的要求
+        let java_version = if java_version.trim().is_empty() {
+            resolve_version(&MinecraftPaths::new(), &version_id)
+                .ok()
+                .and_then(|json| json["javaVersion"]["majorVersion"].as_u64())
+                .map(|major| major.to_string())
+                // 旧版本的 JSON 没有 javaVersion，使用 Java 8
+                .unwrap_or_else(|| "8".to_string())
+        } else {
+            java_version
+        };
+
+        let java_path = find_java(&java_version).unwrap_or_default();
+
+        let launch_args = Self::load_launch_args(
+            startup_parameter,
+            &version_id,
+            &asset_index_id,
+            username,
+            &game_dir,
+        );
+
+        Self {
+            java_path,
+            java_version,
+            launch_args,
+            version_id,
+            game_dir,
+        }
     }
 
     pub fn load_launch_args(
@@ -124,6 +231,7 @@ impl StartGame {
         version_id: &str,
         asset_index_id: &str,
         username: String,
+        game_dir: &Path,
     ) -> Vec<String> {
         let mut args = Vec::new();
         let info = os_info::get();
@@ -132,26 +240,58 @@ impl StartGame {
 
         // 获取路径管理结构体
         let paths = MinecraftPaths::new();
+        // 读取本地版本 JSON，启动不需要联网
+        let version = resolve_version(&paths, version_id).ok();
+        // 继承其他版本时，客户端jar、natives和日志配置都来自被继承的版本
+        let jar_id = version
+            .as_ref()
+            .and_then(|json| json["jar"].as_str())
+            .unwrap_or(version_id)
+            .to_string();
+        let main_class = version
+            .as_ref()
+            .and_then(|json| json["mainClass"].as_str())
+            .unwrap_or(DEFAULT_MAIN_CLASS)
+            .to_string();
+        let asset_index_id = match version.as_ref() {
+            Some(json) if asset_index_id.is_empty() => {
+                json["assetIndex"]["id"].as_str().unwrap_or_default()
+            }
+            _ => asset_index_id,
+        };
+
         // 获取日志配置文件路径
         let log4j_config_path =
-            paths.get_absolute_path(paths.get_version_dir(version_id).join("client-1.12.xml"));
+            paths.get_absolute_path(paths.get_version_dir(&jar_id).join("client-1.12.xml"));
         // 获取客户端jar路径
-        let game_jar_route = get_game_jar_path(version_id);
+        let game_jar_route = get_game_jar_path(&jar_id);
         // 获取解压的natives目录路径
-        let natives_path = paths.get_absolute_path(
-            paths
-                .get_version_dir(version_id)
-                .join(format!("{}-natives", version_id)),
-        );
-        // 获取所有libraries的jar文件路径
-        let mut classpath = paths.get_libraries_classpath();
-        classpath.push(get_game_jar_path(version_id));
+        let natives_path = paths.get_absolute_path(paths.get_natives_dir(&jar_id));
+        // 按版本 JSON 中的库文件生成classpath，没有版本 JSON 时扫描整个libraries目录
+        let classpath: Vec<String> = match version.as_ref() {
+            Some(json) => installed::classpath(&paths, json)
+                .into_iter()
+                .map(|path| paths.get_absolute_path(path))
+                .filter(|path| !path.is_empty())
+                .collect(),
+            None => {
+                let mut classpath = paths.get_libraries_classpath();
+                classpath.push(get_game_jar_path(version_id));
+                classpath
+            }
+        };
 
         // 获取classpath路径
-        let libraries_path = if OS == "windows" {
-            format!("{}", classpath.join(";"))
-        } else {
-            format!("{}", classpath.join(":"))
+        let separator = if OS == "windows" { ";" } else { ":" };
+        let libraries_path = classpath.join(separator);
+
+        // 加载器需要的额外参数，替换其中的路径变量
+        let (loader_jvm_args, loader_game_args) = installed::loader_arguments(&paths, version_id);
+        let libraries_dir = paths.get_absolute_path(paths.libraries_dir.clone());
+        let substitute = |arg: String| {
+            arg.replace("${library_directory}", &libraries_dir)
+                .replace("${classpath_separator}", separator)
+                .replace("${version_name}", version_id)
         };
 
         // 分割内存参数并添加到启动参数中
@@ -184,9 +324,12 @@ impl StartGame {
             format!("-Dminecraft.client.jar={}", game_jar_route),
             format!("-Dlog4j.configurationFile={}", log4j_config_path),
             format!("-Djava.library.path={}", natives_path),
+        ]);
+        args.extend(loader_jvm_args.into_iter().map(substitute));
+        args.extend(vec![
             "-cp".to_string(),
             libraries_path,
-            "net.minecraft.client.main.Main".to_string(),
+            main_class,
             "--version".to_string(),
             version_id.to_string(),
             "--username".to_string(),
@@ -194,58 +337,87 @@ impl StartGame {
             "--accessToken".to_string(),
             "00000FFFFFFFFFFFFFFFFFFFFFF9E747".to_string(),
             "--gameDir".to_string(),
-            paths.base_dir.to_string_lossy().into_owned(),
+            game_dir.to_string_lossy().into_owned(),
             "--assetsDir".to_string(),
             paths.assets_dir.to_string_lossy().into_owned(),
             "--assetIndex".to_string(),
             asset_index_id.to_string(),
         ]);
+        args.extend(loader_game_args.into_iter().map(substitute));
 
         args
     }
 
-    pub fn start_game(&self) -> Result<String, String> {
+    // 启动游戏进程并交给进程管理器托管，返回进程编号
+    pub fn start_game(
+        &self,
+        manager: &GameProcessManager,
+        sink: Arc<dyn GameEventSink>,
+    ) -> LauncherResult<String> {
+        if self.java_path.is_empty() {
+            return Err(LauncherError::JavaNotFound(self.java_version.clone()));
+        }
+        // 旧版本安装时没有保存版本 JSON，此时只检查客户端jar
+        let has_json = MinecraftPaths::new()
+            .get_version_json(&self.version_id)
+            .exists();
+        if !has_json && !Path::new(&get_game_jar_path(&self.version_id)).exists() {
+            return Err(LauncherError::VersionNotFound(self.version_id.clone()));
+        }
+
         let mut command = match OS {
-            "windows" | "linux" | "macos" => Command::new(&self.java_path),
-            _ => return Err("不支持的操作系统".to_string()),
+            "windows" | "linux" | "macos" => tokio::process::Command::new(&self.java_path),
+            _ => return Err(LauncherError::Unsupported(OS.to_string())),
         };
 
-        // 设置工作目录为 .minecraft 文件夹
-        let paths = MinecraftPaths::new();
-        command.current_dir(&paths.base_dir);
+        // 设置工作目录为游戏目录
+        std::fs::create_dir_all(&self.game_dir)
+            .map_err(|e| LauncherError::io(&self.game_dir, e))?;
+        command.current_dir(&self.game_dir);
 
         command.args(&self.launch_args);
 
         // 完整的启动命令
         let full_command = format!("\"{}\" {}", &self.java_path, &self.launch_args.join(" "));
-        println!("完整启动命令: {}", full_command);
-        println!("工作目录: {}", paths.base_dir.display());
+        log::info!("完整启动命令: {}", full_command);
+        log::info!("工作目录: {}", self.game_dir.display());
 
         // 打印启动命令和参数
-        println!("启动Java: {}", &self.java_path);
-        println!("启动参数: {:?}", &self.launch_args);
+        log::info!("启动Java: {}", &self.java_path);
+        log::info!("启动参数: {:?}", &self.launch_args);
 
         // 启动游戏进程
-        match command.spawn() {
-            Ok(mut child) => {
-                println!("游戏启动成功，进程ID: {:?}", child.id());
-
-                // 等待游戏进程结束
-                match child.wait() {
-                    Ok(status) => {
-                        println!("游戏进程已结束，退出状态: {}", status);
-                        Ok("游戏已关闭".to_string())
-                    }
-                    Err(e) => {
-                        println!("等待游戏进程时出错: {}", e);
-                        Err(e.to_string())
-                    }
-                }
-            }
-            Err(e) => {
-                println!("游戏启动失败: {}", e);
-                Err(e.to_string())
-            }
-        }
+        manager
+            .spawn(command, &self.version_id, self.game_dir.clone(), sink)
+            .map_err(|e| {
+                log::error!("游戏启动失败: {}", e);
+                e
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_java_argument_overrides_settings() {
+        let mut settings = LauncherSettings::default();
+        settings.java.java_version = Some("17".to_string());
+        settings.java.java_path = Some("/opt/jdk17/bin/java".to_string());
+
+        assert_eq!(
+            StartGame::resolve_java_version("21".to_string(), &settings),
+            "21"
+        );
+        assert_eq!(StartGame::settings_java_path("21", &settings), None);
+        assert_eq!(
+            StartGame::resolve_java_version(" ".to_string(), &settings),
+            "17"
+        );
+        assert_eq!(
+            StartGame::settings_java_path("", &settings).as_deref(),
+            Some("/opt/jdk17/bin/java")
+        );
     }
 }
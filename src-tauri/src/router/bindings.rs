@@ -0,0 +1,452 @@
+/*
+RTLauncher, a third-party Minecraft launcher built with the newest
+technology and provides innovative funtionalities
+Copyright (C) 2025 lutouna
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+// ***
+// 根据命令注册表和 Rust 签名生成 TypeScript 绑定
+// 更新绑定: RTL_UPDATE_BINDINGS=1 cargo test bindings
+// ***
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use syn::{Attribute, Fields, FnArg, GenericArgument, Item, PathArguments, ReturnType, Type};
+
+// 绑定文件相对 src-tauri 的位置
+const BINDINGS_PATH: &str = "../src/bindings.ts";
+
+// 由 Tauri 注入、不需要前端传入的参数类型
+const INJECTED_TYPES: [&str; 5] = ["State", "AppHandle", "Window", "WebviewWindow", "Webview"];
+
+// 注册表中的一条命令
+struct CommandInfo {
+    domain: &'static str,
+    path: String,
+}
+
+macro_rules! command_list {
+    ($($domain:ident: [$($command:path),* $(,)?]),* $(,)?) => {
+        vec![$($(CommandInfo {
+            domain: stringify!($domain),
+            path: stringify!($command).replace(' ', ""),
+        },)*)*]
+    };
+}
+
+fn commands() -> Vec<CommandInfo> {
+    super::router::command_registry!(command_list)
+}
+
+// 从 crate::a::b::c 得到源文件 src/a/b.rs 或 src/a/b/mod.rs
+fn source_file(src_dir: &Path, path: &str) -> PathBuf {
+    let segments: Vec<&str> = path.split("::").skip(1).collect();
+    let module = &segments[..segments.len() - 1];
+    let file = src_dir.join(module.join("/")).with_extension("rs");
+    if file.exists() {
+        file
+    } else {
+        src_dir.join(module.join("/")).join("mod.rs")
+    }
+}
+
+fn parse_file(path: &Path) -> syn::File {
+    let content = std::fs::read_to_string(path).unwrap();
+    syn::parse_file(&content).unwrap()
+}
+
+// snake_case 转 camelCase，与 Tauri 的参数命名一致
+fn camel_case(name: &str) -> String {
+    let mut result = String::new();
+    let mut upper = false;
+    for c in name.chars() {
+        if c == '_' {
+            upper = true;
+        } else if upper {
+            result.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+// 按 serde 的 rename_all 规则重命名
+fn rename(name: &str, rule: Option<&str>) -> String {
+    let snake = || {
+        let mut result = String::new();
+        for (i, c) in name.chars().enumerate() {
+            if c.is_uppercase() && i > 0 {
+                result.push('_');
+            }
+            result.extend(c.to_lowercase());
+        }
+        result
+    };
+    match rule {
+        Some("lowercase") => name.to_lowercase(),
+        Some("UPPERCASE") => name.to_uppercase(),
+        Some("snake_case") => snake(),
+        Some("SCREAMING_SNAKE_CASE") => snake().to_uppercase(),
+        Some("kebab-case") => snake().replace('_', "-"),
+        Some("camelCase") => camel_case(&snake()),
+        _ => name.to_string(),
+    }
+}
+
+// 读取 #[serde(...)] 中的键值与标记
+#[derive(Default)]
+struct SerdeAttrs {
+    values: HashMap<String, String>,
+    flags: HashSet<String>,
+}
+
+impl SerdeAttrs {
+    fn parse(attrs: &[Attribute]) -> Self {
+        let mut result = Self::default();
+        for attr in attrs.iter().filter(|a| a.path().is_ident("serde")) {
+            let _ = attr.parse_nested_meta(|meta| {
+                let key = meta
+                    .path
+                    .get_ident()
+                    .map(|i| i.to_string())
+                    .unwrap_or_default();
+                if meta.input.peek(syn::Token![=]) {
+                    let value: syn::LitStr = meta.value()?.parse()?;
+                    result.values.insert(key, value.value());
+                } else {
+                    result.flags.insert(key);
+                }
+                Ok(())
+            });
+        }
+        result
+    }
+
+    fn get(&self, key: &str) -> Option<&str> {
+        self.values.get(key).map(|s| s.as_str())
+    }
+
+    fn skipped(&self) -> bool {
+        self.flags.contains("skip") || self.flags.contains("skip_serializing")
+    }
+}
+
+struct Generator {
+    types: HashMap<String, Item>,
+    emitted: BTreeMap<String, String>,
+    pending: Vec<String>,
+}
+
+impl Generator {
+    fn new(src_dir: &Path) -> Self {
+        let mut types = HashMap::new();
+        for entry in walkdir::WalkDir::new(src_dir)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.path().extension().is_some_and(|ext| ext == "rs"))
+        {
+            for item in parse_file(entry.path()).items {
+                let name = match &item {
+                    Item::Struct(item) => item.ident.to_string(),
+                    Item::Enum(item) => item.ident.to_string(),
+                    _ => continue,
+                };
+                types.entry(name).or_insert(item);
+            }
+        }
+        Self {
+            types,
+            emitted: BTreeMap::new(),
+            pending: Vec::new(),
+        }
+    }
+
+    fn ts_type(&mut self, ty: &Type) -> String {
+        match ty {
+            Type::Reference(r) => self.ts_type(&r.elem),
+            Type::Tuple(t) if t.elems.is_empty() => "null".to_string(),
+            Type::Tuple(t) => {
+                let elems: Vec<String> = t.elems.iter().map(|e| self.ts_type(e)).collect();
+                format!("[{}]", elems.join(", "))
+            }
+            Type::Slice(s) => format!("{}[]", self.ts_type(&s.elem)),
+            Type::Path(p) => {
+                let segment = p.path.segments.last().unwrap();
+                let name = segment.ident.to_string();
+                let args: Vec<&Type> = match &segment.arguments {
+                    PathArguments::AngleBracketed(a) => a
+                        .args
+                        .iter()
+                        .filter_map(|arg| match arg {
+                            GenericArgument::Type(t) => Some(t),
+                            _ => None,
+                        })
+                        .collect(),
+                    _ => Vec::new(),
+                };
+                match name.as_str() {
+                    "String" | "str" | "char" | "PathBuf" | "Path" => "string".to_string(),
+                    "bool" => "boolean".to_string(),
+                    "u8" | "u16" | "u32" | "u64" | "usize" | "i8" | "i16" | "i32" | "i64"
+                    | "isize" | "f32" | "f64" => "number".to_string(),
+                    "Value" => "unknown".to_string(),
+                    "Option" => format!("{} | null", self.ts_type(args[0])),
+                    "Vec" | "VecDeque" | "HashSet" | "BTreeSet" => {
+                        let inner = self.ts_type(args[0]);
+                        if inner.contains(' ') {
+                            format!("({})[]", inner)
+                        } else {
+                            format!("{}[]", inner)
+                        }
+                    }
+                    "HashMap" | "BTreeMap" => {
+                        format!("Record<string, {}>", self.ts_type(args[1]))
+                    }
+                    "Box" | "Arc" | "Rc" => self.ts_type(args[0]),
+                    "Result" | "LauncherResult" => self.ts_type(args[0]),
+                    _ => {
+                        if !self.emitted.contains_key(&name) && !self.pending.contains(&name) {
+                            self.pending.push(name.clone());
+                        }
+                        name
+                    }
+                }
+            }
+            _ => "unknown".to_string(),
+        }
+    }
+
+    // 生成所有被引用到的类型定义
+    fn emit_pending(&mut self) {
+        while let Some(name) = self.pending.pop() {
+            let item = self
+                .types
+                .get(&name)
+                .cloned()
+                .unwrap_or_else(|| panic!("找不到类型定义: {}", name));
+            let definition = match &item {
+                Item::Struct(item) => self.struct_definition(&name, &item.attrs, &item.fields),
+                Item::Enum(item) => self.enum_definition(&name, item),
+                _ => unreachable!(),
+            };
+            self.emitted.insert(name, definition);
+        }
+    }
+
+    fn fields_object(&mut self, fields: &Fields, rule: Option<&str>) -> String {
+        let mut lines = Vec::new();
+        for field in fields.iter() {
+            let serde = SerdeAttrs::parse(&field.attrs);
+            if serde.skipped() {
+                continue;
+            }
+            let ident = field.ident.as_ref().unwrap().to_string();
+            let name = serde
+                .get("rename")
+                .map(|s| s.to_string())
+                .unwrap_or_else(|| rename(&ident, rule));
+            lines.push(format!("  {}: {};", name, self.ts_type(&field.ty)));
+        }
+        format!("{{\n{}\n}}", lines.join("\n"))
+    }
+
+    fn struct_definition(&mut self, name: &str, attrs: &[Attribute], fields: &Fields) -> String {
+        let serde = SerdeAttrs::parse(attrs);
+        match fields {
+            Fields::Named(_) => format!(
+                "export interface {} {}",
+                name,
+                self.fields_object(fields, serde.get("rename_all"))
+            ),
+            Fields::Unnamed(unnamed) if unnamed.unnamed.len() == 1 => {
+                let inner = self.ts_type(&unnamed.unnamed[0].ty);
+                format!("export type {} = {};", name, inner)
+            }
+            _ => format!("export type {} = null;", name),
+        }
+    }
+
+    fn enum_definition(&mut self, name: &str, item: &syn::ItemEnum) -> String {
+        let serde = SerdeAttrs::parse(&item.attrs);
+        let rule = serde.get("rename_all").map(|s| s.to_string());
+        let tag = serde.get("tag").map(|s| s.to_string());
+        let mut variants = Vec::new();
+        for variant in &item.variants {
+            let variant_serde = SerdeAttrs::parse(&variant.attrs);
+            if variant_serde.skipped() {
+                continue;
+            }
+            let variant_name = variant_serde
+                .get("rename")
+                .map(|s| s.to_string())
+                .unwrap_or_else(|| rename(&variant.ident.to_string(), rule.as_deref()));
+            let ts = match (&variant.fields, &tag) {
+                (Fields::Unit, None) => format!("\"{}\"", variant_name),
+                (Fields::Unit, Some(tag)) => format!("{{ {}: \"{}\" }}", tag, variant_name),
+                (Fields::Named(_), Some(tag)) => format!(
+                    "({{ {}: \"{}\" }} & {})",
+                    tag,
+                    variant_name,
+                    self.fields_object(&variant.fields, None)
+                ),
+                (Fields::Named(_), None) => format!(
+                    "{{ {}: {} }}",
+                    variant_name,
+                    self.fields_object(&variant.fields, None)
+                ),
+                (Fields::Unnamed(unnamed), _) => {
+                    let inner = self.ts_type(&unnamed.unnamed[0].ty);
+                    format!("{{ {}: {} }}", variant_name, inner)
+                }
+            };
+            variants.push(ts);
+        }
+        format!("export type {} =\n  | {};", name, variants.join("\n  | "))
+    }
+}
+
+// LauncherError 手动实现了序列化，错误码取自 code() 的返回值
+fn launcher_error_definition(src_dir: &Path) -> String {
+    let content = std::fs::read_to_string(src_dir.join("utils/error.rs")).unwrap();
+    let body = content
+        .split("pub fn code(&self)")
+        .nth(1)
+        .and_then(|s| s.split("pub fn message").next())
+        .unwrap();
+    let codes: Vec<String> = body
+        .split('"')
+        .skip(1)
+        .step_by(2)
+        .map(|code| format!("\"{}\"", code))
+        .collect();
+    format!(
+        "export type LauncherErrorCode =\n  | {};\n\nexport interface LauncherError {{\n  code: LauncherErrorCode;\n  message: string;\n  details: unknown;\n}}",
+        codes.join("\n  | ")
+    )
+}
+
+pub fn generate(src_dir: &Path) -> String {
+    let mut generator = Generator::new(src_dir);
+    // LauncherError 单独生成
+    generator.emitted.insert(
+        "LauncherError".to_string(),
+        launcher_error_definition(src_dir),
+    );
+
+    let mut functions = String::new();
+    let mut current_domain = "";
+    for command in commands() {
+        let name = command.path.rsplit("::").next().unwrap().to_string();
+        let file = parse_file(&source_file(src_dir, &command.path));
+        let function = file
+            .items
+            .iter()
+            .find_map(|item| match item {
+                Item::Fn(f) if f.sig.ident == name => Some(f.clone()),
+                _ => None,
+            })
+            .unwrap_or_else(|| panic!("找不到命令: {}", command.path));
+
+        let mut params = Vec::new();
+        for input in &function.sig.inputs {
+            let FnArg::Typed(arg) = input else { continue };
+            if let Type::Path(p) = arg.ty.as_ref() {
+                let ident = p.path.segments.last().unwrap().ident.to_string();
+                if INJECTED_TYPES.contains(&ident.as_str()) {
+                    continue;
+                }
+            }
+            let syn::Pat::Ident(pat) = arg.pat.as_ref() else {
+                continue;
+            };
+            // Option 参数可以省略
+            let ty = generator.ts_type(&arg.ty);
+            let optional = if ty.ends_with(" | null") { "?" } else { "" };
+            params.push(format!(
+                "{}{}: {}",
+                camel_case(&pat.ident.to_string()),
+                optional,
+                ty
+            ));
+        }
+        let output = match &function.sig.output {
+            ReturnType::Default => "void".to_string(),
+            ReturnType::Type(_, ty) => match generator.ts_type(ty).as_str() {
+                "null" => "void".to_string(),
+                ty => ty.to_string(),
+            },
+        };
+
+        if command.domain != current_domain {
+            current_domain = command.domain;
+            functions.push_str(&format!("// {}\n", current_domain));
+        }
+        let ts_name = camel_case(&name);
+        if params.is_empty() {
+            functions.push_str(&format!(
+                "export function {}(): Promise<{}> {{\n  return invoke(\"{}\");\n}}\n\n",
+                ts_name, output, name
+            ));
+        } else {
+            functions.push_str(&format!(
+                "export function {}(args: {{ {} }}): Promise<{}> {{\n  return invoke(\"{}\", args);\n}}\n\n",
+                ts_name,
+                params.join("; "),
+                output,
+                name
+            ));
+        }
+    }
+    generator.emit_pending();
+
+    let mut output = String::from(
+        "// 此文件由 src-tauri/src/router/bindings.rs 生成，请勿手动修改\n\
+         // 更新: RTL_UPDATE_BINDINGS=1 cargo test bindings\n\n\
+         import { invoke } from \"@tauri-apps/api/core\";\n\n",
+    );
+    output.push_str(functions.trim_end());
+    output.push_str("\n\n// 类型\n");
+    for definition in generator.emitted.values() {
+        output.push('\n');
+        output.push_str(definition);
+        output.push('\n');
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 检查绑定文件是否与 Rust 签名一致
+    #[test]
+    fn test_bindings_up_to_date() {
+        let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let bindings = generate(&manifest_dir.join("src"));
+        let path = manifest_dir.join(BINDINGS_PATH);
+
+        if std::env::var_os("RTL_UPDATE_BINDINGS").is_some() {
+            std::fs::write(&path, &bindings).unwrap();
+        }
+        let current = std::fs::read_to_string(&path).unwrap_or_default();
+        assert!(
+            current == bindings,
+            "TypeScript 绑定已过期，请运行 RTL_UPDATE_BINDINGS=1 cargo test bindings"
+        );
+    }
+}
@@ -1 +1,132 @@
+/*
+RTLauncher, a third-party Minecraft launcher built with the newest
+technology and provides innovative funtionalities
+Copyright (C) 2025 lutouna
 
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+// ***
+// 命令注册表，main.rs 与 lib.rs 共用
+// ***
+
+// 所有前端可调用的命令，按领域分组
+// 新增命令只需在这里登记，invoke handler 与 TypeScript 绑定都由此生成
+macro_rules! command_registry {
+    ($($callback:tt)*) => {
+        $($callback)*! {
+            auth: [
+                crate::api::login::get_code,
+            ],
+            versions: [
+                crate::module::download::dwl_main::get_version_manifest,
+                crate::module::download::dwl_main::dwl_version_manifest,
+                crate::module::download::verify::verify_version,
+                crate::module::download::installed::list_installed_versions,
+                crate::module::download::manifest::get_network_status,
+            ],
+            loaders: [
+                crate::module::loader::fabric::get_fabric_game_versions,
+                crate::module::loader::fabric::get_fabric_loader_versions,
+                crate::module::loader::fabric::install_fabric,
+                crate::module::loader::quilt::get_quilt_game_versions,
+                crate::module::loader::quilt::get_quilt_loader_versions,
+                crate::module::loader::quilt::install_quilt,
+                crate::module::loader::forge::get_forge_versions,
+                crate::module::loader::forge::install_forge,
+                crate::module::loader::optifine::get_optifine_versions,
+                crate::module::loader::optifine::install_optifine,
+                crate::module::loader::optifine::install_optifine_mod,
+            ],
+            instances: [
+                crate::module::instance::ins_main::list_instances,
+                crate::module::instance::ins_main::get_instance,
+                crate::module::instance::ins_main::create_instance,
+                crate::module::instance::ins_main::update_instance,
+                crate::module::instance::ins_main::delete_instance,
+            ],
+            game: [
+                crate::module::start_game::stg_main::stg,
+                crate::module::start_game::process::list_game_processes,
+                crate::module::start_game::process::get_game_process,
+                crate::module::start_game::process::get_game_logs,
+                crate::module::start_game::process::kill_game,
+                crate::module::start_game::process::clear_finished_games,
+                crate::module::start_game::process::get_crash_report,
+                crate::module::start_game::process::export_crash_report,
+                crate::utils::export_bat::export_bat,
+                crate::utils::export_bat::export_script,
+            ],
+            java: [
+                crate::utils::get_java_path::get_java_path,
+            ],
+            content: [
+                crate::module::content::search_content,
+                crate::module::content::search_instance_content,
+                crate::module::content::get_content_project,
+                crate::module::content::get_content_versions,
+                crate::module::content::get_instance_content_versions,
+                crate::module::content::get_content_files,
+                crate::module::content::download_content,
+                crate::module::content::dependency::plan_content_install,
+                crate::module::content::dependency::install_content,
+                crate::module::content::scanner::scan_instance_mods,
+                crate::module::content::scanner::get_mod_icon,
+                crate::module::content::update::check_content_updates,
+                crate::module::content::update::update_content,
+                crate::module::content::update::update_all_content,
+                crate::module::content::manage::list_local_content,
+                crate::module::content::manage::set_content_enabled,
+                crate::module::content::manage::delete_content,
+                crate::module::content::manage::import_content,
+                crate::module::content::manage::list_worlds,
+                crate::module::modpack::mrpack::import_mrpack,
+                crate::module::modpack::curseforge::import_curseforge_pack,
+                crate::module::modpack::multimc::import_multimc_instance,
+                crate::module::modpack::export::list_export_entries,
+                crate::module::modpack::export::export_modpack,
+            ],
+            settings: [
+                crate::Setting::config::get_settings,
+                crate::Setting::config::set_settings,
+                crate::Setting::config::reset_settings,
+                crate::Setting::config::get_effective_settings,
+            ],
+            logs: [
+                crate::log::logger::get_launcher_logs,
+                crate::log::logger::get_log_level,
+                crate::log::logger::set_log_level,
+                crate::log::logger::get_log_dir,
+            ],
+        }
+    };
+}
+
+// 展开为 tauri::generate_handler!
+macro_rules! generate_handler {
+    ($($domain:ident: [$($command:path),* $(,)?]),* $(,)?) => {
+        tauri::generate_handler![$($($command,)*)*]
+    };
+}
+
+// 构建注册了全部命令的 invoke handler
+macro_rules! invoke_handler {
+    () => {
+        $crate::router::router::command_registry!($crate::router::router::generate_handler)
+    };
+}
+
+pub(crate) use command_registry;
+pub(crate) use generate_handler;
+pub(crate) use invoke_handler;
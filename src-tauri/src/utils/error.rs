@@ -0,0 +1,295 @@
+/*
+RTLauncher, a third-party Minecraft launcher built with the newest
+technology and provides innovative funtionalities
+Copyright (C) 2025 lutouna
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+// ***
+// 统一错误类型，前端通过 code 判断错误种类
+// ***
+
+use crate::Setting::config::{self, SettingsError};
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+use serde_json::{json, Value};
+use std::fmt;
+use std::path::Path;
+
+pub type LauncherResult<T> = Result<T, LauncherError>;
+
+#[derive(Debug)]
+pub enum LauncherError {
+    Network {
+        url: String,
+        status: Option<u16>,
+        reason: String,
+    },
+    HashMismatch {
+        path: String,
+        expected: String,
+        actual: String,
+    },
+    Io {
+        path: Option<String>,
+        reason: String,
+    },
+    Auth(String),
+    JavaNotFound(String),
+    VersionNotFound(String),
+    InstanceNotFound(String),
+    ProcessNotFound(String),
+    InvalidSettings(Vec<SettingsError>),
+    InvalidArgument(String),
+    Parse(String),
+    Unsupported(String),
+    Other(String),
+}
+
+impl LauncherError {
+    pub fn network(url: &str, reason: impl ToString) -> Self {
+        Self::Network {
+            url: url.to_string(),
+            status: None,
+            reason: reason.to_string(),
+        }
+    }
+
+    pub fn io(path: &Path, error: std::io::Error) -> Self {
+        Self::Io {
+            path: Some(path.to_string_lossy().to_string()),
+            reason: error.to_string(),
+        }
+    }
+
+    // 稳定的错误码，不随语言变化
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Network { .. } => "network",
+            Self::HashMismatch { .. } => "hash_mismatch",
+            Self::Io { .. } => "io",
+            Self::Auth(_) => "auth",
+            Self::JavaNotFound(_) => "java_not_found",
+            Self::VersionNotFound(_) => "version_not_found",
+            Self::InstanceNotFound(_) => "instance_not_found",
+            Self::ProcessNotFound(_) => "process_not_found",
+            Self::InvalidSettings(_) => "invalid_settings",
+            Self::InvalidArgument(_) => "invalid_argument",
+            Self::Parse(_) => "parse",
+            Self::Unsupported(_) => "unsupported",
+            Self::Other(_) => "other",
+        }
+    }
+
+    // 按语言生成提示信息，目前支持 zh-CN 与 en-US
+    pub fn message(&self, language: &str) -> String {
+        let english = language.starts_with("en");
+        let text = |zh: String, en: String| if english { en } else { zh };
+        match self {
+            Self::Network { url, .. } => text(
+                format!("网络请求失败: {}", url),
+                format!("Network request failed: {}", url),
+            ),
+            Self::HashMismatch { path, .. } => text(
+                format!("文件校验失败: {}", path),
+                format!("Hash mismatch: {}", path),
+            ),
+            Self::Io {
+                path: Some(path), ..
+            } => text(
+                format!("文件读写失败: {}", path),
+                format!("File operation failed: {}", path),
+            ),
+            Self::Io { path: None, reason } => text(
+                format!("文件读写失败: {}", reason),
+                format!("File operation failed: {}", reason),
+            ),
+            Self::Auth(reason) => text(
+                format!("登录失败: {}", reason),
+                format!("Sign-in failed: {}", reason),
+            ),
+            Self::JavaNotFound(version) => text(
+                format!("未找到 Java {}", version),
+                format!("Java {} not found", version),
+            ),
+            Self::VersionNotFound(version) => text(
+                format!("版本不存在: {}", version),
+                format!("Version not found: {}", version),
+            ),
+            Self::InstanceNotFound(id) => text(
+                format!("实例不存在: {}", id),
+                format!("Instance not found: {}", id),
+            ),
+            Self::ProcessNotFound(id) => text(
+                format!("游戏进程不存在: {}", id),
+                format!("Game process not found: {}", id),
+            ),
+            Self::InvalidSettings(errors) => text(
+                format!("设置无效，共 {} 处错误", errors.len()),
+                format!("Invalid settings ({} errors)", errors.len()),
+            ),
+            Self::InvalidArgument(reason) => text(
+                format!("参数无效: {}", reason),
+                format!("Invalid argument: {}", reason),
+            ),
+            Self::Parse(reason) => text(
+                format!("数据解析失败: {}", reason),
+                format!("Failed to parse data: {}", reason),
+            ),
+            Self::Unsupported(reason) => text(
+                format!("不支持的操作: {}", reason),
+                format!("Unsupported: {}", reason),
+            ),
+            Self::Other(reason) => reason.clone(),
+        }
+    }
+
+    // 附加信息，供前端展示或排查
+    pub fn details(&self) -> Value {
+        match self {
+            Self::Network {
+                url,
+                status,
+                reason,
+            } => json!({ "url": url, "status": status, "reason": reason }),
+            Self::HashMismatch {
+                path,
+                expected,
+                actual,
+            } => json!({ "path": path, "expected": expected, "actual": actual }),
+            Self::Io { path, reason } => json!({ "path": path, "reason": reason }),
+            Self::InvalidSettings(errors) => json!({ "errors": errors }),
+            Self::JavaNotFound(version) => json!({ "version": version }),
+            Self::VersionNotFound(id) | Self::InstanceNotFound(id) | Self::ProcessNotFound(id) => {
+                json!({ "id": id })
+            }
+            Self::Auth(reason)
+            | Self::InvalidArgument(reason)
+            | Self::Parse(reason)
+            | Self::Unsupported(reason)
+            | Self::Other(reason) => json!({ "reason": reason }),
+        }
+    }
+}
+
+impl fmt::Display for LauncherError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = self.message("zh-CN");
+        match self {
+            Self::Network { reason, .. } | Self::Io { reason, .. } => {
+                write!(f, "{} ({})", message, reason)
+            }
+            Self::HashMismatch {
+                expected, actual, ..
+            } => write!(f, "{}，期望：{}，实际：{}", message, expected, actual),
+            _ => f.write_str(&message),
+        }
+    }
+}
+
+impl std::error::Error for LauncherError {}
+
+// 序列化为 { code, message, details }
+impl Serialize for LauncherError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("LauncherError", 3)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.message(&config::current().language))?;
+        state.serialize_field("details", &self.details())?;
+        state.end()
+    }
+}
+
+impl From<std::io::Error> for LauncherError {
+    fn from(error: std::io::Error) -> Self {
+        Self::Io {
+            path: None,
+            reason: error.to_string(),
+        }
+    }
+}
+
+impl From<reqwest::Error> for LauncherError {
+    fn from(error: reqwest::Error) -> Self {
+        Self::Network {
+            url: error.url().map(|u| u.to_string()).unwrap_or_default(),
+            status: error.status().map(|s| s.as_u16()),
+            reason: error.to_string(),
+        }
+    }
+}
+
+impl From<serde_json::Error> for LauncherError {
+    fn from(error: serde_json::Error) -> Self {
+        Self::Parse(error.to_string())
+    }
+}
+
+impl From<zip::result::ZipError> for LauncherError {
+    fn from(error: zip::result::ZipError) -> Self {
+        match error {
+            zip::result::ZipError::Io(e) => e.into(),
+            e => Self::Parse(e.to_string()),
+        }
+    }
+}
+
+impl From<tokio::task::JoinError> for LauncherError {
+    fn from(error: tokio::task::JoinError) -> Self {
+        Self::Other(error.to_string())
+    }
+}
+
+impl From<Vec<SettingsError>> for LauncherError {
+    fn from(errors: Vec<SettingsError>) -> Self {
+        Self::InvalidSettings(errors)
+    }
+}
+
+impl From<String> for LauncherError {
+    fn from(reason: String) -> Self {
+        Self::Other(reason)
+    }
+}
+
+impl From<&str> for LauncherError {
+    fn from(reason: &str) -> Self {
+        Self::Other(reason.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serialize_error() {
+        let error = LauncherError::HashMismatch {
+            path: "assets/objects/ab/abcd".to_string(),
+            expected: "abcd".to_string(),
+            actual: "ef01".to_string(),
+        };
+        let value = serde_json::to_value(&error).unwrap();
+        assert_eq!(value["code"], "hash_mismatch");
+        assert_eq!(value["details"]["expected"], "abcd");
+        assert!(value["message"].as_str().unwrap().contains("abcd"));
+
+        assert_eq!(
+            LauncherError::JavaNotFound("21".to_string()).message("en-US"),
+            "Java 21 not found"
+        );
+        let io: LauncherError = std::io::Error::new(std::io::ErrorKind::NotFound, "missing").into();
+        assert_eq!(io.code(), "io");
+    }
+}
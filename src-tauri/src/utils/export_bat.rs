@@ -18,12 +18,307 @@ along with this program.  If not, see <https://www.gnu.org/licenses/>.
 */
 
 // ***
-// 导出启动脚本的功能，生成一个 .bat 文件供启动游戏使用
+// 导出启动脚本的功能，支持 .bat / .ps1 / .sh / .command
 // ***
 
+use crate::module::instance::ins_main::InstanceStore;
 use crate::module::start_game::stg_main::StartGame;
+use crate::utils::error::{LauncherError, LauncherResult};
+use serde::Deserialize;
 use std::fs::File;
 use std::io::Write;
+use std::path::Path;
+
+// 脚本中保存令牌的环境变量
+const TOKEN_VARIABLE: &str = "RTL_ACCESS_TOKEN";
+
+// 脚本格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScriptFormat {
+    Bat,
+    Ps1,
+    Sh,
+    Command,
+}
+
+impl ScriptFormat {
+    // 根据文件扩展名推断格式
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_lowercase().as_str() {
+            "bat" | "cmd" => Some(Self::Bat),
+            "ps1" => Some(Self::Ps1),
+            "sh" => Some(Self::Sh),
+            "command" => Some(Self::Command),
+            _ => None,
+        }
+    }
+}
+
+// 访问令牌的处理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TokenMode {
+    #[default]
+    Plain, // 明文写入
+    Placeholder, // 从环境变量读取
+    Prompt,      // 运行时提示输入
+}
+
+// 启动参数，令牌单独标记以便替换
+#[derive(Debug, Clone, PartialEq)]
+enum ScriptArg {
+    Literal(String),
+    Token,
+}
+
+// 启动脚本
+pub struct LaunchScript {
+    java_path: String,
+    args: Vec<ScriptArg>,
+    access_token: String,
+    working_dir: String,
+}
+
+impl LaunchScript {
+    pub fn new(java_path: &str, launch_args: &[String], working_dir: &str) -> Self {
+        let mut args = Vec::new();
+        let mut access_token = String::new();
+        let mut is_token = false;
+        for arg in launch_args {
+            if is_token {
+                access_token = arg.clone();
+                args.push(ScriptArg::Token);
+            } else {
+                args.push(ScriptArg::Literal(arg.clone()));
+            }
+            is_token = arg == "--accessToken";
+        }
+
+        Self {
+            java_path: java_path.to_string(),
+            args,
+            access_token,
+            working_dir: working_dir.to_string(),
+        }
+    }
+
+    // 生成脚本内容
+    pub fn render(&self, format: ScriptFormat, token_mode: TokenMode) -> String {
+        let quote: fn(&str) -> String = match format {
+            ScriptFormat::Bat => quote_cmd,
+            ScriptFormat::Ps1 => quote_powershell,
+            ScriptFormat::Sh | ScriptFormat::Command => quote_posix,
+        };
+        let token = match (token_mode, format) {
+            (TokenMode::Plain, _) => quote(&self.access_token),
+            (_, ScriptFormat::Bat) => format!("\"%{}%\"", TOKEN_VARIABLE),
+            (_, ScriptFormat::Ps1) => format!("$env:{}", TOKEN_VARIABLE),
+            (_, _) => format!("\"${}\"", TOKEN_VARIABLE),
+        };
+        let args: Vec<String> = self
+            .args
+            .iter()
+            .map(|arg| match arg {
+                ScriptArg::Literal(value) => quote(value),
+                ScriptArg::Token => token.clone(),
+            })
+            .collect();
+        let command = format!("{} {}", quote(&self.java_path), args.join(" "));
+
+        let mut lines = Vec::new();
+        match format {
+            ScriptFormat::Bat => {
+                lines.push("@echo off".to_string());
+                lines.push("chcp 65001 > nul".to_string());
+                lines.push(format!("cd /d {}", quote_cmd(&self.working_dir)));
+                match token_mode {
+                    TokenMode::Plain => {}
+                    TokenMode::Placeholder => lines.push(format!(
+                        "if not defined {0} (echo 请先设置环境变量 {0} & pause & exit /b 1)",
+                        TOKEN_VARIABLE
+                    )),
+                    TokenMode::Prompt => {
+                        lines.push(format!("set /p {}=请输入访问令牌: ", TOKEN_VARIABLE))
+                    }
+                }
+                lines.push(command);
+                lines.push("pause".to_string());
+            }
+            ScriptFormat::Ps1 => {
+                lines.push("$ErrorActionPreference = 'Stop'".to_string());
+                lines.push(format!(
+                    "Set-Location -LiteralPath {}",
+                    quote_powershell(&self.working_dir)
+                ));
+                match token_mode {
+                    TokenMode::Plain => {}
+                    TokenMode::Placeholder => lines.push(format!(
+                        "if (-not $env:{0}) {{ Write-Error '请先设置环境变量 {0}'; exit 1 }}",
+                        TOKEN_VARIABLE
+                    )),
+                    TokenMode::Prompt => lines.push(format!(
+                        "$env:{} = Read-Host -Prompt '请输入访问令牌'",
+                        TOKEN_VARIABLE
+                    )),
+                }
+                lines.push(format!("& {}", command));
+            }
+            ScriptFormat::Sh | ScriptFormat::Command => {
+                lines.push("#!/bin/sh".to_string());
+                lines.push(format!("cd {} || exit 1", quote_posix(&self.working_dir)));
+                match token_mode {
+                    TokenMode::Plain => {}
+                    TokenMode::Placeholder => lines.push(format!(
+                        ": \"${{{0}:?请先设置环境变量 {0}}}\"",
+                        TOKEN_VARIABLE
+                    )),
+                    TokenMode::Prompt => {
+                        lines.push("printf '请输入访问令牌: '".to_string());
+                        lines.push("stty -echo 2>/dev/null".to_string());
+                        lines.push(format!("read -r {}", TOKEN_VARIABLE));
+                        lines.push("stty echo 2>/dev/null".to_string());
+                        lines.push("echo".to_string());
+                    }
+                }
+                lines.push(format!("exec {}", command));
+            }
+        }
+
+        let newline = match format {
+            ScriptFormat::Bat | ScriptFormat::Ps1 => "\r\n",
+            ScriptFormat::Sh | ScriptFormat::Command => "\n",
+        };
+        let mut content = lines.join(newline);
+        content.push_str(newline);
+        content
+    }
+
+    // 写入文件，sh 和 command 会加上可执行权限
+    pub fn write(
+        &self,
+        output_path: &Path,
+        format: ScriptFormat,
+        token_mode: TokenMode,
+    ) -> std::io::Result<()> {
+        let content = self.render(format, token_mode);
+        let mut file = File::create(output_path)?;
+        if format == ScriptFormat::Ps1 {
+            // Windows PowerShell 5 需要 BOM 才能识别 UTF-8
+            file.write_all("\u{feff}".as_bytes())?;
+        }
+        file.write_all(content.as_bytes())?;
+
+        #[cfg(unix)]
+        if matches!(format, ScriptFormat::Sh | ScriptFormat::Command) {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(output_path, std::fs::Permissions::from_mode(0o755))?;
+        }
+        Ok(())
+    }
+}
+
+// POSIX shell：单引号包裹，内部单引号写作 '\''
+fn quote_posix(arg: &str) -> String {
+    let is_safe = !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./:=@%+,".contains(c));
+    if is_safe {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', "'\\''"))
+    }
+}
+
+// PowerShell：单引号包裹，内部单引号写作 ''
+fn quote_powershell(arg: &str) -> String {
+    format!("'{}'", arg.replace('\'', "''"))
+}
+
+// cmd.exe：有特殊字符时整体用引号包裹，内部引号写作 ""，% 写作 %%
+// cmd 中每个引号都会切换引号状态，"" 保证 & | > 等字符始终在引号内
+fn quote_cmd(arg: &str) -> String {
+    let needs_quote = arg.is_empty()
+        || arg
+            .chars()
+            .any(|c| c.is_whitespace() || "\"&|<>^(),;=!".contains(c));
+    if !needs_quote {
+        return arg.replace('%', "%%");
+    }
+
+    let mut escaped = String::new();
+    let mut backslashes = 0;
+    for c in arg.chars() {
+        match c {
+            '\\' => backslashes += 1,
+            '"' => {
+                // 引号前的反斜杠需要加倍，否则会转义引号
+                escaped.push_str(&"\\".repeat(backslashes * 2));
+                escaped.push_str("\"\"");
+                backslashes = 0;
+            }
+            _ => {
+                escaped.push_str(&"\\".repeat(backslashes));
+                escaped.push(c);
+                backslashes = 0;
+            }
+        }
+    }
+    // 结尾的反斜杠会转义右引号，需要加倍
+    escaped.push_str(&"\\".repeat(backslashes * 2));
+    format!("\"{}\"", escaped.replace('%', "%%"))
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn export_script(
+    startup_parameter: String,
+    version_id: String,
+    java_version: String,
+    output_path: String,
+    asset_index_id: String,
+    username: String,
+    format: Option<ScriptFormat>,
+    token_mode: Option<TokenMode>,
+    instance_id: Option<String>,
+) -> LauncherResult<String> {
+    let format = format
+        .or_else(|| ScriptFormat::from_path(Path::new(&output_path)))
+        .ok_or_else(|| LauncherError::InvalidArgument("无法识别的脚本格式".to_string()))?;
+    // 指定实例时使用实例的游戏目录和配置
+    let start_game = match instance_id {
+        Some(instance_id) => StartGame::from_instance(
+            &InstanceStore::new().get(&instance_id)?,
+            startup_parameter,
+            java_version,
+            asset_index_id,
+            username,
+        )?,
+        None => StartGame::new(
+            startup_parameter,
+            version_id,
+            java_version,
+            asset_index_id,
+            username,
+        ),
+    };
+    let script = LaunchScript::new(
+        &start_game.java_path,
+        &start_game.launch_args,
+        &start_game.game_dir.to_string_lossy(),
+    );
+
+    script
+        .write(
+            Path::new(&output_path),
+            format,
+            token_mode.unwrap_or_default(),
+        )
+        .map_err(|e| LauncherError::io(Path::new(&output_path), e))?;
+
+    Ok("启动脚本已成功导出".to_string())
+}
 
 #[tauri::command]
 pub async fn export_bat(
@@ -33,32 +328,71 @@ pub async fn export_bat(
     output_path: String,
     asset_index_id: String,
     username: String,
-) -> Result<String, String> {
-    let start_game = StartGame::new(
+) -> LauncherResult<String> {
+    export_script(
         startup_parameter,
         version_id,
         java_version,
+        output_path,
         asset_index_id,
         username,
-    );
-    let full_command = format!(
-        "\"{}\" {}",
-        start_game.java_path,
-        start_game.launch_args.join(" ")
-    );
+        Some(ScriptFormat::Bat),
+        None,
+        None,
+    )
+    .await
+    .map(|_| "批处理文件已成功导出".to_string())
+}
 
-    // 生成 .bat 文件内容
-    let content = format!("@echo off\r\n{}\r\npause\r\n", full_command);
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    // 将内容写入指定的 .bat 文件
-    match File::create(&output_path) {
-        Ok(mut file) => {
-            if let Err(e) = file.write_all(content.as_bytes()) {
-                return Err(format!("写入文件失败: {}", e));
-            }
-        }
-        Err(e) => return Err(format!("创建文件失败: {}", e)),
+    #[test]
+    fn test_quote_args() {
+        assert_eq!(quote_posix("-Xmx2G"), "-Xmx2G");
+        assert_eq!(quote_posix("it's here"), "'it'\\''s here'");
+        assert_eq!(quote_posix(""), "''");
+        assert_eq!(quote_powershell("C:\\It's"), "'C:\\It''s'");
+        assert_eq!(quote_cmd("-Xmx2G"), "-Xmx2G");
+        assert_eq!(
+            quote_cmd("C:\\Program Files\\Java\\"),
+            "\"C:\\Program Files\\Java\\\\\""
+        );
+        assert_eq!(quote_cmd("say \"hi\" 100%"), "\"say \"\"hi\"\" 100%%\"");
+        // 引号与 & > 同时出现时不能让 & 落到引号外
+        assert_eq!(quote_cmd("a\" & del x > y"), "\"a\"\" & del x > y\"");
+        assert_eq!(quote_cmd("C:\\dir\\\"x"), "\"C:\\dir\\\\\"\"x\"");
     }
 
-    Ok("批处理文件已成功导出".to_string())
+    #[test]
+    fn test_render_with_token_placeholder() {
+        let args: Vec<String> = [
+            "-cp",
+            "a b.jar",
+            "--accessToken",
+            "secret",
+            "--gameDir",
+            "/tmp/mc",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+        let script = LaunchScript::new("/usr/bin/java", &args, "/home/steve/.minecraft");
+
+        let sh = script.render(ScriptFormat::Sh, TokenMode::Placeholder);
+        assert!(!sh.contains("secret"));
+        assert!(sh.contains("cd /home/steve/.minecraft || exit 1"));
+        assert!(sh.contains(
+            "exec /usr/bin/java -cp 'a b.jar' --accessToken \"$RTL_ACCESS_TOKEN\" --gameDir /tmp/mc"
+        ));
+
+        let bat = script.render(ScriptFormat::Bat, TokenMode::Plain);
+        assert!(bat.contains("--accessToken secret"));
+        assert!(bat.ends_with("pause\r\n"));
+
+        let ps1 = script.render(ScriptFormat::Ps1, TokenMode::Prompt);
+        assert!(ps1.contains("Read-Host"));
+        assert!(ps1.contains("--accessToken' $env:RTL_ACCESS_TOKEN"));
+    }
 }
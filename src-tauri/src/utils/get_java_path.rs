@@ -183,6 +183,31 @@ fn scan_for_java_installation() -> Vec<String> {
     paths
 }
 
+// 查找版本信息包含指定版本号的 java 可执行文件，版本号为空时返回找到的第一个
+pub fn find_java(java_version: &str) -> Option<String> {
+    get_java_path().iter().find_map(|path| {
+        let possible_paths = match OS {
+            "windows" => vec![
+                format!("{}\\bin\\java.exe", path),
+                format!("{}\\java.exe", path),
+                format!("{}\\javapath\\java.exe", path),
+            ],
+            _ => vec![format!("{}/bin/java", path), format!("{}/java", path)],
+        };
+
+        // 遍历所有可能的路径，检查Java版本
+        possible_paths
+            .into_iter()
+            .find(|p| java_version_info(p).is_some_and(|info| info.contains(java_version)))
+    })
+}
+
+// 获取java版本
+fn java_version_info(java_path: &str) -> Option<String> {
+    let output = Command::new(java_path).arg("-version").output().ok()?;
+    Some(String::from_utf8_lossy(&output.stderr).to_string())
+}
+
 // 测试
 #[cfg(test)]
 mod tests {
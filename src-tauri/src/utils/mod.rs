@@ -17,6 +17,7 @@ You should have received a copy of the GNU General Public License
 along with this program.  If not, see <https://www.gnu.org/licenses/>.
 */
 
+pub mod error;
 pub mod export_bat;
 pub mod get_java_path;
 pub mod request;
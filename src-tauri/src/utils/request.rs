@@ -21,8 +21,8 @@ along with this program.  If not, see <https://www.gnu.org/licenses/>.
 // 请求工具类
 // ***
 
+use super::error::{LauncherError, LauncherResult};
 use reqwest::Client;
-use std::error::Error;
 
 #[derive(Clone)]
 pub struct Request {
@@ -39,9 +39,19 @@ impl Request {
     }
 
     // 发送get请求
-    pub async fn fetch_get(&self) -> Result<String, Box<dyn Error + Send + Sync>> {
-        let response = reqwest::get(&self.url).await?;
-        let body = response.text().await?;
+    pub async fn fetch_get(&self) -> LauncherResult<String> {
+        let response = reqwest::get(&self.url)
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| LauncherError::Network {
+                url: self.url.clone(),
+                status: e.status().map(|s| s.as_u16()),
+                reason: e.to_string(),
+            })?;
+        let body = response
+            .text()
+            .await
+            .map_err(|e| LauncherError::network(&self.url, e))?;
         Ok(body)
     }
 
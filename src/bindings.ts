@@ -0,0 +1,696 @@
+// 此文件由 src-tauri/src/router/bindings.rs 生成，请勿手动修改
+// 更新: RTL_UPDATE_BINDINGS=1 cargo test bindings
+
+import { invoke } from "@tauri-apps/api/core";
+
+// auth
+export function getCode(): Promise<string> {
+  return invoke("get_code");
+}
+
+// versions
+export function getVersionManifest(): Promise<unknown> {
+  return invoke("get_version_manifest");
+}
+
+export function dwlVersionManifest(args: { url: string }): Promise<unknown> {
+  return invoke("dwl_version_manifest", args);
+}
+
+export function verifyVersion(args: { versionId: string }): Promise<VerifyReport> {
+  return invoke("verify_version", args);
+}
+
+export function listInstalledVersions(): Promise<InstalledVersion[]> {
+  return invoke("list_installed_versions");
+}
+
+export function getNetworkStatus(): Promise<NetworkStatus> {
+  return invoke("get_network_status");
+}
+
+// loaders
+export function getFabricGameVersions(): Promise<LoaderGameVersion[]> {
+  return invoke("get_fabric_game_versions");
+}
+
+export function getFabricLoaderVersions(args: { gameVersion: string }): Promise<LoaderVersion[]> {
+  return invoke("get_fabric_loader_versions", args);
+}
+
+export function installFabric(args: { gameVersion: string; loaderVersion?: string | null }): Promise<LoaderInstallResult> {
+  return invoke("install_fabric", args);
+}
+
+export function getQuiltGameVersions(): Promise<LoaderGameVersion[]> {
+  return invoke("get_quilt_game_versions");
+}
+
+export function getQuiltLoaderVersions(args: { gameVersion: string }): Promise<LoaderVersion[]> {
+  return invoke("get_quilt_loader_versions", args);
+}
+
+export function installQuilt(args: { gameVersion: string; loaderVersion?: string | null }): Promise<LoaderInstallResult> {
+  return invoke("install_quilt", args);
+}
+
+export function getForgeVersions(args: { kind: ForgeKind; gameVersion: string }): Promise<LoaderVersion[]> {
+  return invoke("get_forge_versions", args);
+}
+
+export function installForge(args: { kind: ForgeKind; loaderVersion: string }): Promise<LoaderInstallResult> {
+  return invoke("install_forge", args);
+}
+
+export function getOptifineVersions(args: { gameVersion: string }): Promise<OptiFineBuild[]> {
+  return invoke("get_optifine_versions", args);
+}
+
+export function installOptifine(args: { gameVersion: string; edition?: string | null; jarPath?: string | null }): Promise<LoaderInstallResult> {
+  return invoke("install_optifine", args);
+}
+
+export function installOptifineMod(args: { instanceId: string; edition?: string | null; jarPath?: string | null }): Promise<string> {
+  return invoke("install_optifine_mod", args);
+}
+
+// instances
+export function listInstances(): Promise<Instance[]> {
+  return invoke("list_instances");
+}
+
+export function getInstance(args: { id: string }): Promise<Instance> {
+  return invoke("get_instance", args);
+}
+
+export function createInstance(args: { name: string; versionId: string; icon?: string | null }): Promise<Instance> {
+  return invoke("create_instance", args);
+}
+
+export function updateInstance(args: { instance: Instance }): Promise<Instance> {
+  return invoke("update_instance", args);
+}
+
+export function deleteInstance(args: { id: string }): Promise<void> {
+  return invoke("delete_instance", args);
+}
+
+// game
+export function stg(args: { startupParameter: string; versionId: string; javaVersion: string; assetIndexId: string; username: string; instanceId?: string | null }): Promise<string> {
+  return invoke("stg", args);
+}
+
+export function listGameProcesses(): Promise<GameProcessInfo[]> {
+  return invoke("list_game_processes");
+}
+
+export function getGameProcess(args: { id: string }): Promise<GameProcessInfo> {
+  return invoke("get_game_process", args);
+}
+
+export function getGameLogs(args: { id: string }): Promise<GameLogRecord[]> {
+  return invoke("get_game_logs", args);
+}
+
+export function killGame(args: { id: string }): Promise<void> {
+  return invoke("kill_game", args);
+}
+
+export function clearFinishedGames(): Promise<void> {
+  return invoke("clear_finished_games");
+}
+
+export function getCrashReport(args: { id: string }): Promise<CrashReport | null> {
+  return invoke("get_crash_report", args);
+}
+
+export function exportCrashReport(args: { id: string; outputPath: string }): Promise<string> {
+  return invoke("export_crash_report", args);
+}
+
+export function exportBat(args: { startupParameter: string; versionId: string; javaVersion: string; outputPath: string; assetIndexId: string; username: string }): Promise<string> {
+  return invoke("export_bat", args);
+}
+
+export function exportScript(args: { startupParameter: string; versionId: string; javaVersion: string; outputPath: string; assetIndexId: string; username: string; format?: ScriptFormat | null; tokenMode?: TokenMode | null; instanceId?: string | null }): Promise<string> {
+  return invoke("export_script", args);
+}
+
+// java
+export function getJavaPath(): Promise<string[]> {
+  return invoke("get_java_path");
+}
+
+// content
+export function searchContent(args: { query: string; contentType: ContentType; source?: ContentSource | null; gameVersion?: string | null; loader?: string | null; page?: number | null; pageSize?: number | null }): Promise<ContentPage> {
+  return invoke("search_content", args);
+}
+
+export function searchInstanceContent(args: { instanceId: string; query: string; contentType: ContentType; source?: ContentSource | null; page?: number | null; pageSize?: number | null }): Promise<ContentPage> {
+  return invoke("search_instance_content", args);
+}
+
+export function getContentProject(args: { platform: Platform; projectId: string }): Promise<UnifiedProject> {
+  return invoke("get_content_project", args);
+}
+
+export function getContentVersions(args: { platform: Platform; projectId: string; gameVersion?: string | null }): Promise<UnifiedVersion[]> {
+  return invoke("get_content_versions", args);
+}
+
+export function getInstanceContentVersions(args: { instanceId: string; contentType: ContentType; platform: Platform; projectId: string }): Promise<UnifiedVersion[]> {
+  return invoke("get_instance_content_versions", args);
+}
+
+export function getContentFiles(args: { platform: Platform; projectId: string; versionId: string }): Promise<UnifiedFile[]> {
+  return invoke("get_content_files", args);
+}
+
+export function downloadContent(args: { instanceId: string; contentType: ContentType; file: UnifiedFile; world?: string | null }): Promise<string> {
+  return invoke("download_content", args);
+}
+
+export function planContentInstall(args: { instanceId: string; contentType: ContentType; platform: Platform; projectId: string; versionId?: string | null; includeOptional?: boolean | null }): Promise<InstallPlan> {
+  return invoke("plan_content_install", args);
+}
+
+export function installContent(args: { instanceId: string; plan: InstallPlan; world?: string | null }): Promise<string[]> {
+  return invoke("install_content", args);
+}
+
+export function scanInstanceMods(args: { instanceId: string }): Promise<ModScanReport> {
+  return invoke("scan_instance_mods", args);
+}
+
+export function getModIcon(args: { instanceId: string; fileName: string }): Promise<string | null> {
+  return invoke("get_mod_icon", args);
+}
+
+export function checkContentUpdates(args: { instanceId: string }): Promise<UpdateReport> {
+  return invoke("check_content_updates", args);
+}
+
+export function updateContent(args: { instanceId: string; updates: ContentUpdate[] }): Promise<string[]> {
+  return invoke("update_content", args);
+}
+
+export function updateAllContent(args: { instanceId: string }): Promise<string[]> {
+  return invoke("update_all_content", args);
+}
+
+export function listLocalContent(args: { instanceId: string; contentType: ContentType; world?: string | null }): Promise<LocalContent[]> {
+  return invoke("list_local_content", args);
+}
+
+export function setContentEnabled(args: { instanceId: string; contentType: ContentType; world?: string | null; fileName: string; enabled: boolean }): Promise<string> {
+  return invoke("set_content_enabled", args);
+}
+
+export function deleteContent(args: { instanceId: string; contentType: ContentType; world?: string | null; fileName: string }): Promise<void> {
+  return invoke("delete_content", args);
+}
+
+export function importContent(args: { instanceId: string; contentType: ContentType; world?: string | null; source: string }): Promise<string> {
+  return invoke("import_content", args);
+}
+
+export function listWorlds(args: { instanceId: string }): Promise<string[]> {
+  return invoke("list_worlds", args);
+}
+
+export function importMrpack(args: { path: string; name?: string | null; includeOptional?: boolean | null }): Promise<ModpackImport> {
+  return invoke("import_mrpack", args);
+}
+
+export function importCurseforgePack(args: { path: string; name?: string | null }): Promise<ModpackImport> {
+  return invoke("import_curseforge_pack", args);
+}
+
+export function importMultimcInstance(args: { path: string; name?: string | null }): Promise<Instance> {
+  return invoke("import_multimc_instance", args);
+}
+
+export function listExportEntries(args: { instanceId: string }): Promise<ExportEntry[]> {
+  return invoke("list_export_entries", args);
+}
+
+export function exportModpack(args: { instanceId: string; options: ExportOptions; output: string }): Promise<ModpackExport> {
+  return invoke("export_modpack", args);
+}
+
+// settings
+export function getSettings(): Promise<LauncherSettings> {
+  return invoke("get_settings");
+}
+
+export function setSettings(args: { settings: LauncherSettings }): Promise<LauncherSettings> {
+  return invoke("set_settings", args);
+}
+
+export function resetSettings(): Promise<LauncherSettings> {
+  return invoke("reset_settings");
+}
+
+export function getEffectiveSettings(args: { instanceId: string }): Promise<LauncherSettings> {
+  return invoke("get_effective_settings", args);
+}
+
+// logs
+export function getLauncherLogs(args: { limit?: number | null }): Promise<LogRecord[]> {
+  return invoke("get_launcher_logs", args);
+}
+
+export function getLogLevel(): Promise<string> {
+  return invoke("get_log_level");
+}
+
+export function setLogLevel(args: { level: string }): Promise<void> {
+  return invoke("set_log_level", args);
+}
+
+export function getLogDir(): Promise<string> {
+  return invoke("get_log_dir");
+}
+
+// 类型
+
+export interface ContentConflict {
+  project_id: string;
+  conflicts_with: string;
+  reason: string;
+}
+
+export interface ContentDependency {
+  kind: DependencyKind;
+  project_id: string | null;
+  version_id: string | null;
+}
+
+export interface ContentPage {
+  items: UnifiedProject[];
+  page: number;
+  page_size: number;
+  total_hits: number;
+}
+
+export interface ContentSettings {
+  curseforge_api_keys: string[];
+}
+
+export type ContentSource =
+  | "modrinth"
+  | "curse_forge"
+  | "both";
+
+export type ContentType =
+  | "mod"
+  | "resource_pack"
+  | "shader"
+  | "data_pack";
+
+export interface ContentUpdate {
+  file_name: string;
+  platform: Platform;
+  project_id: string;
+  current_version_id: string;
+  current_version: string;
+  latest: UnifiedVersion;
+  file: UnifiedFile;
+}
+
+export type CrashCause =
+  | "wrong_java_version"
+  | "missing_natives"
+  | "out_of_memory"
+  | "duplicate_mods"
+  | "mixin_failure"
+  | "missing_dependencies"
+  | "gpu_driver";
+
+export interface CrashFile {
+  kind: CrashFileKind;
+  path: string;
+}
+
+export type CrashFileKind =
+  | "crash_report"
+  | "jvm_error_log"
+  | "latest_log";
+
+export interface CrashFinding {
+  cause: CrashCause;
+  title: string;
+  suggestion: string;
+  evidence: string;
+}
+
+export interface CrashReport {
+  exit_code: number | null;
+  files: CrashFile[];
+  log_tail: string[];
+  findings: CrashFinding[];
+}
+
+export type DependencyKind =
+  | "required"
+  | "optional"
+  | "incompatible"
+  | "embedded";
+
+export interface DownloadSettings {
+  mirror: Mirror;
+  library_concurrency: number;
+  asset_concurrency: number;
+  max_retries: number;
+}
+
+export interface ExportEntry {
+  name: string;
+  is_dir: boolean;
+  selected: boolean;
+}
+
+export type ExportFormat =
+  | "mrpack"
+  | "curse_forge";
+
+export interface ExportOptions {
+  format: ExportFormat;
+  name: string;
+  version: string;
+  author: string | null;
+  summary: string | null;
+  include: string[];
+}
+
+export type ForgeKind =
+  | "forge"
+  | "neoforge";
+
+export interface GameLogRecord {
+  stream: LogStream;
+  timestamp: number | null;
+  level: string;
+  logger: string | null;
+  thread: string | null;
+  message: string;
+  throwable: string | null;
+}
+
+export interface GameProcessInfo {
+  id: string;
+  version_id: string;
+  pid: number | null;
+  started_at: number;
+  uptime_secs: number;
+  status: GameStatus;
+  exit_code: number | null;
+  crashed: boolean;
+}
+
+export interface GameSettings {
+  game_dir: string | null;
+}
+
+export type GameStatus =
+  | "running"
+  | "exited"
+  | "killed";
+
+export interface InstallPlan {
+  items: PlannedInstall[];
+  optional: OptionalDependency[];
+  conflicts: ContentConflict[];
+  unresolved: string[];
+}
+
+export interface InstalledVersion {
+  id: string;
+  type: string | null;
+  inherits_from: string | null;
+  release_time: string | null;
+  asset_index: string | null;
+  java_major: number | null;
+}
+
+export interface Instance {
+  id: string;
+  name: string;
+  icon: string | null;
+  version_id: string;
+  created_at: number;
+  last_played: number | null;
+  java_path: string | null;
+  java_version: string | null;
+  min_memory_mb: number | null;
+  max_memory_mb: number | null;
+  jvm_args: string[];
+}
+
+export interface JavaSettings {
+  java_path: string | null;
+  java_version: string | null;
+}
+
+export type LauncherErrorCode =
+  | "network"
+  | "hash_mismatch"
+  | "io"
+  | "auth"
+  | "java_not_found"
+  | "version_not_found"
+  | "instance_not_found"
+  | "process_not_found"
+  | "invalid_settings"
+  | "invalid_argument"
+  | "parse"
+  | "unsupported"
+  | "other";
+
+export interface LauncherError {
+  code: LauncherErrorCode;
+  message: string;
+  details: unknown;
+}
+
+export interface LauncherSettings {
+  schema_version: number;
+  language: string;
+  game: GameSettings;
+  java: JavaSettings;
+  memory: MemorySettings;
+  jvm_args: string[];
+  download: DownloadSettings;
+  content: ContentSettings;
+}
+
+export interface LoaderGameVersion {
+  version: string;
+  stable: boolean;
+}
+
+export interface LoaderInstallResult {
+  version_id: string;
+  inherits_from: string;
+  libraries: number;
+}
+
+export interface LoaderVersion {
+  version: string;
+  stable: boolean;
+  mappings: string | null;
+}
+
+export interface LocalContent {
+  file_name: string;
+  enabled: boolean;
+  directory: boolean;
+  size: number;
+  pack: PackMeta | null;
+}
+
+export interface LogRecord {
+  timestamp: number;
+  level: string;
+  target: string;
+  message: string;
+}
+
+export type LogStream =
+  | "stdout"
+  | "stderr";
+
+export interface ManualDownload {
+  project_id: string;
+  file_id: string;
+  name: string;
+  file_name: string;
+  website_url: string | null;
+  target: string;
+}
+
+export interface MemorySettings {
+  min_mb: number;
+  max_mb: number;
+}
+
+export type Mirror =
+  | "official"
+  | "bmclapi";
+
+export interface ModDependencyInfo {
+  id: string;
+  version: string | null;
+  required: boolean;
+}
+
+export interface ModIssue {
+  kind: ModIssueKind;
+  file_name: string;
+  message: string;
+}
+
+export type ModIssueKind =
+  | "duplicate"
+  | "missing_dependency"
+  | "loader_mismatch";
+
+export interface ModMetadata {
+  file_name: string;
+  enabled: boolean;
+  id: string;
+  name: string;
+  version: string | null;
+  authors: string[];
+  description: string | null;
+  icon: string | null;
+  loader: string;
+  dependencies: ModDependencyInfo[];
+  provides: string[];
+}
+
+export interface ModScanReport {
+  mods: ModMetadata[];
+  unreadable: string[];
+  issues: ModIssue[];
+}
+
+export interface ModpackExport {
+  path: string;
+  referenced: number;
+  bundled: string[];
+}
+
+export interface ModpackImport {
+  instance: Instance;
+  downloaded: number;
+  skipped: string[];
+  failed: string[];
+  manual: ManualDownload[];
+}
+
+export interface NetworkFeature {
+  id: string;
+  name: string;
+  available: boolean;
+  note: string;
+}
+
+export interface NetworkStatus {
+  online: boolean;
+  manifest_cached_at: number | null;
+  features: NetworkFeature[];
+}
+
+export interface OptiFineBuild {
+  mcversion: string;
+  type: string;
+  patch: string;
+  filename: string;
+  forge: string | null;
+}
+
+export interface OptionalDependency {
+  platform: Platform;
+  project_id: string;
+  required_by: string;
+}
+
+export interface PackMeta {
+  description: string;
+  pack_format: number | null;
+  min_format: number | null;
+  max_format: number | null;
+  icon: string | null;
+  expected_format: number | null;
+  compatible: boolean | null;
+}
+
+export interface PlannedInstall {
+  content_type: ContentType;
+  version: UnifiedVersion;
+  file: UnifiedFile;
+  required_by: string | null;
+  replaces: string | null;
+}
+
+export type Platform =
+  | "modrinth"
+  | "curse_forge";
+
+export type ScriptFormat =
+  | "bat"
+  | "ps1"
+  | "sh"
+  | "command";
+
+export type TokenMode =
+  | "plain"
+  | "placeholder"
+  | "prompt";
+
+export interface UnifiedFile {
+  platform: Platform;
+  file_id: string;
+  display_name: string;
+  file_name: string;
+  download_url: string;
+  size: number | null;
+  sha1: string | null;
+  primary: boolean;
+}
+
+export interface UnifiedProject {
+  platform: Platform;
+  project_id: string;
+  slug: string | null;
+  name: string;
+  summary: string;
+  icon_url: string | null;
+  downloads: number;
+}
+
+export interface UnifiedVersion {
+  platform: Platform;
+  project_id: string;
+  version_id: string;
+  name: string;
+  date_published: string | null;
+  game_versions: string[];
+  loaders: string[];
+  dependencies: ContentDependency[];
+  incompatible_reason: string | null;
+}
+
+export interface UpdateReport {
+  updates: ContentUpdate[];
+  up_to_date: string[];
+  unknown: string[];
+}
+
+export interface VerifyReport {
+  version_id: string;
+  checked: number;
+  missing: string[];
+  corrupted: string[];
+}